@@ -0,0 +1,202 @@
+use vector3::Vector3;
+use matrix4::Matrix4;
+use ray::Ray;
+
+/// An axis aligned bounding box defined by its minimum and maximum corners.
+#[derive(Debug, Copy, Clone)]
+pub struct Aabb {
+    pub min: Vector3,
+    pub max: Vector3,
+}
+
+impl Aabb {
+    /// Returns a new `Aabb`.
+    pub fn new(min: Vector3, max: Vector3) -> Aabb {
+        Aabb { min, max }
+    }
+
+    /// Returns an inverted `Aabb` that contains nothing and grows to fit anything it is expanded by.
+    pub fn empty() -> Aabb {
+        Aabb {
+            min: Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            max: Vector3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    /// Returns the smallest `Aabb` containing all of `points`.
+    /// Returns an empty `Aabb` if `points` is empty.
+    pub fn from_points(points: &[Vector3]) -> Aabb {
+        points.iter().fold(Aabb::empty(), |b, p| b.expand(*p))
+    }
+
+    /// Returns true if the box contains no points.
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    /// Returns the center of the box.
+    pub fn center(&self) -> Vector3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Returns the size of the box along each axis.
+    pub fn size(&self) -> Vector3 {
+        self.max - self.min
+    }
+
+    /// Returns half the size of the box along each axis.
+    pub fn half_extents(&self) -> Vector3 {
+        (self.max - self.min) * 0.5
+    }
+
+    /// Returns the surface area of the box.
+    pub fn surface_area(&self) -> f32 {
+        let s = self.size();
+        2.0 * (s.x * s.y + s.y * s.z + s.z * s.x)
+    }
+
+    /// Returns the box grown to contain `point`.
+    pub fn expand(&self, point: Vector3) -> Aabb {
+        Aabb { min: Vector3::min(self.min, point), max: Vector3::max(self.max, point) }
+    }
+
+    /// Returns the smallest box containing `b0` and `b1`.
+    pub fn union(b0: &Aabb, b1: &Aabb) -> Aabb {
+        Aabb { min: Vector3::min(b0.min, b1.min), max: Vector3::max(b0.max, b1.max) }
+    }
+
+    /// Returns the 8 corners of the box.
+    pub fn corners(&self) -> [Vector3; 8] {
+        let (a, b) = (self.min, self.max);
+        [Vector3::new(a.x, a.y, a.z), Vector3::new(b.x, a.y, a.z),
+         Vector3::new(b.x, b.y, a.z), Vector3::new(a.x, b.y, a.z),
+         Vector3::new(a.x, a.y, b.z), Vector3::new(b.x, a.y, b.z),
+         Vector3::new(b.x, b.y, b.z), Vector3::new(a.x, b.y, b.z)]
+    }
+
+    /// Returns the closest point inside the box to `point`.
+    pub fn closest_point(&self, point: Vector3) -> Vector3 {
+        Vector3::min(Vector3::max(point, self.min), self.max)
+    }
+
+    /// Returns true if `point` is inside the box.
+    pub fn contains_point(&self, point: Vector3) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x &&
+        point.y >= self.min.y && point.y <= self.max.y &&
+        point.z >= self.min.z && point.z <= self.max.z
+    }
+
+    /// Returns true if the box overlaps `other`.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x && self.max.x >= other.min.x &&
+        self.min.y <= other.max.y && self.max.y >= other.min.y &&
+        self.min.z <= other.max.z && self.max.z >= other.min.z
+    }
+
+    /// Returns the bounding box of this box after it is transformed by `m`.
+    pub fn transform(&self, m: Matrix4) -> Aabb {
+        let mut b = Aabb::new(Vector3::new(m[0][3], m[1][3], m[2][3]),
+                              Vector3::new(m[0][3], m[1][3], m[2][3]));
+        for i in 0..3 {
+            for j in 0..3 {
+                let e = m[i][j] * self.min[j];
+                let f = m[i][j] * self.max[j];
+                b.min[i] += e.min(f);
+                b.max[i] += e.max(f);
+            }
+        }
+
+        b
+    }
+
+    /// Returns the time along `ray` where it enters the box, or 0 if the ray starts inside.
+    /// Returns `None` if the ray misses the box.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<f32> {
+        self.intersect_ray_range(ray, 0.0, f32::INFINITY).map(|(t_min, _)| t_min)
+    }
+
+    /// Returns the times along `ray` where it enters and leaves the box clamped to `t_min` and `t_max`.
+    /// Returns `None` if the ray misses the box inside that range.
+    pub fn intersect_ray_range(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<(f32, f32)> {
+        let origin = ray.origin();
+        let direction = ray.direction();
+        let mut t_min = t_min;
+        let mut t_max = t_max;
+
+        for i in 0..3 {
+            if direction[i] == 0.0 {
+                if origin[i] < self.min[i] || origin[i] > self.max[i] {
+                    return None;
+                }
+                continue;
+            }
+
+            let inv = 1.0 / direction[i];
+            let mut t0 = (self.min[i] - origin[i]) * inv;
+            let mut t1 = (self.max[i] - origin[i]) * inv;
+            if inv < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+            if t_max < t_min {
+                return None;
+            }
+        }
+
+        Some((t_min, t_max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aabb::Aabb;
+    use vector3::Vector3;
+    use matrix4::Matrix4;
+    use ray::Ray;
+
+    #[test]
+    fn test_aabb_from_points() {
+        let b = Aabb::from_points(&[Vector3::new(1.0, -2.0, 3.0), Vector3::new(-1.0, 4.0, 0.0)]);
+        assert_eq!(b.min.x, -1.0);
+        assert_eq!(b.min.y, -2.0);
+        assert_eq!(b.min.z, 0.0);
+        assert_eq!(b.max.x, 1.0);
+        assert_eq!(b.max.y, 4.0);
+        assert_eq!(b.max.z, 3.0);
+
+        assert!(Aabb::from_points(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_aabb_intersects() {
+        let a = Aabb::new(Vector3::zero(), Vector3::new(1.0, 1.0, 1.0));
+        let b = Aabb::new(Vector3::new(0.5, 0.5, 0.5), Vector3::new(2.0, 2.0, 2.0));
+        let c = Aabb::new(Vector3::new(1.5, 0.0, 0.0), Vector3::new(2.0, 1.0, 1.0));
+
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&c));
+        assert!(a.contains_point(Vector3::new(0.5, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn test_aabb_transform() {
+        let a = Aabb::new(Vector3::zero(), Vector3::new(1.0, 1.0, 1.0));
+        let b = a.transform(Matrix4::translation(1.0, 0.0, 0.0) * Matrix4::scale(-2.0, 1.0, 1.0));
+
+        assert_eq!(b.min.x, -1.0);
+        assert_eq!(b.max.x, 1.0);
+        assert_eq!(b.max.y, 1.0);
+    }
+
+    #[test]
+    fn test_aabb_intersect_ray() {
+        let a = Aabb::new(Vector3::new(-1.0, -1.0, 4.0), Vector3::new(1.0, 1.0, 6.0));
+
+        assert_eq!(a.intersect_ray(&Ray::new(Vector3::zero(), Vector3::forward(), 0.0)), Some(4.0));
+        assert_eq!(a.intersect_ray(&Ray::new(Vector3::new(0.0, 0.0, 5.0), Vector3::forward(), 0.0)), Some(0.0));
+        assert!(a.intersect_ray(&Ray::new(Vector3::zero(), -Vector3::forward(), 0.0)).is_none());
+        assert!(a.intersect_ray(&Ray::new(Vector3::new(2.0, 0.0, 0.0), Vector3::forward(), 0.0)).is_none());
+    }
+}
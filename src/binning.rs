@@ -0,0 +1,223 @@
+use vector2::Vector2;
+use vector2i::Vector2i;
+use rect2i::Rect2i;
+use raster::{Coverage, RasterOptions, TriangleSetup};
+use std::cmp::min;
+
+/// A triangle assigned to a tile and how much of the tile it covers.
+#[derive(Debug, Copy, Clone)]
+pub struct BinnedTriangle {
+    pub triangle: usize,
+    /// Either `Coverage::Partial` or `Coverage::Full`.
+    pub coverage: Coverage,
+}
+
+/// Per-tile lists of the triangles overlapping a framebuffer split into square tiles.
+#[derive(Debug, Clone)]
+pub struct TileBins {
+    width: i32,
+    height: i32,
+    tile_size: i32,
+    tiles_x: i32,
+    tiles_y: i32,
+    bins: Vec<Vec<BinnedTriangle>>,
+}
+
+impl TileBins {
+    /// Returns empty bins for a `width` by `height` framebuffer split into `tile_size` square tiles.
+    /// Panics if `tile_size` isn't positive.
+    pub fn new(width: i32, height: i32, tile_size: i32) -> TileBins {
+        assert!(tile_size > 0, "tile size must be positive");
+        let tiles_x = (width.max(0) + tile_size - 1) / tile_size;
+        let tiles_y = (height.max(0) + tile_size - 1) / tile_size;
+
+        TileBins {
+            width,
+            height,
+            tile_size,
+            tiles_x,
+            tiles_y,
+            bins: vec![Vec::new(); (tiles_x * tiles_y) as usize],
+        }
+    }
+
+    /// Returns the size of a tile in pixels.
+    pub fn tile_size(&self) -> i32 {
+        self.tile_size
+    }
+
+    /// Returns the number of tile columns.
+    pub fn tiles_x(&self) -> i32 {
+        self.tiles_x
+    }
+
+    /// Returns the number of tile rows.
+    pub fn tiles_y(&self) -> i32 {
+        self.tiles_y
+    }
+
+    /// Returns the pixels of tile `tile`, cut to fit the framebuffer.
+    pub fn tile_rect(&self, tile: Vector2i) -> Rect2i {
+        let origin = tile * self.tile_size;
+        Rect2i::new(origin, Vector2i::new(min(origin.x + self.tile_size, self.width), min(origin.y + self.tile_size, self.height)))
+    }
+
+    /// Returns the triangles binned to tile `tile` in the order they were inserted.
+    /// Panics if `tile` is outside the `tiles_x` by `tiles_y` grid.
+    pub fn triangles(&self, tile: Vector2i) -> &[BinnedTriangle] {
+        assert!(tile.x >= 0 && tile.x < self.tiles_x && tile.y >= 0 && tile.y < self.tiles_y, "tile out of range");
+        &self.bins[(tile.y * self.tiles_x + tile.x) as usize]
+    }
+
+    /// Adds the triangle `setup` as number `triangle` to every tile where it may cover a pixel center.
+    /// Tiles are trivially rejected or accepted by testing each edge function at the tile's extreme pixel centers.
+    pub fn insert(&mut self, triangle: usize, setup: &TriangleSetup) {
+        let framebuffer = Rect2i::from_size(Vector2i::new(0, 0), self.width, self.height);
+        let bounds = match setup.bounds().and_then(|b| b.intersect(&framebuffer)) {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        let first = bounds.min / self.tile_size;
+        let last = (bounds.max - Vector2i::new(1, 1)) / self.tile_size;
+        for y in first.y..=last.y {
+            for x in first.x..=last.x {
+                let tile = Vector2i::new(x, y);
+                let coverage = setup.classify(&self.tile_rect(tile));
+                if coverage != Coverage::Outside {
+                    self.bins[(y * self.tiles_x + x) as usize].push(BinnedTriangle { triangle, coverage });
+                }
+            }
+        }
+    }
+}
+
+/// Returns `triangles` in screen space binned into `tile_size` square tiles of a `width` by `height` framebuffer.
+/// Degenerate and culled triangles are left out.
+pub fn bin_triangles(triangles: &[[Vector2; 3]], width: i32, height: i32, tile_size: i32, options: &RasterOptions) -> TileBins {
+    let mut bins = TileBins::new(width, height, tile_size);
+    for (i, t) in triangles.iter().enumerate() {
+        if let Some(setup) = TriangleSetup::new(t[0], t[1], t[2], options) {
+            bins.insert(i, &setup);
+        }
+    }
+    bins
+}
+
+/// Returns the pixel centers `setup` covers in the 8x8 block starting at `origin`, with bit `y * 8 + x` set for pixel `origin + (x, y)`.
+/// Blocks entirely outside or inside the triangle are resolved without testing each pixel.
+pub fn coverage_mask_8x8(setup: &TriangleSetup, origin: Vector2i) -> u64 {
+    match setup.classify(&Rect2i::from_size(origin, 8, 8)) {
+        Coverage::Outside => 0,
+        Coverage::Full => !0,
+        Coverage::Partial => {
+            let mut mask = 0;
+            for y in 0..8 {
+                for x in 0..8 {
+                    if setup.covers_pixel(origin + Vector2i::new(x, y)) {
+                        mask |= 1 << (y * 8 + x);
+                    }
+                }
+            }
+            mask
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use binning::{bin_triangles, coverage_mask_8x8};
+    use raster::{rasterize_triangle, Coverage, RasterOptions, TriangleSetup};
+    use rect2i::Rect2i;
+    use std::collections::HashSet;
+    use vector2::Vector2;
+    use vector2i::Vector2i;
+
+    fn triangles() -> Vec<[Vector2; 3]> {
+        vec![[Vector2::new(3.2, 2.7), Vector2::new(60.1, 9.4), Vector2::new(20.6, 47.3)],
+             [Vector2::new(-10.0, -10.0), Vector2::new(30.0, 70.0), Vector2::new(-40.0, 70.0)],
+             [Vector2::new(40.0, 40.0), Vector2::new(41.0, 40.0), Vector2::new(40.5, 40.0)],
+             [Vector2::new(100.0, 10.0), Vector2::new(120.0, 10.0), Vector2::new(110.0, 30.0)],
+             [Vector2::new(-5.0, -5.0), Vector2::new(200.0, -5.0), Vector2::new(-5.0, 200.0)]]
+    }
+
+    #[test]
+    fn test_binning_matches_rasterization() {
+        let options = RasterOptions::default();
+        let triangles = triangles();
+        let bins = bin_triangles(&triangles, 64, 48, 16, &options);
+        assert_eq!((bins.tiles_x(), bins.tiles_y()), (4, 3));
+
+        for (i, t) in triangles.iter().enumerate() {
+            let expected: HashSet<(i32, i32)> = rasterize_triangle(t[0], t[1], t[2], &options)
+                .map(|f| f.position)
+                .filter(|p| p.x >= 0 && p.x < 64 && p.y >= 0 && p.y < 48)
+                .map(|p| (p.x, p.y))
+                .collect();
+
+            let setup = TriangleSetup::new(t[0], t[1], t[2], &options);
+            let mut binned = HashSet::new();
+            for ty in 0..bins.tiles_y() {
+                for tx in 0..bins.tiles_x() {
+                    let tile = Vector2i::new(tx, ty);
+                    let rect = bins.tile_rect(tile);
+                    for b in bins.triangles(tile).iter().filter(|b| b.triangle == i) {
+                        let pixels: Vec<Vector2i> = rect.pixels().filter(|&p| setup.unwrap().covers_pixel(p)).collect();
+                        if b.coverage == Coverage::Full {
+                            assert_eq!(pixels.len() as i64, rect.area());
+                        }
+                        binned.extend(pixels.iter().map(|p| (p.x, p.y)));
+                    }
+                }
+            }
+            assert_eq!(binned, expected);
+        }
+
+        // The degenerate and offscreen triangles land in no tile, the screen covering one fills every tile.
+        for ty in 0..bins.tiles_y() {
+            for tx in 0..bins.tiles_x() {
+                let tile = bins.triangles(Vector2i::new(tx, ty));
+                assert!(tile.iter().all(|b| b.triangle != 2 && b.triangle != 3));
+                assert!(tile.iter().any(|b| b.triangle == 4 && b.coverage == Coverage::Full));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_binning_tile_out_of_range() {
+        let bins = bin_triangles(&triangles(), 64, 48, 16, &RasterOptions::default());
+        bins.triangles(Vector2i::new(-1, 1));
+    }
+
+    #[test]
+    fn test_binning_coverage_mask() {
+        let mut options = RasterOptions::default();
+        for t in triangles() {
+            let setup = match TriangleSetup::new(t[0], t[1], t[2], &options) {
+                Some(setup) => setup,
+                None => continue,
+            };
+            for origin in Rect2i::from_size(Vector2i::new(-8, -8), 10, 10).pixels() {
+                let origin = origin * 8;
+                let mask = coverage_mask_8x8(&setup, origin);
+                for (i, p) in Rect2i::from_size(origin, 8, 8).pixels().enumerate() {
+                    assert_eq!(mask & (1 << i) != 0, setup.covers_pixel(p));
+                }
+            }
+        }
+
+        // Fixed point vertices skip snapping.
+        let fixed = TriangleSetup::from_fixed(Vector2i::new(0, 0), Vector2i::new(16 << 8, 0), Vector2i::new(0, 16 << 8), &options).unwrap();
+        assert_eq!(coverage_mask_8x8(&fixed, Vector2i::new(0, 0)), !0);
+        assert_eq!(coverage_mask_8x8(&fixed, Vector2i::new(8, 8)), 0);
+        assert_eq!(coverage_mask_8x8(&fixed, Vector2i::new(8, 0)).count_ones(), 28);
+
+        // The scissor rectangle keeps a fully covered block from being accepted whole.
+        options.scissor = Some(Rect2i::from_size(Vector2i::new(0, 0), 4, 8));
+        let t = triangles()[4];
+        let setup = TriangleSetup::new(t[0], t[1], t[2], &options).unwrap();
+        assert_eq!(setup.classify(&Rect2i::from_size(Vector2i::new(0, 0), 8, 8)), Coverage::Partial);
+        assert_eq!(coverage_mask_8x8(&setup, Vector2i::new(0, 0)), 0x0f0f_0f0f_0f0f_0f0f);
+    }
+}
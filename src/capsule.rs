@@ -0,0 +1,220 @@
+use vector3::Vector3;
+use ray::Ray;
+use aabb::Aabb;
+use sphere::Sphere;
+use gjk::gjk_distance;
+use distance::{point_segment_distance, segment_segment_distance, PointSegmentDistance, SegmentSegmentDistance};
+
+/// A line segment from `a` to `b`.
+#[derive(Debug, Copy, Clone)]
+pub struct Segment3 {
+    pub a: Vector3,
+    pub b: Vector3,
+}
+
+impl Segment3 {
+    /// Returns a new `Segment3`.
+    pub fn new(a: Vector3, b: Vector3) -> Segment3 {
+        Segment3 { a, b }
+    }
+
+    /// Returns the vector from `a` to `b`.
+    pub fn direction(&self) -> Vector3 {
+        self.b - self.a
+    }
+
+    /// Returns the length of the segment.
+    pub fn length(&self) -> f32 {
+        self.direction().length()
+    }
+
+    /// Returns the point at `t` along the segment, from `a` at 0 to `b` at 1.
+    pub fn point_at(&self, t: f32) -> Vector3 {
+        self.a + self.direction() * t
+    }
+
+    /// Returns the closest point on the segment to `point`.
+    pub fn closest_point(&self, point: Vector3) -> PointSegmentDistance {
+        point_segment_distance(point, self.a, self.b)
+    }
+
+    /// Returns the closest points between the segment and `other`.
+    pub fn closest_points(&self, other: &Segment3) -> SegmentSegmentDistance {
+        segment_segment_distance(self.a, self.b, other.a, other.b)
+    }
+}
+
+/// A capsule made of all points within `radius` of the segment from `a` to `b`.
+#[derive(Debug, Copy, Clone)]
+pub struct Capsule {
+    pub a: Vector3,
+    pub b: Vector3,
+    pub radius: f32,
+}
+
+impl Capsule {
+    /// Returns a new `Capsule`.
+    pub fn new(a: Vector3, b: Vector3, radius: f32) -> Capsule {
+        Capsule { a, b, radius }
+    }
+
+    /// Returns a `Capsule` around `segment`.
+    pub fn from_segment(segment: Segment3, radius: f32) -> Capsule {
+        Capsule { a: segment.a, b: segment.b, radius }
+    }
+
+    /// Returns the segment at the core of the capsule.
+    pub fn segment(&self) -> Segment3 {
+        Segment3::new(self.a, self.b)
+    }
+
+    /// Returns the bounding box of the capsule.
+    pub fn bounding_box(&self) -> Aabb {
+        let r = Vector3::new(self.radius, self.radius, self.radius);
+        Aabb::new(Vector3::min(self.a, self.b) - r, Vector3::max(self.a, self.b) + r)
+    }
+
+    /// Returns the closest point inside the capsule to `point`.
+    pub fn closest_point(&self, point: Vector3) -> Vector3 {
+        let closest = self.segment().closest_point(point);
+        if closest.distance <= self.radius {
+            return point;
+        }
+
+        closest.point + (point - closest.point) * (self.radius / closest.distance)
+    }
+
+    /// Returns true if `point` is inside the capsule.
+    pub fn contains_point(&self, point: Vector3) -> bool {
+        self.segment().closest_point(point).distance <= self.radius
+    }
+
+    /// Returns true if the capsule overlaps `other`.
+    pub fn intersects_capsule(&self, other: &Capsule) -> bool {
+        self.segment().closest_points(&other.segment()).distance <= self.radius + other.radius
+    }
+
+    /// Returns true if the capsule overlaps `sphere`.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.segment().closest_point(sphere.center).distance <= self.radius + sphere.radius
+    }
+
+    /// Returns true if the capsule overlaps `aabb`.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        match gjk_distance(&self.segment(), aabb) {
+            Some(d) => d.distance <= self.radius,
+            None => true,
+        }
+    }
+
+    /// Returns the time along `ray` where it enters the capsule, or 0 if the ray starts inside.
+    /// Returns `None` if the ray misses the capsule.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<f32> {
+        let d = self.b - self.a;
+        let m = ray.origin() - self.a;
+        let n = ray.direction();
+        let dd = d.length_squared();
+        let nd = Vector3::dot(n, d);
+        let md = Vector3::dot(m, d);
+
+        let mut best: Option<f32> = None;
+        let mut consider = |t: Option<f32>| {
+            if let Some(t) = t {
+                best = Some(best.map_or(t, |b| b.min(t)));
+            }
+        };
+
+        consider(Sphere::new(self.a, self.radius).intersect_ray(ray));
+        consider(Sphere::new(self.b, self.radius).intersect_ray(ray));
+
+        if dd > 0.0 {
+            // Intersect the infinite cylinder around the segment and keep hits between the end caps.
+            let a = dd * n.length_squared() - nd * nd;
+            let b = dd * Vector3::dot(m, n) - nd * md;
+            let c = dd * (m.length_squared() - self.radius * self.radius) - md * md;
+
+            if c <= 0.0 && md >= 0.0 && md <= dd {
+                return Some(0.0);
+            }
+
+            let discriminant = b * b - a * c;
+            if a > 0.0 && discriminant >= 0.0 {
+                let t = (-b - discriminant.sqrt()) / a;
+                let s = md + t * nd;
+                if t >= 0.0 && s >= 0.0 && s <= dd {
+                    consider(Some(t));
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Returns the first time in `[0, 1]` a sphere at `center` with `radius` moving by `velocity` touches the capsule.
+    /// Returns 0 if the sphere starts overlapping the capsule and `None` if it never touches it.
+    pub fn sweep_sphere(&self, center: Vector3, radius: f32, velocity: Vector3) -> Option<f32> {
+        let inflated = Capsule::new(self.a, self.b, self.radius + radius);
+        inflated.intersect_ray(&Ray::new(center, velocity, 0.0)).filter(|t| *t <= 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use capsule::{Capsule, Segment3};
+    use vector3::Vector3;
+    use ray::Ray;
+    use aabb::Aabb;
+    use sphere::Sphere;
+
+    fn capsule() -> Capsule {
+        Capsule::new(Vector3::new(0.0, -1.0, 0.0), Vector3::new(0.0, 1.0, 0.0), 0.5)
+    }
+
+    #[test]
+    fn test_segment_closest_point() {
+        let s = Segment3::new(Vector3::zero(), Vector3::new(4.0, 0.0, 0.0));
+        let c = s.closest_point(Vector3::new(1.0, 2.0, 0.0));
+
+        assert_eq!(c.t, 0.25);
+        assert_eq!(c.distance, 2.0);
+        assert_eq!(s.length(), 4.0);
+    }
+
+    #[test]
+    fn test_capsule_intersect_ray() {
+        let c = capsule();
+
+        let t = c.intersect_ray(&Ray::new(Vector3::new(-5.0, 0.5, 0.0), Vector3::left(), 0.0)).unwrap();
+        assert!((t - 4.5).abs() < 0.0001);
+
+        let t = c.intersect_ray(&Ray::new(Vector3::new(0.0, 5.0, 0.0), -Vector3::up(), 0.0)).unwrap();
+        assert!((t - 3.5).abs() < 0.0001);
+
+        assert_eq!(c.intersect_ray(&Ray::new(Vector3::zero(), Vector3::left(), 0.0)), Some(0.0));
+        assert!(c.intersect_ray(&Ray::new(Vector3::new(-5.0, 1.6, 0.0), Vector3::left(), 0.0)).is_none());
+        assert!(c.intersect_ray(&Ray::new(Vector3::new(-5.0, 1.4, 0.0), Vector3::left(), 0.0)).is_some());
+    }
+
+    #[test]
+    fn test_capsule_overlaps() {
+        let c = capsule();
+
+        assert!(c.intersects_capsule(&Capsule::new(Vector3::new(0.9, 0.0, -1.0), Vector3::new(0.9, 0.0, 1.0), 0.5)));
+        assert!(!c.intersects_capsule(&Capsule::new(Vector3::new(1.1, 0.0, -1.0), Vector3::new(1.1, 0.0, 1.0), 0.5)));
+        assert!(c.intersects_sphere(&Sphere::new(Vector3::new(0.0, 2.0, 0.0), 0.6)));
+        assert!(!c.intersects_sphere(&Sphere::new(Vector3::new(0.0, 2.0, 0.0), 0.4)));
+        assert!(c.intersects_aabb(&Aabb::new(Vector3::new(0.4, 0.0, 0.0), Vector3::new(1.0, 1.0, 1.0))));
+        assert!(!c.intersects_aabb(&Aabb::new(Vector3::new(0.6, 0.0, 0.0), Vector3::new(1.0, 1.0, 1.0))));
+        assert!(c.contains_point(Vector3::new(0.0, 1.4, 0.0)));
+    }
+
+    #[test]
+    fn test_capsule_sweep_sphere() {
+        let c = capsule();
+
+        let t = c.sweep_sphere(Vector3::new(-4.0, 0.0, 0.0), 0.5, Vector3::new(8.0, 0.0, 0.0)).unwrap();
+        assert!((t - 0.375).abs() < 0.0001);
+        assert!(c.sweep_sphere(Vector3::new(-4.0, 0.0, 0.0), 0.5, Vector3::new(2.0, 0.0, 0.0)).is_none());
+        assert!(c.sweep_sphere(Vector3::new(-4.0, 3.0, 0.0), 0.5, Vector3::new(8.0, 0.0, 0.0)).is_none());
+    }
+}
@@ -0,0 +1,287 @@
+use vector2i::Vector2i;
+use vector4::Vector4;
+use frustum::ClipDepth;
+use std::ops::{Add, Mul};
+
+/// A polygon vertex in homogeneous clip space with the attributes interpolated across it.
+#[derive(Debug, Copy, Clone)]
+pub struct ClipVertex<T> {
+    pub position: Vector4,
+    pub attributes: T,
+}
+
+impl<T> ClipVertex<T> where T: Copy + Add<Output = T> + Mul<f32, Output = T> {
+    /// Returns a new `ClipVertex`.
+    pub fn new(position: Vector4, attributes: T) -> ClipVertex<T> {
+        ClipVertex { position, attributes }
+    }
+
+    /// Returns the vertex a fraction `t` of the way from `v0` to `v1`.
+    pub fn lerp(v0: &ClipVertex<T>, v1: &ClipVertex<T>, t: f32) -> ClipVertex<T> {
+        ClipVertex {
+            position: v0.position * (1.0 - t) + v1.position * t,
+            attributes: v0.attributes * (1.0 - t) + v1.attributes * t,
+        }
+    }
+}
+
+/// Returns the six view volume planes in clip space ordered left, right, bottom, top, near and far.
+/// A position `p` is inside a plane `q` when `Vector4::dot(q, p) >= 0`.
+pub fn clip_planes(depth: ClipDepth) -> [Vector4; 6] {
+    let near = match depth {
+        ClipDepth::ZeroToOne => Vector4::new(0.0, 0.0, 1.0, 0.0),
+        ClipDepth::NegativeOneToOne => Vector4::new(0.0, 0.0, 1.0, 1.0),
+    };
+
+    [Vector4::new(1.0, 0.0, 0.0, 1.0),
+     Vector4::new(-1.0, 0.0, 0.0, 1.0),
+     Vector4::new(0.0, 1.0, 0.0, 1.0),
+     Vector4::new(0.0, -1.0, 0.0, 1.0),
+     near,
+     Vector4::new(0.0, 0.0, -1.0, 1.0)]
+}
+
+/// Returns the part of `polygon` on the inside of the clip space `plane`.
+pub fn clip_polygon_plane<T>(polygon: &[ClipVertex<T>], plane: Vector4) -> Vec<ClipVertex<T>> where T: Copy + Add<Output = T> + Mul<f32, Output = T> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    let mut prev = match polygon.last() {
+        Some(v) => v,
+        None => return clipped,
+    };
+    let mut prev_distance = Vector4::dot(plane, prev.position);
+
+    for vertex in polygon {
+        let distance = Vector4::dot(plane, vertex.position);
+        if (distance >= 0.0) != (prev_distance >= 0.0) {
+            let t = prev_distance / (prev_distance - distance);
+            clipped.push(ClipVertex::lerp(prev, vertex, t));
+        }
+        if distance >= 0.0 {
+            clipped.push(*vertex);
+        }
+        prev = vertex;
+        prev_distance = distance;
+    }
+
+    clipped
+}
+
+/// Returns `polygon` clipped against the view volume and any `user_planes` using Sutherland-Hodgman clipping.
+/// `depth` is the clip space depth range of the projection. Returns an empty polygon if nothing is visible.
+pub fn clip_polygon<T>(polygon: &[ClipVertex<T>], depth: ClipDepth, user_planes: &[Vector4]) -> Vec<ClipVertex<T>> where T: Copy + Add<Output = T> + Mul<f32, Output = T> {
+    let mut clipped = polygon.to_vec();
+    for &plane in clip_planes(depth).iter().chain(user_planes) {
+        if clipped.len() < 3 {
+            return Vec::new();
+        }
+        clipped = clip_polygon_plane(&clipped, plane);
+    }
+
+    if clipped.len() < 3 {
+        clipped.clear();
+    }
+    clipped
+}
+
+/// Returns the triangle `v0`, `v1`, `v2` clipped against the view volume and any `user_planes`, as a fan of triangles.
+pub fn clip_triangle<T>(v0: ClipVertex<T>, v1: ClipVertex<T>, v2: ClipVertex<T>, depth: ClipDepth, user_planes: &[Vector4]) -> Vec<[ClipVertex<T>; 3]> where T: Copy + Add<Output = T> + Mul<f32, Output = T> {
+    let polygon = clip_polygon(&[v0, v1, v2], depth, user_planes);
+    (2..polygon.len()).map(|i| [polygon[0], polygon[i - 1], polygon[i]]).collect()
+}
+
+const INSIDE: u8 = 0;
+const LEFT: u8 = 1;
+const RIGHT: u8 = 2;
+const BOTTOM: u8 = 4;
+const TOP: u8 = 8;
+
+fn outcode(p: (f64, f64), min: Vector2i, max: Vector2i) -> u8 {
+    let mut code = INSIDE;
+    if p.0 < min.x as f64 {
+        code |= LEFT;
+    } else if p.0 > max.x as f64 {
+        code |= RIGHT;
+    }
+    if p.1 < min.y as f64 {
+        code |= TOP;
+    } else if p.1 > max.y as f64 {
+        code |= BOTTOM;
+    }
+    code
+}
+
+/// Returns the line from `p0` to `p1` clipped to the rectangle of pixels from `min` to `max` inclusive
+/// using Cohen-Sutherland clipping.
+/// Returns `None` if the line is outside the rectangle.
+pub fn clip_line_cohen_sutherland(p0: Vector2i, p1: Vector2i, min: Vector2i, max: Vector2i) -> Option<(Vector2i, Vector2i)> {
+    // Intersections are kept unrounded so a clipped endpoint can't be snapped back inside the rectangle.
+    let mut a = (p0.x as f64, p0.y as f64);
+    let mut b = (p1.x as f64, p1.y as f64);
+    let mut code0 = outcode(a, min, max);
+    let mut code1 = outcode(b, min, max);
+    let (x0, x1) = (min.x as f64, max.x as f64);
+    let (y0, y1) = (min.y as f64, max.y as f64);
+
+    loop {
+        if code0 | code1 == INSIDE {
+            let round = |p: (f64, f64)| Vector2i::new((p.0.round() as i32).clamp(min.x, max.x), (p.1.round() as i32).clamp(min.y, max.y));
+            return Some((round(a), round(b)));
+        }
+        if code0 & code1 != INSIDE {
+            return None;
+        }
+
+        let code = if code0 != INSIDE { code0 } else { code1 };
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let p = if code & TOP != 0 {
+            (a.0 + dx * (y0 - a.1) / dy, y0)
+        } else if code & BOTTOM != 0 {
+            (a.0 + dx * (y1 - a.1) / dy, y1)
+        } else if code & LEFT != 0 {
+            (x0, a.1 + dy * (x0 - a.0) / dx)
+        } else {
+            (x1, a.1 + dy * (x1 - a.0) / dx)
+        };
+
+        if code == code0 {
+            a = p;
+            code0 = outcode(a, min, max);
+        } else {
+            b = p;
+            code1 = outcode(b, min, max);
+        }
+    }
+}
+
+/// Returns the line from `p0` to `p1` clipped to the rectangle of pixels from `min` to `max` inclusive
+/// using Liang-Barsky clipping.
+/// Returns `None` if the line is outside the rectangle.
+pub fn clip_line_liang_barsky(p0: Vector2i, p1: Vector2i, min: Vector2i, max: Vector2i) -> Option<(Vector2i, Vector2i)> {
+    let dx = (p1.x - p0.x) as f64;
+    let dy = (p1.y - p0.y) as f64;
+    let checks = [(-dx, (p0.x - min.x) as f64),
+                  (dx, (max.x - p0.x) as f64),
+                  (-dy, (p0.y - min.y) as f64),
+                  (dy, (max.y - p0.y) as f64)];
+
+    let mut t0 = 0.0f64;
+    let mut t1 = 1.0f64;
+    for &(p, q) in checks.iter() {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    let point = |t: f64| {
+        let x = (p0.x as f64 + dx * t).round() as i32;
+        let y = (p0.y as f64 + dy * t).round() as i32;
+        Vector2i::new(x.clamp(min.x, max.x), y.clamp(min.y, max.y))
+    };
+    Some((point(t0), point(t1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use clip::{clip_line_cohen_sutherland, clip_line_liang_barsky, clip_polygon, clip_triangle, ClipVertex};
+    use frustum::ClipDepth;
+    use matrix4::Matrix4;
+    use vector2i::Vector2i;
+    use vector3::Vector3;
+    use vector4::Vector4;
+
+    #[test]
+    fn test_clip_triangle_near_plane() {
+        let view_proj = Matrix4::perpective(90.0, 1.0, 0.1, 100.0);
+        let vertex = |p: Vector3| ClipVertex::new(view_proj * p.to_vector4(1.0), p);
+        let a = vertex(Vector3::new(-1.0, 0.0, -5.0));
+        let b = vertex(Vector3::new(1.0, 0.0, -5.0));
+
+        // Only the near plane cuts a vertex between the eye and the near plane, leaving a quad.
+        let triangles = clip_triangle(a, b, vertex(Vector3::new(0.0, 0.0, -0.05)), ClipDepth::ZeroToOne, &[]);
+        assert_eq!(triangles.len(), 2);
+
+        // A vertex behind the eye is clipped away as well.
+        let mut triangles = clip_triangle(a, b, vertex(Vector3::new(0.0, 0.0, 5.0)), ClipDepth::ZeroToOne, &[]);
+        assert!(!triangles.is_empty());
+        triangles.extend(clip_triangle(a, b, vertex(Vector3::new(0.0, 0.0, -0.05)), ClipDepth::ZeroToOne, &[]));
+        for v in triangles.iter().flat_map(|t| t.iter()) {
+            assert!(v.position.w > 0.0);
+            assert!(v.position.z >= -1e-5);
+            // Attributes stay consistent with the clipped positions.
+            let p = view_proj * v.attributes.to_vector4(1.0);
+            assert!((p.x - v.position.x).abs() < 1e-4 && (p.z - v.position.z).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_clip_polygon_inside_and_outside() {
+        let square = |offset: f32| -> Vec<ClipVertex<f32>> {
+            [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)].iter()
+                .map(|&(x, y)| ClipVertex::new(Vector4::new(x + offset, y, 0.5, 1.0), x))
+                .collect()
+        };
+
+        let inside = clip_polygon(&square(0.0), ClipDepth::NegativeOneToOne, &[]);
+        assert_eq!(inside.len(), 4);
+        assert!(clip_polygon(&square(3.0), ClipDepth::NegativeOneToOne, &[]).is_empty());
+
+        let straddling = clip_polygon(&square(1.0), ClipDepth::NegativeOneToOne, &[]);
+        assert_eq!(straddling.len(), 4);
+        assert!(straddling.iter().all(|v| v.position.x <= 1.0 + 1e-6));
+        assert!(straddling.iter().any(|v| (v.attributes - 0.0).abs() < 1e-6 && (v.position.x - 1.0).abs() < 1e-6));
+
+        // A user plane keeping x <= 0 cuts the square in half.
+        let user = [Vector4::new(-1.0, 0.0, 0.0, 0.0)];
+        let half = clip_polygon(&square(0.0), ClipDepth::NegativeOneToOne, &user);
+        assert_eq!(half.len(), 4);
+        assert!(half.iter().all(|v| v.position.x <= 1e-6));
+    }
+
+    #[test]
+    fn test_clip_line() {
+        let min = Vector2i::new(0, 0);
+        let max = Vector2i::new(99, 49);
+        let clippers = [clip_line_cohen_sutherland, clip_line_liang_barsky];
+
+        for clip in clippers.iter() {
+            let (a, b) = clip(Vector2i::new(10, 10), Vector2i::new(20, 30), min, max).unwrap();
+            assert_eq!((a.x, a.y, b.x, b.y), (10, 10, 20, 30));
+
+            let (a, b) = clip(Vector2i::new(-50, 25), Vector2i::new(150, 25), min, max).unwrap();
+            assert_eq!((a.x, a.y, b.x, b.y), (0, 25, 99, 25));
+
+            let (a, b) = clip(Vector2i::new(-10, -10), Vector2i::new(60, 60), min, max).unwrap();
+            assert_eq!((a.x, a.y, b.x, b.y), (0, 0, 49, 49));
+
+            assert!(clip(Vector2i::new(-10, 60), Vector2i::new(200, 60), min, max).is_none());
+            assert!(clip(Vector2i::new(-10, 5), Vector2i::new(5, -10), min, max).is_none());
+        }
+
+        // Both clippers agree on a spread of lines.
+        for i in 0..200 {
+            let p0 = Vector2i::new((i * 37) % 300 - 100, (i * 53) % 200 - 75);
+            let p1 = Vector2i::new((i * 71) % 300 - 100, (i * 29) % 200 - 75);
+            let cs = clip_line_cohen_sutherland(p0, p1, min, max);
+            let lb = clip_line_liang_barsky(p0, p1, min, max);
+            assert_eq!(cs.is_some(), lb.is_some(), "{:?} {:?}", p0, p1);
+            if let (Some(cs), Some(lb)) = (cs, lb) {
+                for &(a, b) in [(cs.0, lb.0), (cs.1, lb.1)].iter() {
+                    assert!((a.x - b.x).abs() <= 1 && (a.y - b.y).abs() <= 1, "{:?} {:?}", a, b);
+                }
+            }
+        }
+    }
+}
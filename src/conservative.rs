@@ -0,0 +1,245 @@
+use vector2::Vector2;
+use vector2i::Vector2i;
+use rect2i::{Rect2i, Rect2iPixels};
+use raster::{snap_to_fixed, RasterOptions, TriangleSetup};
+
+/// Which pixels conservative rasterization reports.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConservativeMode {
+    /// Every pixel the triangle overlaps.
+    Overestimate,
+    /// Only pixels entirely inside the triangle.
+    Underestimate,
+}
+
+/// A zero area triangle snapped to fixed point, covering a segment or a single point.
+/// Having no inside, it touches every pixel whose closed square meets the segment or point, border included.
+#[derive(Debug, Copy, Clone)]
+struct Degenerate {
+    p0: (i64, i64),
+    p1: (i64, i64),
+    min: (i64, i64),
+    max: (i64, i64),
+    scale: i64,
+}
+
+impl Degenerate {
+    fn new(p: [(i64, i64); 3], subpixel_bits: u32) -> Degenerate {
+        // The two points furthest apart span the others when all three are collinear.
+        let length = |a: (i64, i64), b: (i64, i64)| (b.0 - a.0).abs() + (b.1 - a.1).abs();
+        let (i, j) = [(0, 1), (1, 2), (2, 0)].iter()
+            .cloned()
+            .max_by_key(|&(i, j)| length(p[i], p[j]))
+            .unwrap();
+
+        Degenerate {
+            p0: p[i],
+            p1: p[j],
+            min: (p[0].0.min(p[1].0).min(p[2].0), p[0].1.min(p[1].1).min(p[2].1)),
+            max: (p[0].0.max(p[1].0).max(p[2].0), p[0].1.max(p[1].1).max(p[2].1)),
+            scale: 1 << subpixel_bits,
+        }
+    }
+
+    fn bounds(&self) -> Rect2i {
+        let cell = |a: i64| a.div_euclid(self.scale) as i32;
+        Rect2i::new(Vector2i::new(cell(self.min.0 - 1), cell(self.min.1 - 1)),
+                    Vector2i::new(cell(self.max.0) + 1, cell(self.max.1) + 1))
+    }
+
+    fn touches_pixel(&self, pixel: Vector2i) -> bool {
+        let s = self.scale;
+        let (x0, y0) = (pixel.x as i64 * s, pixel.y as i64 * s);
+        if x0 > self.max.0 || x0 + s < self.min.0 || y0 > self.max.1 || y0 + s < self.min.1 {
+            return false;
+        }
+
+        // The segment's line must meet the pixel, which always holds for a point.
+        let (a, b) = (-(self.p1.1 - self.p0.1), self.p1.0 - self.p0.0);
+        let twice = 2 * (a * (x0 - self.p0.0) + b * (y0 - self.p0.1)) + (a + b) * s;
+        twice.abs() <= (a.abs() + b.abs()) * s
+    }
+}
+
+/// An iterator over the pixels reported by conservative rasterization of a triangle, in row-major order.
+#[derive(Debug, Clone)]
+pub struct ConservativeRaster {
+    mode: ConservativeMode,
+    setup: Option<TriangleSetup>,
+    degenerate: Option<Degenerate>,
+    scissor: Option<Rect2i>,
+    pixels: Rect2iPixels,
+}
+
+impl Iterator for ConservativeRaster {
+    type Item = Vector2i;
+
+    fn next(&mut self) -> Option<Vector2i> {
+        for pixel in self.pixels.by_ref() {
+            let covered = match (self.setup, self.degenerate) {
+                (Some(setup), _) => match self.mode {
+                    ConservativeMode::Overestimate => setup.overlaps_pixel(pixel),
+                    ConservativeMode::Underestimate => setup.contains_pixel(pixel),
+                },
+                (None, Some(degenerate)) => {
+                    self.mode == ConservativeMode::Overestimate &&
+                        self.scissor.map_or(true, |scissor| scissor.contains(pixel)) &&
+                        degenerate.touches_pixel(pixel)
+                }
+                (None, None) => false,
+            };
+            if covered {
+                return Some(pixel);
+            }
+        }
+        None
+    }
+}
+
+/// Returns an iterator over the pixels of the screen space triangle `v0`, `v1`, `v2` under conservative rasterization.
+/// Overestimation reports every pixel the triangle passes through, including thin slivers that miss every pixel center.
+/// Zero area triangles are treated as segments or points, are never culled, and report every pixel they touch,
+/// including pixels they only meet along a border or at a corner.
+/// Underestimation reports only pixels the triangle covers entirely, so zero area triangles report none.
+pub fn rasterize_triangle_conservative(v0: Vector2, v1: Vector2, v2: Vector2, mode: ConservativeMode, options: &RasterOptions) -> ConservativeRaster {
+    let bits = options.subpixel_bits;
+    let area = |p: [(i64, i64); 3]| (p[1].0 - p[0].0) * (p[2].1 - p[0].1) - (p[1].1 - p[0].1) * (p[2].0 - p[0].0);
+    let snapped = (snap_to_fixed(v0, bits), snap_to_fixed(v1, bits), snap_to_fixed(v2, bits));
+
+    // Triangles with a vertex outside the fixed point range fall through to the setup, which rejects them.
+    let (setup, degenerate, bounds) = match snapped {
+        (Some(p0), Some(p1), Some(p2)) if area([p0, p1, p2]) == 0 => {
+            let degenerate = Degenerate::new([p0, p1, p2], bits);
+            let bounds = match options.scissor {
+                Some(scissor) => degenerate.bounds().intersect(&scissor),
+                None => Some(degenerate.bounds()),
+            };
+            (None, Some(degenerate), bounds)
+        }
+        _ => {
+            let setup = TriangleSetup::new(v0, v1, v2, options);
+            (setup, None, setup.and_then(|s| s.touched_bounds()))
+        }
+    };
+
+    ConservativeRaster {
+        mode,
+        setup,
+        degenerate,
+        scissor: options.scissor,
+        pixels: bounds.unwrap_or_else(|| Rect2i::new(Vector2i::new(0, 0), Vector2i::new(0, 0))).pixels(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use conservative::{rasterize_triangle_conservative, ConservativeMode};
+    use raster::{rasterize_triangle, CullMode, RasterOptions};
+    use std::collections::HashSet;
+    use vector2::Vector2;
+
+    fn pixels(v: [Vector2; 3], mode: ConservativeMode, options: &RasterOptions) -> HashSet<(i32, i32)> {
+        rasterize_triangle_conservative(v[0], v[1], v[2], mode, options).map(|p| (p.x, p.y)).collect()
+    }
+
+    /// Returns the points of a dense grid inside `pixel` that lie inside the triangle `v`.
+    fn inside_samples(v: [Vector2; 3], pixel: (i32, i32)) -> usize {
+        let mut count = 0;
+        for i in 0..16 {
+            for j in 0..16 {
+                let p = Vector2::new(pixel.0 as f32 + (i as f32 + 0.5) / 16.0, pixel.1 as f32 + (j as f32 + 0.5) / 16.0);
+                let e = [Vector2::cross(v[1] - v[0], p - v[0]), Vector2::cross(v[2] - v[1], p - v[1]), Vector2::cross(v[0] - v[2], p - v[2])];
+                if e.iter().all(|&e| e >= 0.0) || e.iter().all(|&e| e <= 0.0) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_conservative_bounds_standard_coverage() {
+        let options = RasterOptions::default();
+        let triangles = [[Vector2::new(1.3, 2.2), Vector2::new(14.7, 4.1), Vector2::new(6.2, 13.8)],
+                         [Vector2::new(3.0, 3.0), Vector2::new(3.5, 12.25), Vector2::new(11.0, 7.0)],
+                         [Vector2::new(0.1, 0.2), Vector2::new(15.9, 1.0), Vector2::new(0.3, 0.9)]];
+
+        for &t in triangles.iter() {
+            let over = pixels(t, ConservativeMode::Overestimate, &options);
+            let under = pixels(t, ConservativeMode::Underestimate, &options);
+            let standard: HashSet<(i32, i32)> = rasterize_triangle(t[0], t[1], t[2], &options).map(|f| (f.position.x, f.position.y)).collect();
+
+            assert!(under.is_subset(&standard));
+            assert!(standard.is_subset(&over));
+            for y in -1..17 {
+                for x in -1..17 {
+                    let inside = inside_samples(t, (x, y));
+                    if inside > 0 {
+                        assert!(over.contains(&(x, y)), "{:?} missed", (x, y));
+                    }
+                    if under.contains(&(x, y)) {
+                        assert_eq!(inside, 256);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_conservative_aligned_square() {
+        let options = RasterOptions::default();
+        let a = Vector2::new(0.0, 0.0);
+        let b = Vector2::new(4.0, 0.0);
+        let c = Vector2::new(4.0, 4.0);
+        let d = Vector2::new(0.0, 4.0);
+
+        let mut over = pixels([a, b, c], ConservativeMode::Overestimate, &options);
+        over.extend(pixels([a, c, d], ConservativeMode::Overestimate, &options));
+        assert_eq!(over.len(), 16);
+
+        // Pixels on the diagonal belong entirely to neither half.
+        let mut under = pixels([a, b, c], ConservativeMode::Underestimate, &options);
+        under.extend(pixels([a, c, d], ConservativeMode::Underestimate, &options));
+        assert_eq!(under.len(), 12);
+        assert!((0..4).all(|i| !under.contains(&(i, i))));
+    }
+
+    #[test]
+    fn test_conservative_thin_and_degenerate() {
+        let options = RasterOptions::default();
+        // A sliver between pixel centers still touches a connected run of pixels.
+        let sliver = [Vector2::new(0.6, 2.1), Vector2::new(9.4, 2.1), Vector2::new(9.4, 2.15)];
+        assert_eq!(rasterize_triangle(sliver[0], sliver[1], sliver[2], &options).count(), 0);
+        let over = pixels(sliver, ConservativeMode::Overestimate, &options);
+        assert_eq!(over, (0..10).map(|x| (x, 2)).collect());
+        assert!(pixels(sliver, ConservativeMode::Underestimate, &options).is_empty());
+
+        // A zero area triangle covers the pixels its segment crosses, including both pixels at a crossed corner.
+        let line = [Vector2::new(0.5, 0.5), Vector2::new(2.5, 1.5), Vector2::new(4.5, 2.5)];
+        let over = pixels(line, ConservativeMode::Overestimate, &options);
+        assert_eq!(over, [(0, 0), (1, 0), (1, 1), (2, 1), (3, 1), (3, 2), (4, 2)].iter().cloned().collect());
+        assert!(pixels(line, ConservativeMode::Underestimate, &options).is_empty());
+
+        let point = [Vector2::new(3.25, 7.75); 3];
+        assert_eq!(pixels(point, ConservativeMode::Overestimate, &options), [(3, 7)].iter().cloned().collect());
+
+        // On the pixel grid, points and segments touch the pixels on every side of them.
+        let corner = [Vector2::new(3.0, 7.0); 3];
+        assert_eq!(pixels(corner, ConservativeMode::Overestimate, &options), [(2, 6), (3, 6), (2, 7), (3, 7)].iter().cloned().collect());
+        let edge = [Vector2::new(0.0, 2.0), Vector2::new(5.0, 2.0), Vector2::new(2.0, 2.0)];
+        let over = pixels(edge, ConservativeMode::Overestimate, &options);
+        assert_eq!(over, (-1..6).flat_map(|x| vec![(x, 1), (x, 2)]).collect());
+
+        // Culling applies to triangles with a facing but not to zero area ones.
+        let mut culled = options;
+        culled.cull = CullMode::Front;
+        let t = [Vector2::new(1.0, 1.0), Vector2::new(1.0, 6.0), Vector2::new(6.0, 1.0)];
+        assert!(pixels(t, ConservativeMode::Overestimate, &culled).is_empty());
+        assert_eq!(pixels(line, ConservativeMode::Overestimate, &culled).len(), 7);
+
+        // Vertices outside the fixed point range are rejected rather than overflowing, with or without area.
+        let far = Vector2::new(1e8, 10.0);
+        assert!(pixels([Vector2::zero(), far, Vector2::new(0.0, 10.0)], ConservativeMode::Overestimate, &options).is_empty());
+        assert!(pixels([Vector2::zero(), far, far], ConservativeMode::Overestimate, &options).is_empty());
+    }
+}
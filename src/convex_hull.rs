@@ -0,0 +1,361 @@
+use std::collections::HashMap;
+use vector3::Vector3;
+use gjk::Support;
+
+/// A 3D convex hull built from a set of points with the quickhull algorithm.
+/// Vertex, face and edge indices refer to `points`, which holds the points the hull was built from.
+#[derive(Debug, Clone)]
+pub struct ConvexHull {
+    /// The points the hull was built from.
+    pub points: Vec<Vector3>,
+    /// The indices of the points on the hull's corners, in ascending order.
+    pub vertices: Vec<usize>,
+    /// The hull's triangles, wound counter-clockwise when seen from outside.
+    /// Coplanar regions are split into several triangles.
+    pub faces: Vec<[usize; 3]>,
+    /// The unique edges of the hull's triangles with the smaller index first.
+    pub edges: Vec<(usize, usize)>,
+}
+
+/// A face of the hull while it is being built.
+struct HullFace {
+    indices: [usize; 3],
+    normal: Vector3,
+    offset: f32,
+    outside: Vec<usize>,
+    alive: bool,
+}
+
+impl HullFace {
+    fn new(points: &[Vector3], indices: [usize; 3]) -> HullFace {
+        let [a, b, c] = indices;
+        let n = Vector3::cross(points[b] - points[a], points[c] - points[a]);
+        let l = n.length();
+        let normal = if l > 0.0 { n / l } else { Vector3::zero() };
+
+        HullFace { indices, normal, offset: Vector3::dot(normal, points[a]), outside: Vec::new(), alive: true }
+    }
+
+    fn distance(&self, point: Vector3) -> f32 {
+        Vector3::dot(self.normal, point) - self.offset
+    }
+}
+
+impl ConvexHull {
+    /// Returns the convex hull of `points` with a tolerance scaled to the size of the input.
+    /// Returns `None` if the points do not span a volume.
+    pub fn new(points: &[Vector3]) -> Option<ConvexHull> {
+        let extent = points.iter().fold(Vector3::zero(), |e, p| Vector3::max(e, p.abs()));
+        let epsilon = 3.0 * f32::EPSILON * (extent.x + extent.y + extent.z);
+        ConvexHull::with_epsilon(points, epsilon)
+    }
+
+    /// Returns the convex hull of `points`.
+    /// Points within `epsilon` of the hull's surface, including duplicates and points on coplanar faces,
+    /// are not used as vertices.
+    /// Returns `None` if the points do not span a volume larger than `epsilon`.
+    pub fn with_epsilon(points: &[Vector3], epsilon: f32) -> Option<ConvexHull> {
+        let initial = ConvexHull::initial_simplex(points, epsilon)?;
+
+        let mut faces: Vec<HullFace> = Vec::new();
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+        let add_face = |faces: &mut Vec<HullFace>, edges: &mut HashMap<(usize, usize), usize>, indices: [usize; 3]| {
+            let index = faces.len();
+            for e in 0..3 {
+                edges.insert((indices[e], indices[(e + 1) % 3]), index);
+            }
+            faces.push(HullFace::new(points, indices));
+            index
+        };
+
+        let [i0, i1, i2, i3] = initial;
+        let flip = Vector3::dot(Vector3::cross(points[i1] - points[i0], points[i2] - points[i0]), points[i3] - points[i0]) > 0.0;
+        let (i1, i2) = if flip { (i2, i1) } else { (i1, i2) };
+        for indices in [[i0, i1, i2], [i0, i3, i1], [i1, i3, i2], [i2, i3, i0]].iter() {
+            add_face(&mut faces, &mut edges, *indices);
+        }
+
+        for (i, p) in points.iter().enumerate() {
+            if initial.contains(&i) {
+                continue;
+            }
+            if let Some(face) = faces.iter_mut().find(|f| f.distance(*p) > epsilon) {
+                face.outside.push(i);
+            }
+        }
+
+        while let Some(current) = faces.iter().position(|f| f.alive && !f.outside.is_empty()) {
+            let eye = faces[current].outside.iter()
+                .cloned()
+                .fold(faces[current].outside[0], |best, i| {
+                    if faces[current].distance(points[i]) > faces[current].distance(points[best]) { i } else { best }
+                });
+            let eye_point = points[eye];
+
+            // Flood out from the current face to every face the eye point can see, recording the horizon.
+            let mut visible = vec![current];
+            let mut horizon = Vec::new();
+            faces[current].alive = false;
+            let mut next = 0;
+            while next < visible.len() {
+                let f = visible[next];
+                next += 1;
+                for e in 0..3 {
+                    let (a, b) = (faces[f].indices[e], faces[f].indices[(e + 1) % 3]);
+                    let neighbor = edges[&(b, a)];
+                    if !faces[neighbor].alive {
+                        continue;
+                    }
+                    if faces[neighbor].distance(eye_point) > epsilon {
+                        faces[neighbor].alive = false;
+                        visible.push(neighbor);
+                    } else {
+                        horizon.push((a, b));
+                    }
+                }
+            }
+
+            let mut orphans = Vec::new();
+            for f in visible.iter() {
+                orphans.append(&mut faces[*f].outside);
+                for e in 0..3 {
+                    let edge = (faces[*f].indices[e], faces[*f].indices[(e + 1) % 3]);
+                    if edges.get(&edge) == Some(f) {
+                        edges.remove(&edge);
+                    }
+                }
+            }
+
+            let first_new = faces.len();
+            for (a, b) in horizon {
+                add_face(&mut faces, &mut edges, [a, b, eye]);
+            }
+
+            for i in orphans {
+                if i == eye {
+                    continue;
+                }
+                if let Some(face) = faces[first_new..].iter_mut().find(|f| f.distance(points[i]) > epsilon) {
+                    face.outside.push(i);
+                }
+            }
+        }
+
+        let hull_faces: Vec<[usize; 3]> = faces.iter().filter(|f| f.alive).map(|f| f.indices).collect();
+        let mut vertices: Vec<usize> = hull_faces.iter().flat_map(|f| f.iter().cloned()).collect();
+        vertices.sort();
+        vertices.dedup();
+
+        let mut hull_edges: Vec<(usize, usize)> = hull_faces.iter()
+            .flat_map(|f| vec![(f[0], f[1]), (f[1], f[2]), (f[2], f[0])])
+            .filter(|&(a, b)| a < b)
+            .collect();
+        hull_edges.sort();
+
+        Some(ConvexHull { points: points.to_vec(), vertices, faces: hull_faces, edges: hull_edges })
+    }
+
+    /// Returns the indices of 4 points spanning a tetrahedron with the largest extent the input allows.
+    fn initial_simplex(points: &[Vector3], epsilon: f32) -> Option<[usize; 4]> {
+        if points.len() < 4 {
+            return None;
+        }
+
+        let mut extremes = [0; 6];
+        for (i, p) in points.iter().enumerate() {
+            for axis in 0..3 {
+                if p[axis] < points[extremes[axis * 2]][axis] {
+                    extremes[axis * 2] = i;
+                }
+                if p[axis] > points[extremes[axis * 2 + 1]][axis] {
+                    extremes[axis * 2 + 1] = i;
+                }
+            }
+        }
+
+        let mut i0 = extremes[0];
+        let mut i1 = extremes[1];
+        for a in extremes.iter() {
+            for b in extremes.iter() {
+                if (points[*a] - points[*b]).length_squared() > (points[i0] - points[i1]).length_squared() {
+                    i0 = *a;
+                    i1 = *b;
+                }
+            }
+        }
+        if (points[i1] - points[i0]).length() <= epsilon {
+            return None;
+        }
+
+        let line = (points[i1] - points[i0]).normalized();
+        let line_distance = |p: Vector3| {
+            let d = p - points[i0];
+            (d - line * Vector3::dot(d, line)).length()
+        };
+        let i2 = (0..points.len()).fold(i0, |best, i| if line_distance(points[i]) > line_distance(points[best]) { i } else { best });
+        if line_distance(points[i2]) <= epsilon {
+            return None;
+        }
+
+        let normal = Vector3::cross(points[i1] - points[i0], points[i2] - points[i0]).normalized();
+        let plane_distance = |p: Vector3| Vector3::dot(p - points[i0], normal).abs();
+        let i3 = (0..points.len()).fold(i0, |best, i| if plane_distance(points[i]) > plane_distance(points[best]) { i } else { best });
+        if plane_distance(points[i3]) <= epsilon {
+            return None;
+        }
+
+        Some([i0, i1, i2, i3])
+    }
+
+    /// Returns the volume enclosed by the hull.
+    pub fn volume(&self) -> f32 {
+        let reference = self.points[self.vertices[0]];
+        self.faces.iter().fold(0.0, |v, f| {
+            let (a, b, c) = (self.points[f[0]] - reference, self.points[f[1]] - reference, self.points[f[2]] - reference);
+            v + Vector3::dot(a, Vector3::cross(b, c)) / 6.0
+        })
+    }
+
+    /// Returns the center of mass of the hull assuming uniform density.
+    pub fn centroid(&self) -> Vector3 {
+        let reference = self.points[self.vertices[0]];
+        let mut volume = 0.0;
+        let mut weighted = Vector3::zero();
+        for f in self.faces.iter() {
+            let (a, b, c) = (self.points[f[0]] - reference, self.points[f[1]] - reference, self.points[f[2]] - reference);
+            let v = Vector3::dot(a, Vector3::cross(b, c)) / 6.0;
+            volume += v;
+            weighted = weighted + (a + b + c) * (v / 4.0);
+        }
+
+        reference + weighted / volume
+    }
+
+    /// Returns the inertia tensor of the hull about its centroid with a density of 1, as rows of a 3x3 matrix.
+    /// Multiply by the mass divided by `volume` for other densities.
+    pub fn inertia_tensor(&self) -> [[f32; 3]; 3] {
+        let reference = self.points[self.vertices[0]];
+        let mut volume = 0.0;
+        let mut weighted = Vector3::zero();
+        let mut covariance = [[0.0f32; 3]; 3];
+
+        for f in self.faces.iter() {
+            let (a, b, c) = (self.points[f[0]] - reference, self.points[f[1]] - reference, self.points[f[2]] - reference);
+            let det = Vector3::dot(a, Vector3::cross(b, c));
+            volume += det / 6.0;
+            weighted = weighted + (a + b + c) * (det / 24.0);
+
+            // Covariance of the tetrahedron (reference, a, b, c) from the canonical tetrahedron's covariance.
+            let sum = a + b + c;
+            for (i, row) in covariance.iter_mut().enumerate() {
+                for (j, value) in row.iter_mut().enumerate() {
+                    *value += det / 120.0 * (a[i] * a[j] + b[i] * b[j] + c[i] * c[j] + sum[i] * sum[j]);
+                }
+            }
+        }
+
+        let center = weighted / volume;
+        for (i, row) in covariance.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value -= volume * center[i] * center[j];
+            }
+        }
+
+        let trace = covariance[0][0] + covariance[1][1] + covariance[2][2];
+        let mut inertia = [[0.0f32; 3]; 3];
+        for (i, row) in inertia.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = if i == j { trace } else { 0.0 } - covariance[i][j];
+            }
+        }
+
+        inertia
+    }
+}
+
+impl Support for ConvexHull {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        let best = self.vertices.iter().cloned().fold(self.vertices[0], |best, i| {
+            if Vector3::dot(self.points[i], direction) > Vector3::dot(self.points[best], direction) { i } else { best }
+        });
+        self.points[best]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use convex_hull::ConvexHull;
+    use vector3::Vector3;
+
+    fn cube_with_interior_points() -> Vec<Vector3> {
+        let mut points = Vec::new();
+        for x in 0..3 {
+            for y in 0..3 {
+                for z in 0..3 {
+                    points.push(Vector3::new(x as f32 * 0.5, y as f32 * 0.5, z as f32 * 0.5));
+                }
+            }
+        }
+        points.push(Vector3::new(1.0, 1.0, 1.0));
+        points.push(Vector3::new(0.0, 0.0, 0.0));
+        points
+    }
+
+    #[test]
+    fn test_convex_hull_cube() {
+        let points = cube_with_interior_points();
+        let hull = ConvexHull::new(&points).unwrap();
+
+        assert_eq!(hull.vertices.len(), 8);
+        assert_eq!(hull.faces.len(), 12);
+        assert_eq!(hull.edges.len(), 18);
+        for v in hull.vertices.iter() {
+            let p = points[*v];
+            assert!((p.x == 0.0 || p.x == 1.0) && (p.y == 0.0 || p.y == 1.0) && (p.z == 0.0 || p.z == 1.0));
+        }
+    }
+
+    #[test]
+    fn test_convex_hull_mass_properties() {
+        let hull = ConvexHull::new(&cube_with_interior_points()).unwrap();
+        let inertia = hull.inertia_tensor();
+
+        assert!((hull.volume() - 1.0).abs() < 0.0001);
+        assert!((hull.centroid() - Vector3::new(0.5, 0.5, 0.5)).length() < 0.0001);
+        for (i, row) in inertia.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                let expected = if i == j { 1.0 / 6.0 } else { 0.0 };
+                assert!((value - expected).abs() < 0.0001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_convex_hull_faces_point_outward() {
+        let mut points = Vec::new();
+        for i in 0..64 {
+            let t = i as f32 * 0.7;
+            let z = (i as f32 / 32.0) - 1.0;
+            let r = (1.0 - z * z).sqrt();
+            points.push(Vector3::new(r * t.cos(), r * t.sin(), z));
+        }
+
+        let hull = ConvexHull::new(&points).unwrap();
+        let center = hull.centroid();
+        for f in hull.faces.iter() {
+            let n = Vector3::cross(points[f[1]] - points[f[0]], points[f[2]] - points[f[0]]);
+            assert!(Vector3::dot(n, points[f[0]] - center) > 0.0);
+            for p in points.iter() {
+                assert!(Vector3::dot(n.normalized(), *p - points[f[0]]) < 0.0001);
+            }
+        }
+        assert_eq!(hull.faces.len(), 2 * hull.vertices.len() - 4);
+    }
+
+    #[test]
+    fn test_convex_hull_degenerate() {
+        let flat = [Vector3::zero(), Vector3::left(), Vector3::up(), Vector3::new(1.0, 1.0, 0.0)];
+        assert!(ConvexHull::new(&flat).is_none());
+        assert!(ConvexHull::new(&[Vector3::zero(); 10]).is_none());
+    }
+}
@@ -0,0 +1,307 @@
+use vector3::Vector3;
+use ray::Ray;
+use aabb::Aabb;
+
+/// Squared lengths at or below this are treated as zero length segments.
+const DEGENERATE_EPSILON: f32 = 1e-12;
+
+/// The closest point on a segment to a point.
+#[derive(Debug, Copy, Clone)]
+pub struct PointSegmentDistance {
+    /// The closest point on the segment.
+    pub point: Vector3,
+    /// The parameter of `point` along the segment, from 0 at the start to 1 at the end.
+    pub t: f32,
+    pub distance: f32,
+}
+
+/// The closest point on a triangle to a point.
+#[derive(Debug, Copy, Clone)]
+pub struct PointTriangleDistance {
+    /// The closest point on the triangle.
+    pub point: Vector3,
+    /// The barycentric coordinates of `point` for the triangle's vertices.
+    pub barycentric: Vector3,
+    pub distance: f32,
+}
+
+/// The closest points between two segments.
+#[derive(Debug, Copy, Clone)]
+pub struct SegmentSegmentDistance {
+    /// The closest point on the first segment.
+    pub point0: Vector3,
+    /// The closest point on the second segment.
+    pub point1: Vector3,
+    /// The parameter of `point0` along the first segment.
+    pub s: f32,
+    /// The parameter of `point1` along the second segment.
+    pub t: f32,
+    pub distance: f32,
+}
+
+/// The closest point on an `Aabb` to a point.
+#[derive(Debug, Copy, Clone)]
+pub struct PointAabbDistance {
+    /// The closest point on or inside the box.
+    pub point: Vector3,
+    /// The distance to the box, which is 0 if the point is inside.
+    pub distance: f32,
+}
+
+/// The closest points between two rays.
+#[derive(Debug, Copy, Clone)]
+pub struct RayRayDistance {
+    /// The closest point on the first ray.
+    pub point0: Vector3,
+    /// The closest point on the second ray.
+    pub point1: Vector3,
+    /// The time of `point0` along the first ray.
+    pub t0: f32,
+    /// The time of `point1` along the second ray.
+    pub t1: f32,
+    pub distance: f32,
+}
+
+/// Returns the closest point on the segment from `a` to `b` to `point`.
+/// A zero length segment returns `a`.
+pub fn point_segment_distance(point: Vector3, a: Vector3, b: Vector3) -> PointSegmentDistance {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    let t = if length_squared <= DEGENERATE_EPSILON {
+        0.0
+    } else {
+        (Vector3::dot(point - a, ab) / length_squared).clamp(0.0, 1.0)
+    };
+
+    let closest = a + ab * t;
+    PointSegmentDistance { point: closest, t, distance: (point - closest).length() }
+}
+
+/// Returns the closest point on the triangle `a`, `b`, `c` to `point`.
+/// Degenerate triangles are treated as the segment or point they collapse to.
+pub fn point_triangle_distance(point: Vector3, a: Vector3, b: Vector3, c: Vector3) -> PointTriangleDistance {
+    let result = |closest: Vector3, barycentric: Vector3| {
+        PointTriangleDistance { point: closest, barycentric, distance: (point - closest).length() }
+    };
+
+    let ab = b - a;
+    let ac = c - a;
+    let ap = point - a;
+
+    let d1 = Vector3::dot(ab, ap);
+    let d2 = Vector3::dot(ac, ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return result(a, Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    let bp = point - b;
+    let d3 = Vector3::dot(ab, bp);
+    let d4 = Vector3::dot(ac, bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return result(b, Vector3::new(0.0, 1.0, 0.0));
+    }
+
+    let cp = point - c;
+    let d5 = Vector3::dot(ab, cp);
+    let d6 = Vector3::dot(ac, cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return result(c, Vector3::new(0.0, 0.0, 1.0));
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 && d1 - d3 > 0.0 {
+        let v = d1 / (d1 - d3);
+        return result(a + ab * v, Vector3::new(1.0 - v, v, 0.0));
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 && d2 - d6 > 0.0 {
+        let w = d2 / (d2 - d6);
+        return result(a + ac * w, Vector3::new(1.0 - w, 0.0, w));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 && (d4 - d3) + (d5 - d6) > 0.0 {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return result(b + (c - b) * w, Vector3::new(0.0, 1.0 - w, w));
+    }
+
+    let denom = va + vb + vc;
+    if denom.abs() <= DEGENERATE_EPSILON {
+        // The triangle has no area, so the closest point lies on one of its edges.
+        let edges = [(point_segment_distance(point, a, b), 0, 1),
+                     (point_segment_distance(point, a, c), 0, 2),
+                     (point_segment_distance(point, b, c), 1, 2)];
+        let &(closest, i, j) = edges.iter()
+            .fold(&edges[0], |best, e| if e.0.distance < best.0.distance { e } else { best });
+        let mut barycentric = Vector3::zero();
+        barycentric[i] = 1.0 - closest.t;
+        barycentric[j] = closest.t;
+        return result(closest.point, barycentric);
+    }
+
+    let v = vb / denom;
+    let w = vc / denom;
+    result(a + ab * v + ac * w, Vector3::new(1.0 - v - w, v, w))
+}
+
+/// Returns the closest points between the segment from `p0` to `q0` and the segment from `p1` to `q1`.
+/// Parallel segments return one of the equally close pairs of points.
+pub fn segment_segment_distance(p0: Vector3, q0: Vector3, p1: Vector3, q1: Vector3) -> SegmentSegmentDistance {
+    let (s, t) = closest_parameters(p0, q0 - p0, p1, q1 - p1, 1.0);
+    let point0 = p0 + (q0 - p0) * s;
+    let point1 = p1 + (q1 - p1) * t;
+
+    SegmentSegmentDistance { point0, point1, s, t, distance: (point1 - point0).length() }
+}
+
+/// Returns the closest point on or inside `aabb` to `point`.
+pub fn point_aabb_distance(point: Vector3, aabb: &Aabb) -> PointAabbDistance {
+    let closest = aabb.closest_point(point);
+    PointAabbDistance { point: closest, distance: (point - closest).length() }
+}
+
+/// Returns the closest points between the rays `r0` and `r1`.
+/// Parallel rays return one of the equally close pairs of points.
+pub fn ray_ray_distance(r0: &Ray, r1: &Ray) -> RayRayDistance {
+    let (t0, t1) = closest_parameters(r0.origin(), r0.direction(), r1.origin(), r1.direction(), f32::INFINITY);
+    let point0 = r0.point_at_parameter(t0);
+    let point1 = r1.point_at_parameter(t1);
+
+    RayRayDistance { point0, point1, t0, t1, distance: (point1 - point0).length() }
+}
+
+/// Returns the parameters of the closest points between the lines `p0 + d0 * s` and `p1 + d1 * t`
+/// with `s` and `t` clamped to `[0, limit]`.
+fn closest_parameters(p0: Vector3, d0: Vector3, p1: Vector3, d1: Vector3, limit: f32) -> (f32, f32) {
+    let clamp = |v: f32| v.max(0.0).min(limit);
+    let r = p0 - p1;
+    let a = d0.length_squared();
+    let e = d1.length_squared();
+    let f = Vector3::dot(d1, r);
+
+    if a <= DEGENERATE_EPSILON && e <= DEGENERATE_EPSILON {
+        return (0.0, 0.0);
+    }
+    if a <= DEGENERATE_EPSILON {
+        return (0.0, clamp(f / e));
+    }
+
+    let c = Vector3::dot(d0, r);
+    if e <= DEGENERATE_EPSILON {
+        return (clamp(-c / a), 0.0);
+    }
+
+    let b = Vector3::dot(d0, d1);
+    let denom = a * e - b * b;
+
+    // Parallel lines have no unique answer so start from the first line's origin.
+    let mut s = if denom > DEGENERATE_EPSILON * a * e { clamp((b * f - c * e) / denom) } else { 0.0 };
+    let mut t = (b * s + f) / e;
+
+    if t < 0.0 {
+        t = 0.0;
+        s = clamp(-c / a);
+    } else if t > limit {
+        t = limit;
+        s = clamp((b * limit - c) / a);
+    }
+
+    (s, t)
+}
+
+#[cfg(test)]
+mod tests {
+    use distance::*;
+    use vector3::Vector3;
+    use ray::Ray;
+    use aabb::Aabb;
+
+    fn assert_near(a: Vector3, b: Vector3) {
+        assert!((a - b).length() < 0.0001, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_point_segment_distance() {
+        let r = point_segment_distance(Vector3::new(0.5, 1.0, 0.0), Vector3::zero(), Vector3::left());
+        assert_near(r.point, Vector3::new(0.5, 0.0, 0.0));
+        assert_eq!(r.t, 0.5);
+        assert_eq!(r.distance, 1.0);
+
+        let r = point_segment_distance(Vector3::new(3.0, 0.0, 0.0), Vector3::zero(), Vector3::zero());
+        assert_eq!(r.t, 0.0);
+        assert_eq!(r.distance, 3.0);
+    }
+
+    #[test]
+    fn test_point_triangle_distance() {
+        let (a, b, c) = (Vector3::zero(), Vector3::new(2.0, 0.0, 0.0), Vector3::new(0.0, 2.0, 0.0));
+
+        let r = point_triangle_distance(Vector3::new(0.5, 0.5, 3.0), a, b, c);
+        assert_near(r.point, Vector3::new(0.5, 0.5, 0.0));
+        assert_near(r.barycentric, Vector3::new(0.5, 0.25, 0.25));
+        assert!((r.distance - 3.0).abs() < 0.0001);
+
+        let r = point_triangle_distance(Vector3::new(2.0, 2.0, 0.0), a, b, c);
+        assert_near(r.point, Vector3::new(1.0, 1.0, 0.0));
+        assert_near(r.barycentric, Vector3::new(0.0, 0.5, 0.5));
+
+        let r = point_triangle_distance(Vector3::new(-1.0, -1.0, 0.0), a, b, c);
+        assert_near(r.barycentric, Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_point_triangle_distance_degenerate() {
+        let (a, b, c) = (Vector3::zero(), Vector3::new(1.0, 0.0, 0.0), Vector3::new(2.0, 0.0, 0.0));
+        let r = point_triangle_distance(Vector3::new(1.5, 1.0, 0.0), a, b, c);
+
+        assert_near(r.point, Vector3::new(1.5, 0.0, 0.0));
+        assert!((r.distance - 1.0).abs() < 0.0001);
+        assert!(!r.barycentric.has_nans());
+    }
+
+    #[test]
+    fn test_segment_segment_distance() {
+        let r = segment_segment_distance(Vector3::new(-1.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0),
+                                         Vector3::new(0.0, -1.0, 2.0), Vector3::new(0.0, 1.0, 2.0));
+        assert_near(r.point0, Vector3::zero());
+        assert_near(r.point1, Vector3::new(0.0, 0.0, 2.0));
+        assert_eq!(r.s, 0.5);
+        assert_eq!(r.t, 0.5);
+        assert!((r.distance - 2.0).abs() < 0.0001);
+
+        let r = segment_segment_distance(Vector3::zero(), Vector3::left(),
+                                         Vector3::new(2.0, 1.0, 0.0), Vector3::new(3.0, 1.0, 0.0));
+        assert_near(r.point0, Vector3::left());
+        assert_near(r.point1, Vector3::new(2.0, 1.0, 0.0));
+
+        let r = segment_segment_distance(Vector3::zero(), Vector3::zero(),
+                                         Vector3::new(-1.0, 1.0, 0.0), Vector3::new(1.0, 1.0, 0.0));
+        assert_near(r.point1, Vector3::up());
+    }
+
+    #[test]
+    fn test_point_aabb_distance() {
+        let b = Aabb::new(Vector3::zero(), Vector3::new(1.0, 1.0, 1.0));
+        let r = point_aabb_distance(Vector3::new(2.0, 0.5, 0.5), &b);
+
+        assert_near(r.point, Vector3::new(1.0, 0.5, 0.5));
+        assert_eq!(r.distance, 1.0);
+        assert_eq!(point_aabb_distance(Vector3::new(0.5, 0.5, 0.5), &b).distance, 0.0);
+    }
+
+    #[test]
+    fn test_ray_ray_distance() {
+        let r0 = Ray::new(Vector3::zero(), Vector3::left(), 0.0);
+        let r1 = Ray::new(Vector3::new(3.0, -2.0, 1.0), Vector3::up(), 0.0);
+        let r = ray_ray_distance(&r0, &r1);
+
+        assert_near(r.point0, Vector3::new(3.0, 0.0, 0.0));
+        assert_near(r.point1, Vector3::new(3.0, 0.0, 1.0));
+        assert_eq!(r.t0, 3.0);
+        assert_eq!(r.t1, 2.0);
+
+        let behind = Ray::new(Vector3::new(-3.0, -2.0, 1.0), Vector3::up(), 0.0);
+        assert_eq!(ray_ray_distance(&r0, &behind).t0, 0.0);
+    }
+}
@@ -0,0 +1,172 @@
+use vector3::Vector3;
+use vector4::Vector4;
+use matrix4::Matrix4;
+use plane::Plane;
+use aabb::Aabb;
+
+/// The range clip space depth is mapped to by a projection matrix.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClipDepth {
+    /// Depth ranges from 0 at the near plane to 1 at the far plane, as produced by `Matrix4::perpective`.
+    ZeroToOne,
+    /// Depth ranges from -1 at the near plane to 1 at the far plane, as produced by `Matrix4::ortho`.
+    NegativeOneToOne,
+}
+
+/// The result of testing a volume against a `Frustum`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Containment {
+    Outside,
+    Intersect,
+    Inside,
+}
+
+/// A view frustum made of 6 inward facing planes ordered left, right, bottom, top, near and far.
+#[derive(Debug, Copy, Clone)]
+pub struct Frustum {
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    pub const LEFT: usize = 0;
+    pub const RIGHT: usize = 1;
+    pub const BOTTOM: usize = 2;
+    pub const TOP: usize = 3;
+    pub const NEAR: usize = 4;
+    pub const FAR: usize = 5;
+
+    /// Returns the `Frustum` of a view projection matrix using the Gribb-Hartmann method.
+    /// `depth` is the clip space depth range the matrix produces.
+    pub fn from_matrix(m: Matrix4, depth: ClipDepth) -> Frustum {
+        let row = |i: usize| Vector4::new(m[i][0], m[i][1], m[i][2], m[i][3]);
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+        let plane = |v: Vector4| Plane::new(v.xyz(), v.w).normalized();
+
+        let near = match depth {
+            ClipDepth::ZeroToOne => r2,
+            ClipDepth::NegativeOneToOne => r3 + r2,
+        };
+
+        Frustum {
+            planes: [plane(r3 + r0),
+                     plane(r3 - r0),
+                     plane(r3 + r1),
+                     plane(r3 - r1),
+                     plane(near),
+                     plane(r3 - r2)],
+        }
+    }
+
+    /// Returns true if `point` is inside the frustum.
+    pub fn contains_point(&self, point: Vector3) -> bool {
+        self.planes.iter().all(|p| p.distance(point) >= 0.0)
+    }
+
+    /// Returns whether the sphere at `center` with `radius` is inside, outside or crossing the frustum.
+    pub fn test_sphere(&self, center: Vector3, radius: f32) -> Containment {
+        let mut result = Containment::Inside;
+        for p in self.planes.iter() {
+            let d = p.distance(center);
+            if d < -radius {
+                return Containment::Outside;
+            }
+            if d < radius {
+                result = Containment::Intersect;
+            }
+        }
+
+        result
+    }
+
+    /// Returns whether `aabb` is inside, outside or crossing the frustum.
+    /// Boxes near the frustum corners may be reported as intersecting when they are outside.
+    pub fn test_aabb(&self, aabb: &Aabb) -> Containment {
+        let center = aabb.center();
+        let extents = aabb.half_extents();
+        let mut result = Containment::Inside;
+        for p in self.planes.iter() {
+            let d = p.distance(center);
+            let r = Vector3::dot(extents, p.normal.abs());
+            if d < -r {
+                return Containment::Outside;
+            }
+            if d < r {
+                result = Containment::Intersect;
+            }
+        }
+
+        result
+    }
+
+    /// Returns the 8 corners of the frustum ordered left-bottom, right-bottom, right-top and left-top
+    /// on the near plane followed by the same order on the far plane.
+    /// Returns `None` if the frustum has parallel planes, such as an infinite far plane.
+    pub fn corners(&self) -> Option<[Vector3; 8]> {
+        let p = &self.planes;
+        let mut corners = [Vector3::zero(); 8];
+        for (i, depth) in [Frustum::NEAR, Frustum::FAR].iter().enumerate() {
+            let d = &p[*depth];
+            corners[i * 4] = Plane::intersect_planes(d, &p[Frustum::LEFT], &p[Frustum::BOTTOM])?;
+            corners[i * 4 + 1] = Plane::intersect_planes(d, &p[Frustum::RIGHT], &p[Frustum::BOTTOM])?;
+            corners[i * 4 + 2] = Plane::intersect_planes(d, &p[Frustum::RIGHT], &p[Frustum::TOP])?;
+            corners[i * 4 + 3] = Plane::intersect_planes(d, &p[Frustum::LEFT], &p[Frustum::TOP])?;
+        }
+
+        Some(corners)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use frustum::{Frustum, ClipDepth, Containment};
+    use vector3::Vector3;
+    use matrix4::Matrix4;
+    use aabb::Aabb;
+
+    fn perspective_frustum() -> Frustum {
+        Frustum::from_matrix(Matrix4::perpective(90.0, 1.0, 1.0, 100.0), ClipDepth::ZeroToOne)
+    }
+
+    #[test]
+    fn test_frustum_contains_point() {
+        let f = perspective_frustum();
+
+        assert!(f.contains_point(Vector3::new(0.0, 0.0, -5.0)));
+        assert!(f.contains_point(Vector3::new(4.9, -4.9, -5.0)));
+        assert!(!f.contains_point(Vector3::new(5.1, 0.0, -5.0)));
+        assert!(!f.contains_point(Vector3::new(0.0, 0.0, 5.0)));
+        assert!(!f.contains_point(Vector3::new(0.0, 0.0, -0.5)));
+        assert!(!f.contains_point(Vector3::new(0.0, 0.0, -101.0)));
+    }
+
+    #[test]
+    fn test_frustum_test_sphere() {
+        let f = perspective_frustum();
+
+        assert_eq!(f.test_sphere(Vector3::new(0.0, 0.0, -10.0), 1.0), Containment::Inside);
+        assert_eq!(f.test_sphere(Vector3::new(0.0, 0.0, -100.0), 1.0), Containment::Intersect);
+        assert_eq!(f.test_sphere(Vector3::new(0.0, 0.0, 10.0), 1.0), Containment::Outside);
+    }
+
+    #[test]
+    fn test_frustum_test_aabb() {
+        let f = Frustum::from_matrix(Matrix4::ortho(-1.0, 1.0, -1.0, 1.0, 1.0, 10.0), ClipDepth::NegativeOneToOne);
+
+        let inside = Aabb::new(Vector3::new(-0.5, -0.5, -5.0), Vector3::new(0.5, 0.5, -4.0));
+        let crossing = Aabb::new(Vector3::new(0.5, -0.5, -5.0), Vector3::new(1.5, 0.5, -4.0));
+        let outside = Aabb::new(Vector3::new(-0.5, -0.5, 1.0), Vector3::new(0.5, 0.5, 2.0));
+
+        assert_eq!(f.test_aabb(&inside), Containment::Inside);
+        assert_eq!(f.test_aabb(&crossing), Containment::Intersect);
+        assert_eq!(f.test_aabb(&outside), Containment::Outside);
+    }
+
+    #[test]
+    fn test_frustum_corners() {
+        let corners = perspective_frustum().corners().unwrap();
+
+        assert!((corners[0] - Vector3::new(-1.0, -1.0, -1.0)).length() < 0.001);
+        assert!((corners[2] - Vector3::new(1.0, 1.0, -1.0)).length() < 0.001);
+        assert!((corners[6] - Vector3::new(100.0, 100.0, -100.0)).length() < 0.1);
+    }
+}
@@ -0,0 +1,614 @@
+use vector3::Vector3;
+use matrix4::Matrix4;
+use quaternion::Quaternion;
+use aabb::Aabb;
+use obb::Obb;
+use sphere::Sphere;
+use capsule::{Capsule, Segment3};
+use triangle::Triangle;
+use distance::{point_segment_distance, point_triangle_distance};
+
+/// The most iterations GJK and EPA will run before returning their best result.
+const MAX_ITERATIONS: usize = 64;
+
+/// The relative improvement below which GJK and EPA treat their result as converged.
+const TOLERANCE: f32 = 1e-5;
+
+/// A convex shape that can report its farthest point in a direction.
+pub trait Support {
+    /// Returns the point of the shape farthest along `direction`.
+    /// `direction` is not required to be normalized.
+    fn support(&self, direction: Vector3) -> Vector3;
+}
+
+/// A set of points treated as their convex hull.
+#[derive(Debug, Clone)]
+pub struct ConvexPointCloud {
+    pub points: Vec<Vector3>,
+}
+
+impl ConvexPointCloud {
+    /// Returns a new `ConvexPointCloud`.
+    pub fn new(points: Vec<Vector3>) -> ConvexPointCloud {
+        ConvexPointCloud { points }
+    }
+}
+
+/// A convex shape placed in the world by an affine transformation matrix.
+#[derive(Debug, Copy, Clone)]
+pub struct Transformed<S> {
+    pub shape: S,
+    pub transform: Matrix4,
+}
+
+impl<S: Support> Transformed<S> {
+    /// Returns `shape` transformed by `transform`.
+    pub fn new(shape: S, transform: Matrix4) -> Transformed<S> {
+        Transformed { shape, transform }
+    }
+
+    /// Returns `shape` rotated by `orientation` and then moved to `position`.
+    pub fn from_position_orientation(shape: S, position: Vector3, orientation: Quaternion) -> Transformed<S> {
+        Transformed {
+            shape,
+            transform: Matrix4::translation(position.x, position.y, position.z) * Matrix4::rotation(orientation),
+        }
+    }
+}
+
+impl Support for Sphere {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        let l = direction.length();
+        if l == 0.0 {
+            return self.center;
+        }
+
+        self.center + direction * (self.radius / l)
+    }
+}
+
+impl Support for Aabb {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        Vector3::new(if direction.x >= 0.0 { self.max.x } else { self.min.x },
+                     if direction.y >= 0.0 { self.max.y } else { self.min.y },
+                     if direction.z >= 0.0 { self.max.z } else { self.min.z })
+    }
+}
+
+impl Support for Obb {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        let mut p = self.center;
+        for (i, axis) in self.axes().iter().enumerate() {
+            let sign = if Vector3::dot(direction, *axis) >= 0.0 { 1.0 } else { -1.0 };
+            p = p + *axis * (sign * self.half_extents[i]);
+        }
+
+        p
+    }
+}
+
+impl Support for Capsule {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        let end = if Vector3::dot(direction, self.b - self.a) >= 0.0 { self.b } else { self.a };
+        let l = direction.length();
+        if l == 0.0 {
+            return end;
+        }
+
+        end + direction * (self.radius / l)
+    }
+}
+
+impl Support for Segment3 {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        if Vector3::dot(direction, self.b - self.a) >= 0.0 { self.b } else { self.a }
+    }
+}
+
+impl Support for Triangle {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        farthest_point(&[self.v0, self.v1, self.v2], direction)
+    }
+}
+
+impl Support for ConvexPointCloud {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        farthest_point(&self.points, direction)
+    }
+}
+
+impl<S: Support> Support for Transformed<S> {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        // Directions transform by the transpose of the linear part.
+        let m = &self.transform;
+        let local = Vector3::new(m[0][0] * direction.x + m[1][0] * direction.y + m[2][0] * direction.z,
+                                 m[0][1] * direction.x + m[1][1] * direction.y + m[2][1] * direction.z,
+                                 m[0][2] * direction.x + m[1][2] * direction.y + m[2][2] * direction.z);
+        self.transform * self.shape.support(local)
+    }
+}
+
+impl<S: Support + ?Sized> Support for &S {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        (**self).support(direction)
+    }
+}
+
+/// Returns the point in `points` farthest along `direction`.
+fn farthest_point(points: &[Vector3], direction: Vector3) -> Vector3 {
+    points.iter()
+        .cloned()
+        .fold(None, |best: Option<(Vector3, f32)>, p| {
+            let d = Vector3::dot(p, direction);
+            match best {
+                Some((_, best_d)) if best_d >= d => best,
+                _ => Some((p, d)),
+            }
+        })
+        .map(|(p, _)| p)
+        .unwrap_or_else(Vector3::zero)
+}
+
+/// The closest points between two separated convex shapes.
+#[derive(Debug, Copy, Clone)]
+pub struct GjkDistance {
+    /// The distance between the shapes.
+    pub distance: f32,
+    /// The closest point on the first shape.
+    pub point0: Vector3,
+    /// The closest point on the second shape.
+    pub point1: Vector3,
+}
+
+/// The penetration between two overlapping convex shapes.
+#[derive(Debug, Copy, Clone)]
+pub struct Penetration {
+    /// The distance the second shape must move along `normal` to separate the shapes.
+    pub depth: f32,
+    /// The unit contact normal pointing from the first shape towards the second.
+    pub normal: Vector3,
+    /// The deepest point of the first shape inside the second.
+    pub point0: Vector3,
+    /// The deepest point of the second shape inside the first.
+    pub point1: Vector3,
+}
+
+/// A point of the Minkowski difference `a - b` with the support points that produced it.
+#[derive(Debug, Copy, Clone)]
+struct SupportPoint {
+    v: Vector3,
+    a: Vector3,
+    b: Vector3,
+}
+
+fn support_point<A: Support, B: Support>(a: &A, b: &B, direction: Vector3) -> SupportPoint {
+    let pa = a.support(direction);
+    let pb = b.support(-direction);
+    SupportPoint { v: pa - pb, a: pa, b: pb }
+}
+
+/// The final state of a GJK run.
+struct Simplex {
+    points: Vec<SupportPoint>,
+    weights: Vec<f32>,
+    closest: Vector3,
+    intersecting: bool,
+}
+
+impl Simplex {
+    fn witness_points(&self) -> (Vector3, Vector3) {
+        self.points.iter().zip(self.weights.iter()).fold((Vector3::zero(), Vector3::zero()), |(pa, pb), (p, w)| {
+            (pa + p.a * *w, pb + p.b * *w)
+        })
+    }
+}
+
+/// Returns the closest points between the convex shapes `a` and `b`.
+/// Returns `None` if the shapes overlap.
+pub fn gjk_distance<A: Support, B: Support>(a: &A, b: &B) -> Option<GjkDistance> {
+    let simplex = gjk(a, b);
+    if simplex.intersecting {
+        return None;
+    }
+
+    let (point0, point1) = simplex.witness_points();
+    Some(GjkDistance { distance: simplex.closest.length(), point0, point1 })
+}
+
+/// Returns true if the convex shapes `a` and `b` overlap.
+pub fn gjk_intersects<A: Support, B: Support>(a: &A, b: &B) -> bool {
+    gjk(a, b).intersecting
+}
+
+/// Returns the penetration depth and contact normal of the convex shapes `a` and `b` using EPA.
+/// Returns `None` if the shapes do not overlap.
+pub fn epa_penetration<A: Support, B: Support>(a: &A, b: &B) -> Option<Penetration> {
+    let simplex = gjk(a, b);
+    if !simplex.intersecting {
+        return None;
+    }
+
+    let mut vertices = simplex.points;
+    if !expand_to_tetrahedron(a, b, &mut vertices) {
+        // The Minkowski difference is flat, so the shapes only touch.
+        return Some(touching_penetration(&vertices));
+    }
+
+    let center = vertices.iter().fold(Vector3::zero(), |c, p| c + p.v) / 4.0;
+    let mut faces = Vec::new();
+    for &(i, j, k) in [(0, 1, 2), (0, 3, 1), (0, 2, 3), (1, 3, 2)].iter() {
+        faces.push(EpaFace::new(&vertices, [i, j, k], center));
+    }
+
+    for _ in 0..MAX_ITERATIONS {
+        let face = closest_face(&faces);
+        if face.is_sliver() {
+            return Some(touching_penetration(&vertices));
+        }
+
+        let w = support_point(a, b, face.normal);
+        let gain = Vector3::dot(w.v, face.normal) - face.distance;
+        if gain <= TOLERANCE * face.distance.max(1.0) {
+            break;
+        }
+
+        // Remove every face the new point can see and stitch the hole closed around it.
+        let visible = visible_faces(&faces, &vertices, w.v);
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        for f in faces.iter().zip(visible.iter()).filter(|&(_, &v)| v).map(|(f, _)| f) {
+            for e in 0..3 {
+                let edge = (f.indices[e], f.indices[(e + 1) % 3]);
+                match horizon.iter().position(|h| *h == (edge.1, edge.0)) {
+                    Some(shared) => { horizon.swap_remove(shared); },
+                    None => horizon.push(edge),
+                }
+            }
+        }
+        let mut keep = visible.iter().map(|v| !v);
+        faces.retain(|_| keep.next().unwrap());
+
+        let index = vertices.len();
+        vertices.push(w);
+        for (i, j) in horizon {
+            faces.push(EpaFace::new(&vertices, [i, j, index], center));
+        }
+
+        if faces.is_empty() {
+            return None;
+        }
+    }
+
+    let face = closest_face(&faces);
+    let [i, j, k] = face.indices;
+    let closest = point_triangle_distance(face.normal * face.distance, vertices[i].v, vertices[j].v, vertices[k].v);
+    let weights = closest.barycentric;
+
+    Some(Penetration {
+        depth: face.distance,
+        normal: face.normal,
+        point0: vertices[i].a * weights.x + vertices[j].a * weights.y + vertices[k].a * weights.z,
+        point1: vertices[i].b * weights.x + vertices[j].b * weights.y + vertices[k].b * weights.z,
+    })
+}
+
+/// A face of the EPA polytope with an outward normal.
+#[derive(Debug, Copy, Clone)]
+struct EpaFace {
+    indices: [usize; 3],
+    normal: Vector3,
+    distance: f32,
+}
+
+impl EpaFace {
+    fn new(vertices: &[SupportPoint], indices: [usize; 3], center: Vector3) -> EpaFace {
+        let [i, j, k] = indices;
+        let (a, b, c) = (vertices[i].v, vertices[j].v, vertices[k].v);
+        let n = Vector3::cross(b - a, c - a);
+        let l = n.length();
+        if l <= f32::EPSILON {
+            // Sliver faces are kept to close the polytope but have no normal and are never chosen as the closest face.
+            return EpaFace { indices, normal: Vector3::zero(), distance: f32::INFINITY };
+        }
+
+        let mut normal = n / l;
+        let mut indices = indices;
+        if Vector3::dot(normal, a - center) < 0.0 {
+            normal = -normal;
+            indices.swap(1, 2);
+        }
+
+        EpaFace { indices, normal, distance: Vector3::dot(normal, a).max(0.0) }
+    }
+
+    fn is_sliver(&self) -> bool {
+        self.distance == f32::INFINITY
+    }
+
+    fn shares_edge(&self, other: &EpaFace) -> bool {
+        (0..3).any(|e| {
+            let (i, j) = (self.indices[e], self.indices[(e + 1) % 3]);
+            (0..3).any(|f| other.indices[f] == j && other.indices[(f + 1) % 3] == i)
+        })
+    }
+}
+
+/// Returns which faces of the polytope `point` can see.
+/// Sliver faces have no normal of their own, so they are seen when a face sharing an edge with them is,
+/// which keeps the removed region and its horizon free of holes.
+fn visible_faces(faces: &[EpaFace], vertices: &[SupportPoint], point: Vector3) -> Vec<bool> {
+    let mut visible: Vec<bool> = faces.iter()
+        .map(|f| !f.is_sliver() && Vector3::dot(f.normal, point - vertices[f.indices[0]].v) > 0.0)
+        .collect();
+
+    loop {
+        let mut changed = false;
+        for (i, f) in faces.iter().enumerate() {
+            if f.is_sliver() && !visible[i] && faces.iter().zip(visible.iter()).any(|(g, &v)| v && f.shares_edge(g)) {
+                visible[i] = true;
+                changed = true;
+            }
+        }
+        if !changed {
+            return visible;
+        }
+    }
+}
+
+/// Returns the contact of shapes whose Minkowski difference is flat, using the normal of its plane.
+fn touching_penetration(vertices: &[SupportPoint]) -> Penetration {
+    let p = vertices[0];
+    let mut normal = Vector3::up();
+    for (i, q) in vertices.iter().enumerate().skip(1) {
+        for r in &vertices[i + 1..] {
+            let n = Vector3::cross(q.v - p.v, r.v - p.v);
+            if n.length() > f32::EPSILON {
+                return Penetration { depth: 0.0, normal: n.normalized(), point0: p.a, point1: p.b };
+            }
+        }
+    }
+
+    // A segment or point has no single plane, so any direction perpendicular to it will do.
+    if let Some(q) = vertices.iter().find(|q| (q.v - p.v).length() > f32::EPSILON) {
+        let line = q.v - p.v;
+        let axis = if line.x.abs() < line.y.abs() { Vector3::left() } else { Vector3::up() };
+        normal = Vector3::cross(line, axis).normalized();
+    }
+    Penetration { depth: 0.0, normal, point0: p.a, point1: p.b }
+}
+
+/// Returns the face of the polytope closest to the origin.
+fn closest_face(faces: &[EpaFace]) -> EpaFace {
+    faces.iter().cloned().fold(faces[0], |best, f| if f.distance < best.distance { f } else { best })
+}
+
+/// Grows a simplex containing the origin into a tetrahedron of the Minkowski difference.
+/// Returns false if the Minkowski difference has no volume.
+fn expand_to_tetrahedron<A: Support, B: Support>(a: &A, b: &B, vertices: &mut Vec<SupportPoint>) -> bool {
+    let axes = [Vector3::left(), Vector3::up(), Vector3::forward()];
+    let epsilon = 1e-6;
+
+    if vertices.len() == 1 {
+        for d in axes.iter().flat_map(|a| vec![*a, -*a]) {
+            let p = support_point(a, b, d);
+            if (p.v - vertices[0].v).length() > epsilon {
+                vertices.push(p);
+                break;
+            }
+        }
+    }
+
+    if vertices.len() == 2 {
+        let line = (vertices[1].v - vertices[0].v).normalized();
+        let axis = axes.iter().cloned().fold(axes[0], |best, a| {
+            if Vector3::dot(a, line).abs() < Vector3::dot(best, line).abs() { a } else { best }
+        });
+        let perpendicular = Vector3::cross(line, axis).normalized();
+        for step in 0..6 {
+            let angle = step as f32 * std::f32::consts::PI / 3.0;
+            let d = Quaternion::from_axis_angle(line, angle) * perpendicular;
+            let p = support_point(a, b, d);
+            if point_segment_distance(p.v, vertices[0].v, vertices[1].v).distance > epsilon {
+                vertices.push(p);
+                break;
+            }
+        }
+    }
+
+    if vertices.len() == 3 {
+        let n = Vector3::cross(vertices[1].v - vertices[0].v, vertices[2].v - vertices[0].v).normalized();
+        for d in [n, -n].iter() {
+            let p = support_point(a, b, *d);
+            if Vector3::dot(p.v - vertices[0].v, n).abs() > epsilon {
+                vertices.push(p);
+                break;
+            }
+        }
+    }
+
+    vertices.len() == 4
+}
+
+/// Runs GJK on the Minkowski difference of `a` and `b`.
+fn gjk<A: Support, B: Support>(a: &A, b: &B) -> Simplex {
+    let first = support_point(a, b, Vector3::left());
+    let mut simplex = Simplex { points: vec![first], weights: vec![1.0], closest: first.v, intersecting: false };
+
+    for _ in 0..MAX_ITERATIONS {
+        let v = simplex.closest;
+        let v_length_squared = v.length_squared();
+
+        // Rounding error grows with the size of the simplex, so the origin is reached relative to it.
+        let scale = simplex.points.iter().fold(0.0f32, |s, p| s.max(p.v.length_squared()));
+        if v_length_squared <= TOLERANCE * TOLERANCE * scale {
+            simplex.intersecting = true;
+            return simplex;
+        }
+
+        let w = support_point(a, b, -v);
+        if v_length_squared - Vector3::dot(v, w.v) <= TOLERANCE * v_length_squared {
+            return simplex;
+        }
+        if simplex.points.iter().any(|p| (p.v - w.v).length_squared() <= f32::EPSILON * v_length_squared) {
+            return simplex;
+        }
+
+        let mut points = simplex.points.clone();
+        points.push(w);
+        let next = closest_on_simplex(points);
+        if next.intersecting {
+            return next;
+        }
+        if next.closest.length_squared() >= v_length_squared {
+            // No progress means rounding error dominates, so the current simplex is the answer.
+            return simplex;
+        }
+
+        simplex = next;
+    }
+
+    simplex
+}
+
+/// Returns the point of the simplex closest to the origin, keeping only the points needed to express it.
+fn closest_on_simplex(points: Vec<SupportPoint>) -> Simplex {
+    let reduce = |points: &[SupportPoint], weights: &[f32]| {
+        let mut simplex = Simplex { points: Vec::new(), weights: Vec::new(), closest: Vector3::zero(), intersecting: false };
+        for (p, w) in points.iter().zip(weights.iter()) {
+            if *w > 0.0 {
+                simplex.points.push(*p);
+                simplex.weights.push(*w);
+                simplex.closest = simplex.closest + p.v * *w;
+            }
+        }
+        simplex
+    };
+
+    match points.len() {
+        2 => {
+            let r = point_segment_distance(Vector3::zero(), points[0].v, points[1].v);
+            reduce(&points, &[1.0 - r.t, r.t])
+        },
+        3 => {
+            let r = point_triangle_distance(Vector3::zero(), points[0].v, points[1].v, points[2].v);
+            reduce(&points, &[r.barycentric.x, r.barycentric.y, r.barycentric.z])
+        },
+        4 => {
+            let faces = [(0, 1, 2, 3), (0, 1, 3, 2), (0, 2, 3, 1), (1, 2, 3, 0)];
+            let mut inside = true;
+            let mut best: Option<Simplex> = None;
+            for &(i, j, k, l) in faces.iter() {
+                let (pi, pj, pk, pl) = (points[i].v, points[j].v, points[k].v, points[l].v);
+                let n = Vector3::cross(pj - pi, pk - pi);
+                let side_origin = Vector3::dot(n, -pi);
+                let side_opposite = Vector3::dot(n, pl - pi);
+
+                // Flat tetrahedrons cannot contain the origin, so every face is a candidate.
+                if side_opposite != 0.0 && side_origin * side_opposite > 0.0 {
+                    continue;
+                }
+                inside = false;
+
+                let r = point_triangle_distance(Vector3::zero(), pi, pj, pk);
+                let candidate = reduce(&[points[i], points[j], points[k]],
+                                       &[r.barycentric.x, r.barycentric.y, r.barycentric.z]);
+                best = match best {
+                    Some(b) if b.closest.length_squared() <= candidate.closest.length_squared() => Some(b),
+                    _ => Some(candidate),
+                };
+            }
+
+            if inside {
+                return Simplex { points, weights: vec![0.25; 4], closest: Vector3::zero(), intersecting: true };
+            }
+
+            best.unwrap()
+        },
+        _ => reduce(&points, &[1.0]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gjk::*;
+    use vector3::Vector3;
+    use quaternion::Quaternion;
+    use aabb::Aabb;
+    use obb::Obb;
+    use sphere::Sphere;
+    use capsule::Capsule;
+    use std::f32::consts::FRAC_PI_4;
+
+    #[test]
+    fn test_gjk_distance_spheres() {
+        let a = Sphere::new(Vector3::zero(), 1.0);
+        let b = Sphere::new(Vector3::new(4.0, 0.0, 0.0), 1.0);
+        let d = gjk_distance(&a, &b).unwrap();
+
+        assert!((d.distance - 2.0).abs() < 0.001);
+        assert!((d.point0 - Vector3::new(1.0, 0.0, 0.0)).length() < 0.01);
+        assert!((d.point1 - Vector3::new(3.0, 0.0, 0.0)).length() < 0.01);
+        assert!(gjk_distance(&a, &Sphere::new(Vector3::new(1.5, 0.0, 0.0), 1.0)).is_none());
+    }
+
+    #[test]
+    fn test_gjk_distance_boxes() {
+        let a = Aabb::new(Vector3::zero(), Vector3::new(1.0, 1.0, 1.0));
+        let b = Obb::new(Vector3::new(3.0, 0.5, 0.5), Vector3::new(0.5, 0.5, 0.5),
+                         Quaternion::from_axis_angle(Vector3::forward(), FRAC_PI_4));
+        let d = gjk_distance(&a, &b).unwrap();
+
+        assert!((d.distance - (2.0 - 0.5f32.sqrt())).abs() < 0.001);
+        assert!(gjk_intersects(&a, &Aabb::new(Vector3::new(0.9, 0.9, 0.9), Vector3::new(2.0, 2.0, 2.0))));
+    }
+
+    #[test]
+    fn test_gjk_transformed() {
+        let cloud = ConvexPointCloud::new(Aabb::new(Vector3::zero(), Vector3::new(1.0, 1.0, 1.0)).corners().to_vec());
+        let moved = Transformed::from_position_orientation(&cloud, Vector3::new(5.0, 0.0, 0.0), Quaternion::identity());
+        let capsule = Capsule::new(Vector3::new(3.0, -5.0, 0.5), Vector3::new(3.0, 5.0, 0.5), 0.5);
+        let d = gjk_distance(&moved, &capsule).unwrap();
+
+        assert!((d.distance - 1.5).abs() < 0.001);
+        assert!(!gjk_intersects(&cloud, &capsule));
+    }
+
+    #[test]
+    fn test_epa_penetration() {
+        let a = Sphere::new(Vector3::zero(), 1.0);
+        let b = Sphere::new(Vector3::new(1.5, 0.0, 0.0), 1.0);
+        let p = epa_penetration(&a, &b).unwrap();
+
+        assert!((p.depth - 0.5).abs() < 0.01);
+        assert!((p.normal - Vector3::left()).length() < 0.05);
+        assert!(epa_penetration(&a, &Sphere::new(Vector3::new(3.0, 0.0, 0.0), 1.0)).is_none());
+    }
+
+    #[test]
+    fn test_epa_penetration_boxes() {
+        let a = Aabb::new(Vector3::zero(), Vector3::new(2.0, 2.0, 2.0));
+        let b = Aabb::new(Vector3::new(1.0, 1.75, 1.0), Vector3::new(3.0, 3.0, 3.0));
+        let p = epa_penetration(&a, &b).unwrap();
+
+        assert!((p.depth - 0.25).abs() < 0.001);
+        assert!((p.normal - Vector3::up()).length() < 0.001);
+    }
+
+    #[test]
+    fn test_epa_penetration_nearly_coplanar() {
+        let slab = |z: f32, thickness: f32| {
+            let points = Aabb::new(Vector3::new(0.0, 0.0, z), Vector3::new(2.0, 2.0, z + thickness)).corners().to_vec();
+            ConvexPointCloud::new(points)
+        };
+
+        // Two thin overlapping slabs give a Minkowski difference made almost entirely of slivers.
+        let p = epa_penetration(&slab(0.0, 1e-4), &Transformed::from_position_orientation(&slab(0.0, 1e-4), Vector3::new(0.5, 0.3, 0.0), Quaternion::identity())).unwrap();
+        assert!(p.depth.is_finite() && p.depth <= 2e-4);
+        assert!((p.normal.length() - 1.0).abs() < 0.001);
+        assert!(p.normal.z.abs() > 0.99);
+
+        // Slabs with no thickness only touch, along the normal of their shared plane.
+        let p = epa_penetration(&slab(1.0, 0.0), &Transformed::from_position_orientation(&slab(1.0, 0.0), Vector3::new(0.5, 0.3, 0.0), Quaternion::identity())).unwrap();
+        assert_eq!(p.depth, 0.0);
+        assert!(p.normal.z.abs() > 0.99);
+    }
+}
@@ -0,0 +1,704 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use vector3::Vector3;
+
+/// An error from building or validating a `HalfEdgeMesh`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HalfEdgeError {
+    /// A face refers to a vertex that does not exist.
+    InvalidIndex { face: usize, index: usize },
+    /// A face uses the same vertex more than once.
+    DegenerateFace { face: usize },
+    /// An edge is used by more than two faces or by two faces with opposite windings.
+    NonManifoldEdge { from: usize, to: usize },
+    /// The faces around a vertex do not form a single fan.
+    NonManifoldVertex { vertex: usize },
+    /// A half-edge's links are inconsistent with its neighbors.
+    BrokenLink { half_edge: usize },
+}
+
+impl fmt::Display for HalfEdgeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HalfEdgeError::InvalidIndex { face, index } => write!(f, "face {} refers to missing vertex {}", face, index),
+            HalfEdgeError::DegenerateFace { face } => write!(f, "face {} repeats a vertex", face),
+            HalfEdgeError::NonManifoldEdge { from, to } => write!(f, "edge from {} to {} is not manifold", from, to),
+            HalfEdgeError::NonManifoldVertex { vertex } => write!(f, "vertex {} is not manifold", vertex),
+            HalfEdgeError::BrokenLink { half_edge } => write!(f, "half-edge {} has inconsistent links", half_edge),
+        }
+    }
+}
+
+impl Error for HalfEdgeError {}
+
+/// One side of an edge, belonging to the face on its left or to a boundary loop.
+#[derive(Debug, Copy, Clone)]
+pub struct HalfEdge {
+    /// The vertex the half-edge starts from.
+    pub origin: usize,
+    /// The half-edge on the other side of the edge, going the opposite way.
+    pub twin: usize,
+    /// The next half-edge around the face or boundary loop.
+    pub next: usize,
+    /// The previous half-edge around the face or boundary loop.
+    pub prev: usize,
+    /// The face of the half-edge, or `None` if it is on a boundary.
+    pub face: Option<usize>,
+}
+
+/// A manifold triangle mesh with half-edge connectivity.
+/// Boundaries are closed with face-less half-edges so every edge has two sides.
+/// Removed elements keep their indices until the mesh is converted back with `to_indexed`.
+#[derive(Debug, Clone)]
+pub struct HalfEdgeMesh {
+    positions: Vec<Vector3>,
+    half_edges: Vec<HalfEdge>,
+    /// An outgoing half-edge of each vertex, a boundary one if the vertex is on a boundary.
+    vertex_edges: Vec<Option<usize>>,
+    face_edges: Vec<Option<usize>>,
+    removed_vertices: Vec<bool>,
+    removed_half_edges: Vec<bool>,
+}
+
+/// An iterator over the outgoing half-edges of a vertex.
+pub struct OutgoingHalfEdges<'a> {
+    mesh: &'a HalfEdgeMesh,
+    start: Option<usize>,
+    current: Option<usize>,
+}
+
+impl<'a> Iterator for OutgoingHalfEdges<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let current = self.current?;
+        let next = self.mesh.half_edges[self.mesh.half_edges[current].prev].twin;
+        self.current = if Some(next) == self.start { None } else { Some(next) };
+        Some(current)
+    }
+}
+
+impl HalfEdgeMesh {
+    /// Returns a `HalfEdgeMesh` of the triangles `indices` into `positions`.
+    /// Returns an error if a face is invalid or the triangles do not form an oriented manifold.
+    pub fn new(positions: Vec<Vector3>, indices: &[[usize; 3]]) -> Result<HalfEdgeMesh, HalfEdgeError> {
+        let mut half_edges = Vec::with_capacity(indices.len() * 3);
+        let mut directed: HashMap<(usize, usize), usize> = HashMap::new();
+
+        for (f, face) in indices.iter().enumerate() {
+            for &i in face.iter() {
+                if i >= positions.len() {
+                    return Err(HalfEdgeError::InvalidIndex { face: f, index: i });
+                }
+            }
+            if face[0] == face[1] || face[1] == face[2] || face[2] == face[0] {
+                return Err(HalfEdgeError::DegenerateFace { face: f });
+            }
+
+            let base = half_edges.len();
+            for corner in 0..3 {
+                let (from, to) = (face[corner], face[(corner + 1) % 3]);
+                if directed.insert((from, to), base + corner).is_some() {
+                    return Err(HalfEdgeError::NonManifoldEdge { from, to });
+                }
+
+                half_edges.push(HalfEdge {
+                    origin: from,
+                    twin: usize::MAX,
+                    next: base + (corner + 1) % 3,
+                    prev: base + (corner + 2) % 3,
+                    face: Some(f),
+                });
+            }
+        }
+
+        // Pair up interior edges and give boundary edges a face-less twin.
+        let mut boundary_from: HashMap<usize, usize> = HashMap::new();
+        for h in 0..half_edges.len() {
+            if half_edges[h].twin != usize::MAX {
+                continue;
+            }
+
+            let from = half_edges[h].origin;
+            let to = half_edges[half_edges[h].next].origin;
+            match directed.get(&(to, from)) {
+                Some(&t) => {
+                    half_edges[h].twin = t;
+                    half_edges[t].twin = h;
+                },
+                None => {
+                    let b = half_edges.len();
+                    half_edges.push(HalfEdge { origin: to, twin: h, next: usize::MAX, prev: usize::MAX, face: None });
+                    half_edges[h].twin = b;
+                    if boundary_from.insert(to, b).is_some() {
+                        return Err(HalfEdgeError::NonManifoldVertex { vertex: to });
+                    }
+                },
+            }
+        }
+
+        // Link each boundary half-edge to the one leaving the vertex it ends at.
+        for &b in boundary_from.values() {
+            let to = half_edges[half_edges[b].twin].origin;
+            let next = boundary_from[&to];
+            half_edges[b].next = next;
+            half_edges[next].prev = b;
+        }
+
+        let mut vertex_edges = vec![None; positions.len()];
+        for (h, half_edge) in half_edges.iter().enumerate() {
+            if vertex_edges[half_edge.origin].is_none() {
+                vertex_edges[half_edge.origin] = Some(h);
+            }
+        }
+        for (&v, &b) in &boundary_from {
+            vertex_edges[v] = Some(b);
+        }
+
+        let face_edges = (0..indices.len()).map(|f| Some(f * 3)).collect();
+        let mesh = HalfEdgeMesh {
+            removed_vertices: vec![false; positions.len()],
+            removed_half_edges: vec![false; half_edges.len()],
+            positions,
+            half_edges,
+            vertex_edges,
+            face_edges,
+        };
+
+        mesh.validate()?;
+        Ok(mesh)
+    }
+
+    /// Checks that every link is consistent and that the faces around each vertex form a single fan.
+    pub fn validate(&self) -> Result<(), HalfEdgeError> {
+        let mut outgoing = vec![0; self.positions.len()];
+        for (h, e) in self.half_edges.iter().enumerate() {
+            if self.removed_half_edges[h] {
+                continue;
+            }
+
+            let broken = Err(HalfEdgeError::BrokenLink { half_edge: h });
+            if self.removed_half_edges[e.twin] || self.removed_half_edges[e.next] || self.removed_half_edges[e.prev] {
+                return broken;
+            }
+            if self.half_edges[e.twin].twin != h || self.half_edges[e.next].prev != h || self.half_edges[e.prev].next != h {
+                return broken;
+            }
+            if self.half_edges[e.twin].origin != self.half_edges[e.next].origin || e.twin == h {
+                return broken;
+            }
+            if self.half_edges[e.next].face != e.face || self.removed_vertices[e.origin] {
+                return broken;
+            }
+            if let Some(f) = e.face {
+                if self.half_edges[self.half_edges[e.next].next].next != h || self.face_edges[f].is_none() {
+                    return broken;
+                }
+            }
+            outgoing[e.origin] += 1;
+        }
+
+        for (v, &count) in outgoing.iter().enumerate() {
+            if self.outgoing(v).count() != count {
+                return Err(HalfEdgeError::NonManifoldVertex { vertex: v });
+            }
+
+            let boundaries = self.outgoing(v).filter(|&h| self.half_edges[h].face.is_none()).count();
+            let starts_on_boundary = self.vertex_edges[v].map_or(false, |h| self.half_edges[h].face.is_none());
+            if boundaries > 1 || (boundaries == 1 && !starts_on_boundary) {
+                return Err(HalfEdgeError::NonManifoldVertex { vertex: v });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of vertex slots, including removed vertices.
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns the number of face slots, including removed faces.
+    pub fn face_count(&self) -> usize {
+        self.face_edges.len()
+    }
+
+    /// Returns the half-edge at `index`.
+    pub fn half_edge(&self, index: usize) -> &HalfEdge {
+        &self.half_edges[index]
+    }
+
+    /// Returns the position of vertex `v`.
+    pub fn position(&self, v: usize) -> Vector3 {
+        self.positions[v]
+    }
+
+    /// Sets the position of vertex `v`.
+    pub fn set_position(&mut self, v: usize, position: Vector3) {
+        self.positions[v] = position;
+    }
+
+    /// Returns true if vertex `v` has been removed by a collapse.
+    pub fn is_vertex_removed(&self, v: usize) -> bool {
+        self.removed_vertices[v]
+    }
+
+    /// Returns true if face `f` has been removed by a collapse.
+    pub fn is_face_removed(&self, f: usize) -> bool {
+        self.face_edges[f].is_none()
+    }
+
+    /// Returns the vertex half-edge `h` points to.
+    pub fn target(&self, h: usize) -> usize {
+        self.half_edges[self.half_edges[h].twin].origin
+    }
+
+    /// Returns the indices of the faces that have not been removed.
+    pub fn faces(&self) -> Vec<usize> {
+        (0..self.face_edges.len()).filter(|&f| self.face_edges[f].is_some()).collect()
+    }
+
+    /// Returns one half-edge of each edge that has not been removed.
+    pub fn edges(&self) -> Vec<usize> {
+        (0..self.half_edges.len())
+            .filter(|&h| !self.removed_half_edges[h] && h < self.half_edges[h].twin)
+            .collect()
+    }
+
+    /// Returns a half-edge of face `f`.
+    /// Panics if the face has been removed.
+    pub fn face_half_edge(&self, f: usize) -> usize {
+        self.face_edges[f].expect("face has been removed")
+    }
+
+    /// Returns the vertices of face `f` in counter-clockwise order.
+    pub fn face_vertices(&self, f: usize) -> [usize; 3] {
+        let h = self.face_half_edge(f);
+        let next = self.half_edges[h].next;
+        [self.half_edges[h].origin, self.half_edges[next].origin, self.half_edges[self.half_edges[next].next].origin]
+    }
+
+    /// Returns the half-edge from `from` to `to`, or `None` if they are not connected.
+    pub fn find_half_edge(&self, from: usize, to: usize) -> Option<usize> {
+        self.outgoing(from).find(|&h| self.target(h) == to)
+    }
+
+    /// Returns an iterator over the outgoing half-edges of vertex `v`, starting on the boundary if `v` is on one.
+    pub fn outgoing(&self, v: usize) -> OutgoingHalfEdges<'_> {
+        let start = if self.removed_vertices[v] { None } else { self.vertex_edges[v] };
+        OutgoingHalfEdges { mesh: self, start, current: start }
+    }
+
+    /// Returns the vertices connected to vertex `v` by an edge.
+    pub fn one_ring(&self, v: usize) -> Vec<usize> {
+        self.outgoing(v).map(|h| self.target(h)).collect()
+    }
+
+    /// Returns the faces around vertex `v`.
+    pub fn vertex_faces(&self, v: usize) -> Vec<usize> {
+        self.outgoing(v).filter_map(|h| self.half_edges[h].face).collect()
+    }
+
+    /// Returns the number of edges at vertex `v`.
+    pub fn valence(&self, v: usize) -> usize {
+        self.outgoing(v).count()
+    }
+
+    /// Returns true if vertex `v` is on a boundary.
+    pub fn is_boundary_vertex(&self, v: usize) -> bool {
+        self.vertex_edges[v].map_or(false, |h| self.half_edges[h].face.is_none())
+    }
+
+    /// Returns true if the edge of half-edge `h` is on a boundary.
+    pub fn is_boundary_edge(&self, h: usize) -> bool {
+        self.half_edges[h].face.is_none() || self.half_edges[self.half_edges[h].twin].face.is_none()
+    }
+
+    /// Returns the vertices of each boundary loop in order.
+    pub fn boundary_loops(&self) -> Vec<Vec<usize>> {
+        let mut visited = HashSet::new();
+        let mut loops = Vec::new();
+        for h in 0..self.half_edges.len() {
+            if self.removed_half_edges[h] || self.half_edges[h].face.is_some() || visited.contains(&h) {
+                continue;
+            }
+
+            let mut vertices = Vec::new();
+            let mut current = h;
+            loop {
+                visited.insert(current);
+                vertices.push(self.half_edges[current].origin);
+                current = self.half_edges[current].next;
+                if current == h {
+                    break;
+                }
+            }
+            loops.push(vertices);
+        }
+
+        loops
+    }
+
+    /// Points vertex `v` at a boundary half-edge if it has one, starting the search from `start`.
+    fn update_vertex_edge(&mut self, v: usize, start: usize) {
+        self.vertex_edges[v] = Some(start);
+        let boundary = self.outgoing(v).find(|&h| self.half_edges[h].face.is_none());
+        if boundary.is_some() {
+            self.vertex_edges[v] = boundary;
+        }
+    }
+
+    /// Replaces the edge of half-edge `h` between two triangles with the edge between their opposite vertices.
+    /// Returns false and leaves the mesh unchanged if the edge is on a boundary or the opposite vertices are already connected.
+    pub fn flip_edge(&mut self, h: usize) -> bool {
+        if self.is_boundary_edge(h) {
+            return false;
+        }
+
+        let t = self.half_edges[h].twin;
+        let (h1, h2) = (self.half_edges[h].next, self.half_edges[h].prev);
+        let (t1, t2) = (self.half_edges[t].next, self.half_edges[t].prev);
+        let (a, b) = (self.half_edges[h].origin, self.half_edges[t].origin);
+        let (c, d) = (self.half_edges[h2].origin, self.half_edges[t2].origin);
+        if c == d || self.find_half_edge(c, d).is_some() {
+            return false;
+        }
+
+        let (f0, f1) = (self.half_edges[h].face, self.half_edges[t].face);
+        self.half_edges[h].origin = d;
+        self.half_edges[t].origin = c;
+
+        // The two triangles become (d, c, a) and (c, d, b).
+        for &(e, next, face) in [(h, h2, f0), (h2, t1, f0), (t1, h, f0), (t, t2, f1), (t2, h1, f1), (h1, t, f1)].iter() {
+            self.half_edges[e].next = next;
+            self.half_edges[next].prev = e;
+            self.half_edges[e].face = face;
+        }
+        self.face_edges[f0.unwrap()] = Some(h);
+        self.face_edges[f1.unwrap()] = Some(t);
+
+        if self.vertex_edges[a] == Some(h) {
+            self.vertex_edges[a] = Some(t1);
+        }
+        if self.vertex_edges[b] == Some(t) {
+            self.vertex_edges[b] = Some(h1);
+        }
+        true
+    }
+
+    /// Adds a half-edge with placeholder links and returns its index.
+    fn add_half_edge(&mut self, origin: usize, face: Option<usize>) -> usize {
+        self.half_edges.push(HalfEdge { origin, twin: usize::MAX, next: usize::MAX, prev: usize::MAX, face });
+        self.removed_half_edges.push(false);
+        self.half_edges.len() - 1
+    }
+
+    /// Links `edges` into a loop around `face`.
+    fn link_loop(&mut self, edges: &[usize], face: Option<usize>) {
+        for i in 0..edges.len() {
+            let (e, next) = (edges[i], edges[(i + 1) % edges.len()]);
+            self.half_edges[e].next = next;
+            self.half_edges[next].prev = e;
+            self.half_edges[e].face = face;
+        }
+        if let Some(f) = face {
+            self.face_edges[f] = Some(edges[0]);
+        }
+    }
+
+    /// Makes `h0` and `h1` twins.
+    fn pair(&mut self, h0: usize, h1: usize) {
+        self.half_edges[h0].twin = h1;
+        self.half_edges[h1].twin = h0;
+    }
+
+    /// Splits the edge of half-edge `h` at `position`, dividing the triangles on either side in two.
+    /// Returns the new vertex.
+    pub fn split_edge(&mut self, h: usize, position: Vector3) -> usize {
+        let t = self.half_edges[h].twin;
+        let m = self.positions.len();
+        self.positions.push(position);
+        self.vertex_edges.push(None);
+        self.removed_vertices.push(false);
+
+        // `h` becomes a to m and `t` becomes b to m, with new twins m to b and m to a.
+        let (hf, tf) = (self.half_edges[h].face, self.half_edges[t].face);
+        let h_twin = self.add_half_edge(m, tf);
+        let t_twin = self.add_half_edge(m, hf);
+        self.pair(h, h_twin);
+        self.pair(t, t_twin);
+
+        let mut m_edge = t_twin;
+        for &(e, e_new, f) in [(h, t_twin, hf), (t, h_twin, tf)].iter() {
+            let (next, prev) = (self.half_edges[e].next, self.half_edges[e].prev);
+            match f {
+                Some(f) => {
+                    // The triangle (from, to, c) becomes (from, m, c) and (m, to, c).
+                    let c = self.half_edges[prev].origin;
+                    let g = self.face_edges.len();
+                    self.face_edges.push(None);
+                    let to_c = self.add_half_edge(m, Some(f));
+                    let c_to = self.add_half_edge(c, Some(g));
+                    self.pair(to_c, c_to);
+                    self.link_loop(&[e, to_c, prev], Some(f));
+                    self.link_loop(&[e_new, next, c_to], Some(g));
+                },
+                None => {
+                    self.half_edges[e].next = e_new;
+                    self.half_edges[e_new].prev = e;
+                    self.half_edges[e_new].next = next;
+                    self.half_edges[next].prev = e_new;
+                    self.half_edges[e_new].face = None;
+                    m_edge = e_new;
+                },
+            }
+        }
+
+        self.vertex_edges[m] = Some(m_edge);
+        m
+    }
+
+    /// Returns true if the edge of half-edge `h` can be collapsed without breaking the manifold.
+    pub fn can_collapse(&self, h: usize) -> bool {
+        let t = self.half_edges[h].twin;
+        let (a, b) = (self.half_edges[h].origin, self.half_edges[t].origin);
+
+        // The vertices opposite the edge must be the only neighbors the ends share.
+        let mut opposite = Vec::new();
+        for &e in [h, t].iter() {
+            if self.half_edges[e].face.is_some() {
+                let prev = self.half_edges[e].prev;
+                let next = self.half_edges[e].next;
+                opposite.push(self.half_edges[prev].origin);
+
+                // A triangle with both other edges on the boundary would vanish completely.
+                if self.is_boundary_edge(prev) && self.is_boundary_edge(next) {
+                    return false;
+                }
+            }
+        }
+
+        let ring_a = self.one_ring(a);
+        let ring_b = self.one_ring(b);
+        let shared = ring_a.iter().filter(|v| ring_b.contains(v)).count();
+        if shared != opposite.len() || !opposite.iter().all(|v| ring_a.contains(v) && ring_b.contains(v)) {
+            return false;
+        }
+
+        // Collapsing an interior edge between two boundary vertices would pinch the surface.
+        if !self.is_boundary_edge(h) && self.is_boundary_vertex(a) && self.is_boundary_vertex(b) {
+            return false;
+        }
+
+        // A tetrahedron cannot lose any more vertices.
+        if opposite.len() == 2 && ring_a.len() + ring_b.len() - shared - 2 < 3 {
+            return false;
+        }
+
+        true
+    }
+
+    /// Collapses the edge of half-edge `h` by merging its origin into its target, which moves to `position`.
+    /// Returns the remaining vertex, or `None` and leaves the mesh unchanged if the collapse is not allowed.
+    pub fn collapse_edge(&mut self, h: usize, position: Vector3) -> Option<usize> {
+        if !self.can_collapse(h) {
+            return None;
+        }
+
+        let t = self.half_edges[h].twin;
+        let (a, b) = (self.half_edges[h].origin, self.half_edges[t].origin);
+        let from_a: Vec<usize> = self.outgoing(a).collect();
+
+        let mut touched = Vec::new();
+        for &e in [h, t].iter() {
+            let (next, prev) = (self.half_edges[e].next, self.half_edges[e].prev);
+            match self.half_edges[e].face {
+                Some(f) => {
+                    // Remove the triangle and join the twins of its two other edges.
+                    let (x, y) = (self.half_edges[next].twin, self.half_edges[prev].twin);
+                    self.pair(x, y);
+                    self.face_edges[f] = None;
+                    for &r in [next, prev].iter() {
+                        self.removed_half_edges[r] = true;
+                    }
+
+                    let c = self.half_edges[prev].origin;
+                    touched.push((c, x));
+                    touched.push((self.half_edges[x].origin, x));
+                    touched.push((self.half_edges[y].origin, y));
+                },
+                None => {
+                    self.half_edges[prev].next = next;
+                    self.half_edges[next].prev = prev;
+                    touched.push((self.half_edges[prev].origin, prev));
+                    touched.push((self.half_edges[next].origin, next));
+                },
+            }
+        }
+        self.removed_half_edges[h] = true;
+        self.removed_half_edges[t] = true;
+
+        for e in from_a {
+            self.half_edges[e].origin = b;
+        }
+        self.removed_vertices[a] = true;
+        self.vertex_edges[a] = None;
+        self.positions[b] = position;
+
+        for (v, start) in touched {
+            let v = if v == a { b } else { v };
+            if !self.removed_half_edges[start] {
+                self.update_vertex_edge(v, start);
+            }
+        }
+
+        Some(b)
+    }
+
+    /// Returns the positions of the remaining vertices and the indices of the remaining faces into them.
+    pub fn to_indexed(&self) -> (Vec<Vector3>, Vec<[usize; 3]>) {
+        let mut remap = vec![usize::MAX; self.positions.len()];
+        let mut positions = Vec::new();
+        for (v, index) in remap.iter_mut().enumerate() {
+            if !self.removed_vertices[v] {
+                *index = positions.len();
+                positions.push(self.positions[v]);
+            }
+        }
+
+        let indices = self.faces().into_iter()
+            .map(|f| {
+                let [v0, v1, v2] = self.face_vertices(f);
+                [remap[v0], remap[v1], remap[v2]]
+            })
+            .collect();
+
+        (positions, indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use half_edge::{HalfEdgeMesh, HalfEdgeError};
+    use vector3::Vector3;
+
+    /// A 3 by 3 grid of vertices in the xy plane split into 8 triangles.
+    fn grid() -> HalfEdgeMesh {
+        let mut positions = Vec::new();
+        for y in 0..3 {
+            for x in 0..3 {
+                positions.push(Vector3::new(x as f32, y as f32, 0.0));
+            }
+        }
+
+        let mut indices = Vec::new();
+        for y in 0..2 {
+            for x in 0..2 {
+                let i = y * 3 + x;
+                indices.push([i, i + 1, i + 4]);
+                indices.push([i, i + 4, i + 3]);
+            }
+        }
+
+        HalfEdgeMesh::new(positions, &indices).unwrap()
+    }
+
+    fn tetrahedron() -> HalfEdgeMesh {
+        let positions = vec![Vector3::zero(), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0)];
+        HalfEdgeMesh::new(positions, &[[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]]).unwrap()
+    }
+
+    #[test]
+    fn test_half_edge_topology() {
+        let mesh = grid();
+
+        let mut ring = mesh.one_ring(4);
+        ring.sort();
+        assert_eq!(ring, vec![0, 1, 3, 5, 7, 8]);
+        assert!(!mesh.is_boundary_vertex(4));
+        assert!(mesh.is_boundary_vertex(0));
+        assert_eq!(mesh.valence(0), 3);
+        assert_eq!(mesh.vertex_faces(0).len(), 2);
+        assert_eq!(mesh.boundary_loops().len(), 1);
+        assert_eq!(mesh.boundary_loops()[0].len(), 8);
+        assert_eq!(mesh.edges().len(), 16);
+
+        assert!(tetrahedron().boundary_loops().is_empty());
+    }
+
+    #[test]
+    fn test_half_edge_invalid() {
+        let positions = vec![Vector3::zero(); 5];
+        assert_eq!(HalfEdgeMesh::new(positions.clone(), &[[0, 1, 5]]).unwrap_err(), HalfEdgeError::InvalidIndex { face: 0, index: 5 });
+        assert_eq!(HalfEdgeMesh::new(positions.clone(), &[[0, 1, 1]]).unwrap_err(), HalfEdgeError::DegenerateFace { face: 0 });
+        assert_eq!(HalfEdgeMesh::new(positions.clone(), &[[0, 1, 2], [0, 1, 3]]).unwrap_err(),
+                   HalfEdgeError::NonManifoldEdge { from: 0, to: 1 });
+
+        // Two triangles touching at a single vertex.
+        match HalfEdgeMesh::new(positions, &[[0, 1, 2], [0, 3, 4]]) {
+            Err(HalfEdgeError::NonManifoldVertex { vertex: 0 }) => {},
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_half_edge_flip() {
+        let mut mesh = grid();
+        let h = mesh.find_half_edge(0, 4).unwrap();
+
+        assert!(mesh.flip_edge(h));
+        assert!(mesh.validate().is_ok());
+        assert!(mesh.find_half_edge(0, 4).is_none());
+        assert!(mesh.find_half_edge(1, 3).is_some());
+        assert_eq!(mesh.valence(4), 5);
+
+        let boundary = mesh.find_half_edge(0, 1).unwrap();
+        assert!(!mesh.flip_edge(boundary));
+    }
+
+    #[test]
+    fn test_half_edge_split() {
+        let mut mesh = grid();
+
+        let interior = mesh.find_half_edge(1, 4).unwrap();
+        let m = mesh.split_edge(interior, Vector3::new(1.0, 0.5, 0.0));
+        assert!(mesh.validate().is_ok());
+        assert_eq!(mesh.valence(m), 4);
+        assert_eq!(mesh.faces().len(), 10);
+
+        let boundary = mesh.find_half_edge(0, 1).unwrap();
+        let m = mesh.split_edge(boundary, Vector3::new(0.5, 0.0, 0.0));
+        assert!(mesh.validate().is_ok());
+        assert!(mesh.is_boundary_vertex(m));
+        assert_eq!(mesh.valence(m), 3);
+        assert_eq!(mesh.faces().len(), 11);
+    }
+
+    #[test]
+    fn test_half_edge_collapse() {
+        let mut mesh = grid();
+
+        // The centre vertex merges into a corner of the grid.
+        let h = mesh.find_half_edge(4, 8).unwrap();
+        assert!(mesh.can_collapse(h));
+        assert_eq!(mesh.collapse_edge(h, Vector3::new(2.0, 2.0, 0.0)), Some(8));
+        assert!(mesh.validate().is_ok());
+        assert!(mesh.is_vertex_removed(4));
+
+        let (positions, indices) = mesh.to_indexed();
+        assert_eq!(positions.len(), 8);
+        assert_eq!(indices.len(), 6);
+
+        // Both ends are on the boundary but the edge is not.
+        let mut mesh = grid();
+        let h = mesh.find_half_edge(1, 4).unwrap();
+        mesh.collapse_edge(h, Vector3::new(1.0, 0.5, 0.0)).unwrap();
+        let h = mesh.find_half_edge(3, 4).unwrap();
+        assert!(mesh.collapse_edge(h, Vector3::zero()).is_none());
+
+        let mut mesh = tetrahedron();
+        let h = mesh.find_half_edge(0, 1).unwrap();
+        assert!(mesh.collapse_edge(h, Vector3::zero()).is_none());
+    }
+}
@@ -1,13 +1,71 @@
+pub mod vector2;
 pub mod vector2i;
 pub mod vector3;
+pub mod vector3i;
 pub mod vector4;
 pub mod matrix4;
 pub mod quaternion;
 pub mod ray;
+pub mod plane;
+pub mod aabb;
+pub mod frustum;
+pub mod sphere;
+pub mod obb;
+pub mod distance;
+pub mod triangle;
+pub mod capsule;
+pub mod gjk;
+pub mod convex_hull;
+pub mod sweep;
+pub mod mesh;
+pub mod obj;
+pub mod ply;
+pub mod stl;
+pub mod half_edge;
+pub mod subdivision;
+pub mod simplify;
+pub mod raster;
+pub mod clip;
+pub mod viewport;
+pub mod rect2i;
+pub mod line;
+pub mod voxel;
+pub mod binning;
+pub mod msaa;
+pub mod conservative;
 
+pub use self::vector2::*;
 pub use self::vector2i::*;
 pub use self::vector3::*;
+pub use self::vector3i::*;
 pub use self::vector4::*;
 pub use self::matrix4::*;
 pub use self::quaternion::*;
 pub use self::ray::*;
+pub use self::plane::*;
+pub use self::aabb::*;
+pub use self::frustum::*;
+pub use self::sphere::*;
+pub use self::obb::*;
+pub use self::distance::*;
+pub use self::triangle::*;
+pub use self::capsule::*;
+pub use self::gjk::*;
+pub use self::convex_hull::*;
+pub use self::sweep::*;
+pub use self::mesh::*;
+pub use self::obj::*;
+pub use self::ply::*;
+pub use self::stl::*;
+pub use self::half_edge::*;
+pub use self::subdivision::*;
+pub use self::simplify::*;
+pub use self::raster::*;
+pub use self::clip::*;
+pub use self::viewport::*;
+pub use self::rect2i::*;
+pub use self::line::*;
+pub use self::voxel::*;
+pub use self::binning::*;
+pub use self::msaa::*;
+pub use self::conservative::*;
@@ -0,0 +1,489 @@
+use vector2::Vector2;
+use vector2i::Vector2i;
+use raster::{rasterize_triangle, RasterOptions, TriangleRaster};
+use std::iter::Chain;
+
+/// An iterator over the pixels of a Bresenham line, from the first endpoint to the second.
+#[derive(Debug, Clone)]
+pub struct BresenhamLine {
+    p: Vector2i,
+    end: Vector2i,
+    dx: i32,
+    dy: i32,
+    step: Vector2i,
+    error: i32,
+    done: bool,
+}
+
+impl Iterator for BresenhamLine {
+    type Item = Vector2i;
+
+    fn next(&mut self) -> Option<Vector2i> {
+        if self.done {
+            return None;
+        }
+
+        let p = self.p;
+        if p == self.end {
+            self.done = true;
+            return Some(p);
+        }
+
+        let e2 = 2 * self.error;
+        if e2 >= self.dy {
+            self.error += self.dy;
+            self.p.x += self.step.x;
+        }
+        if e2 <= self.dx {
+            self.error += self.dx;
+            self.p.y += self.step.y;
+        }
+        Some(p)
+    }
+}
+
+/// Returns an iterator over the 8-connected pixels of the line from `p0` to `p1`, including both endpoints.
+pub fn bresenham_line(p0: Vector2i, p1: Vector2i) -> BresenhamLine {
+    let dx = (p1.x - p0.x).abs();
+    let dy = -(p1.y - p0.y).abs();
+
+    BresenhamLine {
+        p: p0,
+        end: p1,
+        dx,
+        dy,
+        step: Vector2i::new((p1.x - p0.x).signum(), (p1.y - p0.y).signum()),
+        error: dx + dy,
+        done: false,
+    }
+}
+
+/// An iterator over the pixels of a DDA line.
+#[derive(Debug, Clone)]
+pub struct DdaLine {
+    start: Vector2,
+    delta: Vector2,
+    steps: u32,
+    i: u32,
+}
+
+impl Iterator for DdaLine {
+    type Item = Vector2i;
+
+    fn next(&mut self) -> Option<Vector2i> {
+        if self.i > self.steps {
+            return None;
+        }
+
+        let p = self.start + self.delta * self.i as f32;
+        self.i += 1;
+        Some(Vector2i::new(p.x.floor() as i32, p.y.floor() as i32))
+    }
+}
+
+/// Returns an iterator over the pixels of the line from `p0` to `p1` using a digital differential analyzer.
+/// Pixel centers lie at half-integer coordinates.
+pub fn dda_line(p0: Vector2, p1: Vector2) -> DdaLine {
+    let d = p1 - p0;
+    let steps = d.x.abs().max(d.y.abs()).ceil() as u32;
+
+    DdaLine {
+        start: p0,
+        delta: if steps == 0 { Vector2::zero() } else { d / steps as f32 },
+        steps,
+        i: 0,
+    }
+}
+
+/// An iterator over the pixels of a thick line.
+#[derive(Debug, Clone)]
+pub struct ThickLine {
+    triangles: Chain<TriangleRaster, TriangleRaster>,
+}
+
+impl Iterator for ThickLine {
+    type Item = Vector2i;
+
+    fn next(&mut self) -> Option<Vector2i> {
+        self.triangles.next().map(|fragment| fragment.position)
+    }
+}
+
+/// Returns an iterator over the pixels of a `width` pixel wide line between the centers of `p0` and `p1`.
+/// The line has square caps extending half its width past each endpoint, and every pixel is visited once.
+pub fn thick_line(p0: Vector2i, p1: Vector2i, width: f32) -> ThickLine {
+    let center = |p: Vector2i| Vector2::new(p.x as f32 + 0.5, p.y as f32 + 0.5);
+    let (c0, c1) = (center(p0), center(p1));
+    let length = (c1 - c0).length();
+    let direction = if length > 0.0 { (c1 - c0) / length } else { Vector2::new(1.0, 0.0) };
+
+    let half = width * 0.5;
+    let along = direction * half;
+    let across = Vector2::new(-direction.y, direction.x) * half;
+    let a = c0 - along + across;
+    let b = c1 + along + across;
+    let c = c1 + along - across;
+    let d = c0 - along - across;
+
+    let options = RasterOptions::default();
+    ThickLine {
+        triangles: rasterize_triangle(a, b, c, &options).chain(rasterize_triangle(a, c, d, &options)),
+    }
+}
+
+/// A pixel of an anti-aliased line and the fraction of it the line covers.
+#[derive(Debug, Copy, Clone)]
+pub struct WuPixel {
+    pub position: Vector2i,
+    pub coverage: f32,
+}
+
+/// An iterator over the pixels of a Xiaolin Wu anti-aliased line.
+#[derive(Debug, Clone)]
+pub struct WuLine {
+    steep: bool,
+    gradient: f32,
+    x: i32,
+    x_start: i32,
+    x_end: i32,
+    y: f32,
+    start: [(i32, f32); 2],
+    end: [(i32, f32); 2],
+    pending: Vec<WuPixel>,
+}
+
+impl WuLine {
+    fn push(&mut self, x: i32, (y, coverage): (i32, f32)) {
+        if coverage > 0.0 {
+            let position = if self.steep { Vector2i::new(y, x) } else { Vector2i::new(x, y) };
+            self.pending.push(WuPixel { position, coverage });
+        }
+    }
+}
+
+impl Iterator for WuLine {
+    type Item = WuPixel;
+
+    fn next(&mut self) -> Option<WuPixel> {
+        while self.pending.is_empty() {
+            if self.x > self.x_end {
+                return None;
+            }
+
+            let x = self.x;
+            let pair = if x == self.x_start {
+                self.start
+            } else if x == self.x_end {
+                self.end
+            } else {
+                let y = self.y.floor();
+                let f = self.y - y;
+                self.y += self.gradient;
+                [(y as i32, 1.0 - f), (y as i32 + 1, f)]
+            };
+            self.x += 1;
+
+            // Pushed in reverse so the pixels come out in order along the minor axis.
+            self.push(x, pair[1]);
+            self.push(x, pair[0]);
+        }
+
+        self.pending.pop()
+    }
+}
+
+/// Returns an iterator over the pixels of the anti-aliased line from `p0` to `p1` with their coverage.
+/// Pixel centers lie at half-integer coordinates.
+pub fn wu_line(p0: Vector2, p1: Vector2) -> WuLine {
+    // Work with pixel centers at integer coordinates and x as the major axis.
+    let (mut a, mut b) = (p0 - Vector2::new(0.5, 0.5), p1 - Vector2::new(0.5, 0.5));
+    let steep = (b.y - a.y).abs() > (b.x - a.x).abs();
+    if steep {
+        a = Vector2::new(a.y, a.x);
+        b = Vector2::new(b.y, b.x);
+    }
+    if a.x > b.x {
+        std::mem::swap(&mut a, &mut b);
+    }
+
+    let dx = b.x - a.x;
+    let gradient = if dx == 0.0 { 1.0 } else { (b.y - a.y) / dx };
+    let fract = |v: f32| v - v.floor();
+
+    // Returns the column of an endpoint, the line height there and the two pixels it covers.
+    let endpoint = |p: Vector2, gap: f32| {
+        let x = p.x.round();
+        let y = p.y + gradient * (x - p.x);
+        let f = fract(y);
+        (x as i32, y, [(y.floor() as i32, (1.0 - f) * gap), (y.floor() as i32 + 1, f * gap)])
+    };
+
+    let (x_start, y_start, mut start) = endpoint(a, 1.0 - fract(a.x + 0.5));
+    let (x_end, _, end) = endpoint(b, fract(b.x + 0.5));
+    if x_start == x_end {
+        // Both endpoints fall in one column, which the line only partly covers.
+        start = endpoint(a, dx).2;
+    }
+
+    WuLine {
+        steep,
+        gradient,
+        x: x_start,
+        x_start,
+        x_end,
+        y: y_start + gradient,
+        start,
+        end,
+        pending: Vec::with_capacity(2),
+    }
+}
+
+/// An iterator over the pixels of a midpoint circle.
+#[derive(Debug, Clone)]
+pub struct MidpointCircle {
+    center: Vector2i,
+    x: i32,
+    y: i32,
+    error: i32,
+    pending: Vec<Vector2i>,
+}
+
+impl Iterator for MidpointCircle {
+    type Item = Vector2i;
+
+    fn next(&mut self) -> Option<Vector2i> {
+        while self.pending.is_empty() {
+            if self.x < self.y {
+                return None;
+            }
+
+            let (x, y) = (self.x, self.y);
+            for &(px, py) in [(x, y), (y, x), (-y, x), (-x, y), (-x, -y), (-y, -x), (y, -x), (x, -y)].iter() {
+                push_unique(&mut self.pending, self.center + Vector2i::new(px, py));
+            }
+
+            self.y += 1;
+            if self.error < 0 {
+                self.error += 2 * self.y + 1;
+            } else {
+                self.x -= 1;
+                self.error += 2 * (self.y - self.x) + 1;
+            }
+        }
+
+        self.pending.pop()
+    }
+}
+
+/// Returns an iterator over the pixels of the circle of `radius` around `center`. Each pixel is visited once.
+pub fn midpoint_circle(center: Vector2i, radius: i32) -> MidpointCircle {
+    MidpointCircle {
+        center,
+        x: radius,
+        y: 0,
+        error: 1 - radius,
+        pending: Vec::with_capacity(8),
+    }
+}
+
+/// An iterator over the pixels of a midpoint ellipse.
+#[derive(Debug, Clone)]
+pub struct MidpointEllipse {
+    center: Vector2i,
+    rx2: i64,
+    ry2: i64,
+    x: i64,
+    y: i64,
+    px: i64,
+    py: i64,
+    decision: f64,
+    first_region: bool,
+    pending: Vec<Vector2i>,
+    /// The run of pixels drawn instead when a radius is 0.
+    flat: Option<BresenhamLine>,
+}
+
+impl Iterator for MidpointEllipse {
+    type Item = Vector2i;
+
+    fn next(&mut self) -> Option<Vector2i> {
+        if let Some(ref mut flat) = self.flat {
+            return flat.next();
+        }
+
+        while self.pending.is_empty() {
+            if self.first_region && self.px >= self.py {
+                // Switch to stepping along y once the slope passes -1.
+                self.first_region = false;
+                let x = self.x as f64 + 0.5;
+                let y = (self.y - 1) as f64;
+                self.decision = self.ry2 as f64 * x * x + self.rx2 as f64 * y * y - (self.rx2 * self.ry2) as f64;
+            }
+            if self.y < 0 {
+                return None;
+            }
+
+            let (x, y) = (self.x as i32, self.y as i32);
+            for &(px, py) in [(x, y), (-x, y), (-x, -y), (x, -y)].iter() {
+                push_unique(&mut self.pending, self.center + Vector2i::new(px, py));
+            }
+
+            if self.first_region {
+                self.x += 1;
+                self.px += 2 * self.ry2;
+                if self.decision < 0.0 {
+                    self.decision += (self.ry2 + self.px) as f64;
+                } else {
+                    self.y -= 1;
+                    self.py -= 2 * self.rx2;
+                    self.decision += (self.ry2 + self.px - self.py) as f64;
+                }
+            } else {
+                self.y -= 1;
+                self.py -= 2 * self.rx2;
+                if self.decision > 0.0 {
+                    self.decision += (self.rx2 - self.py) as f64;
+                } else {
+                    self.x += 1;
+                    self.px += 2 * self.ry2;
+                    self.decision += (self.rx2 - self.py + self.px) as f64;
+                }
+            }
+        }
+
+        self.pending.pop()
+    }
+}
+
+/// Returns an iterator over the pixels of the axis aligned ellipse with radii `rx` and `ry` around `center`.
+/// Each pixel is visited once. An ellipse with a radius of 0 is the line between its extremes.
+pub fn midpoint_ellipse(center: Vector2i, rx: i32, ry: i32) -> MidpointEllipse {
+    let flat = if rx == 0 || ry == 0 {
+        Some(bresenham_line(center - Vector2i::new(rx, ry), center + Vector2i::new(rx, ry)))
+    } else {
+        None
+    };
+    let rx2 = rx as i64 * rx as i64;
+    let ry2 = ry as i64 * ry as i64;
+
+    MidpointEllipse {
+        center,
+        rx2,
+        ry2,
+        x: 0,
+        y: ry as i64,
+        px: 0,
+        py: 2 * rx2 * ry as i64,
+        decision: ry2 as f64 - (rx2 * ry as i64) as f64 + 0.25 * rx2 as f64,
+        first_region: true,
+        pending: Vec::with_capacity(4),
+        flat,
+    }
+}
+
+fn push_unique(points: &mut Vec<Vector2i>, p: Vector2i) {
+    if !points.contains(&p) {
+        points.push(p);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use line::{bresenham_line, dda_line, thick_line, wu_line, midpoint_circle, midpoint_ellipse};
+    use std::collections::HashSet;
+    use vector2::Vector2;
+    use vector2i::Vector2i;
+
+    fn unique(points: &[Vector2i]) -> bool {
+        let set: HashSet<(i32, i32)> = points.iter().map(|p| (p.x, p.y)).collect();
+        set.len() == points.len()
+    }
+
+    #[test]
+    fn test_line_bresenham_and_dda() {
+        let ends = [(Vector2i::new(0, 0), Vector2i::new(10, 3)),
+                    (Vector2i::new(5, 9), Vector2i::new(-2, -4)),
+                    (Vector2i::new(3, 3), Vector2i::new(3, -3)),
+                    (Vector2i::new(1, 1), Vector2i::new(1, 1))];
+
+        for &(p0, p1) in ends.iter() {
+            let line: Vec<Vector2i> = bresenham_line(p0, p1).collect();
+            let major = (p1.x - p0.x).abs().max((p1.y - p0.y).abs());
+            assert_eq!(line.len() as i32, major + 1);
+            assert_eq!(line[0], p0);
+            assert_eq!(line[line.len() - 1], p1);
+            assert!(line.windows(2).all(|w| (w[1].x - w[0].x).abs() <= 1 && (w[1].y - w[0].y).abs() <= 1));
+
+            let center = |p: Vector2i| Vector2::new(p.x as f32 + 0.5, p.y as f32 + 0.5);
+            let dda: Vec<Vector2i> = dda_line(center(p0), center(p1)).collect();
+            assert_eq!(dda.len(), line.len());
+            assert_eq!(dda[0], p0);
+            assert_eq!(dda[dda.len() - 1], p1);
+        }
+    }
+
+    #[test]
+    fn test_line_thick() {
+        let pixels: Vec<Vector2i> = thick_line(Vector2i::new(0, 0), Vector2i::new(10, 0), 3.0).collect();
+        assert!(unique(&pixels));
+        assert_eq!(pixels.len(), 13 * 3);
+        assert!(pixels.iter().all(|p| p.x >= -1 && p.x <= 11 && p.y >= -1 && p.y <= 1));
+
+        let diagonal: Vec<Vector2i> = thick_line(Vector2i::new(0, 0), Vector2i::new(20, 20), 4.0).collect();
+        assert!(unique(&diagonal));
+        assert!(bresenham_line(Vector2i::new(0, 0), Vector2i::new(20, 20)).all(|p| diagonal.contains(&p)));
+
+        assert_eq!(thick_line(Vector2i::new(4, 4), Vector2i::new(4, 4), 2.0).count(), 4);
+    }
+
+    #[test]
+    fn test_line_wu() {
+        let pixels: Vec<_> = wu_line(Vector2::new(0.5, 0.5), Vector2::new(20.5, 7.5)).collect();
+        assert!(pixels.iter().all(|p| p.coverage > 0.0 && p.coverage <= 1.0 + 1e-6));
+        assert!(unique(&pixels.iter().map(|p| p.position).collect::<Vec<_>>()));
+
+        // Each column of a shallow line is covered once in total.
+        for x in 0..21 {
+            let total: f32 = pixels.iter().filter(|p| p.position.x == x).map(|p| p.coverage).sum();
+            let expected = if x == 0 || x == 20 { 0.5 } else { 1.0 };
+            assert!((total - expected).abs() < 1e-4, "column {} has {}", x, total);
+        }
+
+        // A steep line is covered once per row.
+        let steep: Vec<_> = wu_line(Vector2::new(3.5, 0.5), Vector2::new(0.5, 12.5)).collect();
+        for y in 1..12 {
+            let total: f32 = steep.iter().filter(|p| p.position.y == y).map(|p| p.coverage).sum();
+            assert!((total - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_line_circle_and_ellipse() {
+        for radius in 0..12 {
+            let points: Vec<Vector2i> = midpoint_circle(Vector2i::new(3, -2), radius).collect();
+            assert!(unique(&points));
+            for p in &points {
+                let d = (((p.x - 3) * (p.x - 3) + (p.y + 2) * (p.y + 2)) as f32).sqrt();
+                assert!((d - radius as f32).abs() < 0.75);
+            }
+        }
+        assert_eq!(midpoint_circle(Vector2i::new(0, 0), 0).count(), 1);
+
+        let (rx, ry) = (12, 5);
+        let points: Vec<Vector2i> = midpoint_ellipse(Vector2i::new(0, 0), rx, ry).collect();
+        assert!(unique(&points));
+        for &p in [Vector2i::new(rx, 0), Vector2i::new(-rx, 0), Vector2i::new(0, ry), Vector2i::new(0, -ry)].iter() {
+            assert!(points.contains(&p));
+        }
+        for p in &points {
+            let v = (p.x * p.x) as f32 / (rx * rx) as f32 + (p.y * p.y) as f32 / (ry * ry) as f32;
+            assert!((v - 1.0).abs() < 0.35, "{:?} {}", p, v);
+        }
+
+        // A zero radius flattens the ellipse into a run of pixels.
+        let points: Vec<(i32, i32)> = midpoint_ellipse(Vector2i::new(0, 0), 3, 0).map(|p| (p.x, p.y)).collect();
+        assert_eq!(points, (-3..4).map(|x| (x, 0)).collect::<Vec<_>>());
+        assert_eq!(midpoint_ellipse(Vector2i::new(1, 1), 0, 2).count(), 5);
+        assert_eq!(midpoint_ellipse(Vector2i::new(1, 1), 0, 0).count(), 1);
+    }
+}
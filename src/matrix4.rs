@@ -102,6 +102,62 @@ impl Matrix4 {
                         [self[0][2], self[1][2], self[2][2], self[3][2]],
                         [self[0][3], self[1][3], self[2][3], self[3][3]]]}
     }
+
+    /// Returns the 2x2 determinants of the top two rows and of the bottom two rows, shared by `determinant` and `inverse`.
+    fn sub_determinants(&self) -> ([f32; 6], [f32; 6]) {
+        let m = &self.data;
+        let s0 = m[0][0] * m[1][1] - m[1][0] * m[0][1];
+        let s1 = m[0][0] * m[1][2] - m[1][0] * m[0][2];
+        let s2 = m[0][0] * m[1][3] - m[1][0] * m[0][3];
+        let s3 = m[0][1] * m[1][2] - m[1][1] * m[0][2];
+        let s4 = m[0][1] * m[1][3] - m[1][1] * m[0][3];
+        let s5 = m[0][2] * m[1][3] - m[1][2] * m[0][3];
+
+        let c5 = m[2][2] * m[3][3] - m[3][2] * m[2][3];
+        let c4 = m[2][1] * m[3][3] - m[3][1] * m[2][3];
+        let c3 = m[2][1] * m[3][2] - m[3][1] * m[2][2];
+        let c2 = m[2][0] * m[3][3] - m[3][0] * m[2][3];
+        let c1 = m[2][0] * m[3][2] - m[3][0] * m[2][2];
+        let c0 = m[2][0] * m[3][1] - m[3][0] * m[2][1];
+
+        ([s0, s1, s2, s3, s4, s5], [c0, c1, c2, c3, c4, c5])
+    }
+
+    /// Returns the determinant of the matrix.
+    pub fn determinant(&self) -> f32 {
+        let ([s0, s1, s2, s3, s4, s5], [c0, c1, c2, c3, c4, c5]) = self.sub_determinants();
+        s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0
+    }
+
+    /// Returns the inverse of the matrix.
+    /// Returns `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<Matrix4> {
+        let m = &self.data;
+        let ([s0, s1, s2, s3, s4, s5], [c0, c1, c2, c3, c4, c5]) = self.sub_determinants();
+        let det = s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let inv = 1.0 / det;
+
+        Some(Matrix4 {data: [[( m[1][1] * c5 - m[1][2] * c4 + m[1][3] * c3) * inv,
+                              (-m[0][1] * c5 + m[0][2] * c4 - m[0][3] * c3) * inv,
+                              ( m[3][1] * s5 - m[3][2] * s4 + m[3][3] * s3) * inv,
+                              (-m[2][1] * s5 + m[2][2] * s4 - m[2][3] * s3) * inv],
+                             [(-m[1][0] * c5 + m[1][2] * c2 - m[1][3] * c1) * inv,
+                              ( m[0][0] * c5 - m[0][2] * c2 + m[0][3] * c1) * inv,
+                              (-m[3][0] * s5 + m[3][2] * s2 - m[3][3] * s1) * inv,
+                              ( m[2][0] * s5 - m[2][2] * s2 + m[2][3] * s1) * inv],
+                             [( m[1][0] * c4 - m[1][1] * c2 + m[1][3] * c0) * inv,
+                              (-m[0][0] * c4 + m[0][1] * c2 - m[0][3] * c0) * inv,
+                              ( m[3][0] * s4 - m[3][1] * s2 + m[3][3] * s0) * inv,
+                              (-m[2][0] * s4 + m[2][1] * s2 - m[2][3] * s0) * inv],
+                             [(-m[1][0] * c3 + m[1][1] * c1 - m[1][2] * c0) * inv,
+                              ( m[0][0] * c3 - m[0][1] * c1 + m[0][2] * c0) * inv,
+                              (-m[3][0] * s3 + m[3][1] * s1 - m[3][2] * s0) * inv,
+                              ( m[2][0] * s3 - m[2][1] * s1 + m[2][2] * s0) * inv]]})
+    }
 }
 
 impl Index<usize> for Matrix4 {
@@ -231,4 +287,17 @@ mod tests {
         assert_eq!(t[3][2], m[2][3]);
         assert_eq!(t[3][3], m[3][3]);
     }
+
+    #[test]
+    fn test_matrix_inverse() {
+        let m = Matrix4::translation(1.0, -2.0, 3.0) * Matrix4::scale(2.0, 4.0, 0.5);
+        let inv = m.inverse().unwrap();
+        let v = Vector3::new(3.0, 5.0, -7.0);
+        let r = inv * (m * v);
+
+        assert!((r.x - v.x).abs() < 0.0001);
+        assert!((r.y - v.y).abs() < 0.0001);
+        assert!((r.z - v.z).abs() < 0.0001);
+        assert!(Matrix4::zero().inverse().is_none());
+    }
 }
\ No newline at end of file
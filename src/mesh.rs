@@ -0,0 +1,506 @@
+use vector2::Vector2;
+use vector3::Vector3;
+use vector4::Vector4;
+use ray::Ray;
+use aabb::Aabb;
+use triangle::Triangle;
+
+/// The largest number of triangles stored in a leaf of the acceleration structure.
+const LEAF_SIZE: usize = 4;
+
+/// A node of a `Bvh`.
+/// Leaves hold `count` triangles from `start`, interior nodes have their left child next and their right child at `start`.
+#[derive(Debug, Clone)]
+struct BvhNode {
+    bounds: Aabb,
+    start: usize,
+    count: usize,
+}
+
+/// A bounding volume hierarchy over the triangles of a mesh.
+#[derive(Debug, Clone)]
+struct Bvh {
+    nodes: Vec<BvhNode>,
+    triangles: Vec<usize>,
+}
+
+impl Bvh {
+    /// Returns a `Bvh` over `triangles`, splitting nodes at the median centroid along their longest axis.
+    fn new(triangles: &[Triangle]) -> Bvh {
+        let mut bvh = Bvh { nodes: Vec::new(), triangles: (0..triangles.len()).collect() };
+        if !triangles.is_empty() {
+            let bounds: Vec<Aabb> = triangles.iter()
+                .map(|t| Aabb::from_points(&[t.v0, t.v1, t.v2]))
+                .collect();
+            let centroids: Vec<Vector3> = triangles.iter().map(|t| t.centroid()).collect();
+            bvh.build(&bounds, &centroids, 0, triangles.len());
+        }
+
+        bvh
+    }
+
+    /// Adds the node for the triangles in `start..end` and its children, returning its index.
+    fn build(&mut self, bounds: &[Aabb], centroids: &[Vector3], start: usize, end: usize) -> usize {
+        let mut node_bounds = Aabb::empty();
+        let mut centroid_bounds = Aabb::empty();
+        for &i in &self.triangles[start..end] {
+            node_bounds = Aabb::union(&node_bounds, &bounds[i]);
+            centroid_bounds = centroid_bounds.expand(centroids[i]);
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(BvhNode { bounds: node_bounds, start, count: end - start });
+        if end - start <= LEAF_SIZE {
+            return index;
+        }
+
+        let size = centroid_bounds.size();
+        let axis = if size.x > size.y && size.x > size.z { 0 } else if size.y > size.z { 1 } else { 2 };
+        if size[axis] <= 0.0 {
+            return index;
+        }
+
+        let middle = (start + end) / 2;
+        self.triangles[start..end].sort_by(|&a, &b| {
+            centroids[a][axis].partial_cmp(&centroids[b][axis]).unwrap_or(::std::cmp::Ordering::Equal)
+        });
+
+        self.build(bounds, centroids, start, middle);
+        let right = self.build(bounds, centroids, middle, end);
+        self.nodes[index].start = right;
+        self.nodes[index].count = 0;
+        index
+    }
+}
+
+/// A hit between a `Ray` and a `TriangleMesh`.
+#[derive(Debug, Copy, Clone)]
+pub struct MeshHit {
+    /// The time along the ray of the hit.
+    pub t: f32,
+    /// The index of the triangle that was hit.
+    pub triangle: usize,
+    /// The barycentric coordinates of the hit for the triangle's vertices.
+    pub barycentric: Vector3,
+}
+
+/// An indexed triangle mesh with optional per vertex normals and texture coordinates.
+#[derive(Debug, Clone)]
+pub struct TriangleMesh {
+    positions: Vec<Vector3>,
+    normals: Option<Vec<Vector3>>,
+    uvs: Option<Vec<Vector2>>,
+    indices: Vec<[usize; 3]>,
+    bvh: Bvh,
+}
+
+/// Returns the angle between `v0` and `v1`, or 0 if either is zero.
+fn angle_between(v0: Vector3, v1: Vector3) -> f32 {
+    let l = (v0.length_squared() * v1.length_squared()).sqrt();
+    if l <= 0.0 {
+        return 0.0;
+    }
+
+    (Vector3::dot(v0, v1) / l).clamp(-1.0, 1.0).acos()
+}
+
+/// Returns a unit vector perpendicular to the unit vector `n`.
+fn perpendicular(n: Vector3) -> Vector3 {
+    let axis = if n.x.abs() < 0.9 { Vector3::new(1.0, 0.0, 0.0) } else { Vector3::new(0.0, 1.0, 0.0) };
+    Vector3::cross(axis, n).normalized()
+}
+
+impl TriangleMesh {
+    /// Returns a new `TriangleMesh` without normals or texture coordinates.
+    /// Returns `None` if an index is outside `positions`.
+    pub fn new(positions: Vec<Vector3>, indices: Vec<[usize; 3]>) -> Option<TriangleMesh> {
+        if indices.iter().any(|face| face.iter().any(|&i| i >= positions.len())) {
+            return None;
+        }
+
+        let mut mesh = TriangleMesh { positions, normals: None, uvs: None, indices, bvh: Bvh::new(&[]) };
+        mesh.rebuild();
+        Some(mesh)
+    }
+
+    /// Returns a new `TriangleMesh` with the given normals and texture coordinates.
+    /// Normals or texture coordinates that are not one per vertex are left out.
+    /// Returns `None` if an index is outside `positions`.
+    pub fn with_attributes(positions: Vec<Vector3>, normals: Vec<Vector3>, uvs: Vec<Vector2>, indices: Vec<[usize; 3]>) -> Option<TriangleMesh> {
+        let mut mesh = TriangleMesh::new(positions, indices)?;
+        if !normals.is_empty() && normals.len() == mesh.positions.len() {
+            mesh.normals = Some(normals);
+        }
+        if !uvs.is_empty() && uvs.len() == mesh.positions.len() {
+            mesh.uvs = Some(uvs);
+        }
+
+        Some(mesh)
+    }
+
+    /// Rebuilds the acceleration structure after the positions or indices change.
+    fn rebuild(&mut self) {
+        let triangles: Vec<Triangle> = (0..self.indices.len()).map(|i| self.triangle(i)).collect();
+        self.bvh = Bvh::new(&triangles);
+    }
+
+    /// Returns the vertex positions.
+    pub fn positions(&self) -> &[Vector3] {
+        &self.positions
+    }
+
+    /// Returns the vertex normals if the mesh has them.
+    pub fn normals(&self) -> Option<&[Vector3]> {
+        self.normals.as_deref()
+    }
+
+    /// Returns the vertex texture coordinates if the mesh has them.
+    pub fn uvs(&self) -> Option<&[Vector2]> {
+        self.uvs.as_deref()
+    }
+
+    /// Returns the vertex indices of each triangle.
+    pub fn indices(&self) -> &[[usize; 3]] {
+        &self.indices
+    }
+
+    /// Sets the vertex normals.
+    /// Panics if there is not one normal per vertex.
+    pub fn set_normals(&mut self, normals: Vec<Vector3>) {
+        assert_eq!(normals.len(), self.positions.len(), "TriangleMesh needs one normal per vertex");
+        self.normals = Some(normals);
+    }
+
+    /// Sets the vertex texture coordinates.
+    /// Panics if there is not one texture coordinate per vertex.
+    pub fn set_uvs(&mut self, uvs: Vec<Vector2>) {
+        assert_eq!(uvs.len(), self.positions.len(), "TriangleMesh needs one uv per vertex");
+        self.uvs = Some(uvs);
+    }
+
+    /// Returns the number of triangles.
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns the triangle at `index`.
+    pub fn triangle(&self, index: usize) -> Triangle {
+        let [i0, i1, i2] = self.indices[index];
+        Triangle::new(self.positions[i0], self.positions[i1], self.positions[i2])
+    }
+
+    /// Returns the bounding box of the mesh.
+    pub fn bounding_box(&self) -> Aabb {
+        match self.bvh.nodes.first() {
+            Some(root) => root.bounds,
+            None => Aabb::empty(),
+        }
+    }
+
+    /// Returns the total area of the mesh's triangles.
+    pub fn surface_area(&self) -> f32 {
+        (0..self.indices.len()).map(|i| self.triangle(i).area()).sum()
+    }
+
+    /// Sets smooth vertex normals from the normals of the triangles around each vertex weighted by their corner angle.
+    /// Vertices that only touch degenerate triangles get a zero normal.
+    pub fn compute_smooth_normals(&mut self) {
+        let mut normals = vec![Vector3::zero(); self.positions.len()];
+        for (f, face) in self.indices.iter().enumerate() {
+            let n = self.triangle(f).normal();
+            for corner in 0..3 {
+                let p = self.positions[face[corner]];
+                let angle = angle_between(self.positions[face[(corner + 1) % 3]] - p, self.positions[face[(corner + 2) % 3]] - p);
+                normals[face[corner]] = normals[face[corner]] + n * angle;
+            }
+        }
+
+        for n in normals.iter_mut() {
+            if n.length_squared() > 0.0 {
+                *n = n.normalized();
+            }
+        }
+
+        self.normals = Some(normals);
+    }
+
+    /// Sets flat normals by giving every triangle its own 3 vertices with the triangle's normal.
+    pub fn compute_flat_normals(&mut self) {
+        let mut positions = Vec::with_capacity(self.indices.len() * 3);
+        let mut normals = Vec::with_capacity(self.indices.len() * 3);
+        let mut uvs = self.uvs.as_ref().map(|_| Vec::with_capacity(self.indices.len() * 3));
+
+        for (f, face) in self.indices.iter().enumerate() {
+            let n = self.triangle(f).normal();
+            for &i in face.iter() {
+                positions.push(self.positions[i]);
+                normals.push(n);
+                if let (Some(uvs), Some(source)) = (uvs.as_mut(), self.uvs.as_ref()) {
+                    uvs.push(source[i]);
+                }
+            }
+        }
+
+        self.indices = (0..self.indices.len()).map(|f| [f * 3, f * 3 + 1, f * 3 + 2]).collect();
+        self.positions = positions;
+        self.normals = Some(normals);
+        self.uvs = uvs;
+        self.rebuild();
+    }
+
+    /// Returns a tangent for each vertex following the MikkTSpace conventions.
+    /// The `xyz` values are a unit tangent along increasing u perpendicular to the vertex normal
+    /// and `w` is the handedness, so the bitangent is `cross(normal, tangent) * w`.
+    /// Triangle tangents are projected onto the plane of each vertex normal and weighted by corner angle.
+    /// Returns `None` if the mesh does not have both normals and texture coordinates.
+    pub fn compute_tangents(&self) -> Option<Vec<Vector4>> {
+        let normals = self.normals.as_ref()?;
+        let uvs = self.uvs.as_ref()?;
+
+        let mut tangents = vec![Vector3::zero(); self.positions.len()];
+        let mut bitangents = vec![Vector3::zero(); self.positions.len()];
+        for face in &self.indices {
+            let e1 = self.positions[face[1]] - self.positions[face[0]];
+            let e2 = self.positions[face[2]] - self.positions[face[0]];
+            let st1 = uvs[face[1]] - uvs[face[0]];
+            let st2 = uvs[face[2]] - uvs[face[0]];
+
+            let area = Vector2::cross(st1, st2);
+            if area == 0.0 {
+                continue;
+            }
+
+            let sign = if area > 0.0 { 1.0 } else { -1.0 };
+            let s = (e1 * st2.y - e2 * st1.y) * sign;
+            let t = (e2 * st1.x - e1 * st2.x) * sign;
+
+            for corner in 0..3 {
+                let i = face[corner];
+                let n = normals[i];
+                let p = self.positions[i];
+                let project = |v: Vector3| v - n * Vector3::dot(n, v);
+
+                let angle = angle_between(project(self.positions[face[(corner + 1) % 3]] - p),
+                                          project(self.positions[face[(corner + 2) % 3]] - p));
+                let ps = project(s);
+                let pt = project(t);
+                if ps.length_squared() > 0.0 {
+                    tangents[i] = tangents[i] + ps.normalized() * angle;
+                }
+                if pt.length_squared() > 0.0 {
+                    bitangents[i] = bitangents[i] + pt.normalized() * angle;
+                }
+            }
+        }
+
+        let result = tangents.iter().zip(bitangents.iter()).zip(normals.iter())
+            .map(|((&t, &b), &n)| {
+                let t = t - n * Vector3::dot(n, t);
+                let t = if t.length_squared() > 0.0 { t.normalized() } else { perpendicular(n) };
+                let w = if Vector3::dot(Vector3::cross(n, t), b) < 0.0 { -1.0 } else { 1.0 };
+                t.to_vector4(w)
+            })
+            .collect();
+
+        Some(result)
+    }
+
+    /// Returns the closest hit between `ray` and the mesh's triangles from either side.
+    /// Returns `None` if the ray misses the mesh.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<MeshHit> {
+        let mut best: Option<MeshHit> = None;
+        if self.bvh.nodes.is_empty() {
+            return best;
+        }
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.bvh.nodes[index];
+            let t_max = best.map_or(f32::INFINITY, |hit| hit.t);
+            if node.bounds.intersect_ray_range(ray, 0.0, t_max).is_none() {
+                continue;
+            }
+
+            if node.count == 0 {
+                stack.push(node.start);
+                stack.push(index + 1);
+                continue;
+            }
+
+            for &triangle in &self.bvh.triangles[node.start..node.start + node.count] {
+                if let Some(hit) = self.triangle(triangle).intersect_ray(ray) {
+                    if hit.t >= 0.0 && hit.t < best.map_or(f32::INFINITY, |b| b.t) {
+                        best = Some(MeshHit { t: hit.t, triangle, barycentric: hit.barycentric });
+                    }
+                }
+            }
+        }
+
+        best
+    }
+}
+
+/// Returns triangles covering the simple polygon with the vertices `points` in order, as indices into `points`.
+/// Concave polygons are triangulated by ear clipping in the plane of the polygon's Newell normal.
+/// Polygons with no area are triangulated as a fan from the first vertex.
+pub fn triangulate_polygon(points: &[Vector3]) -> Vec<[usize; 3]> {
+    let n = points.len();
+    if n < 3 {
+        return Vec::new();
+    }
+
+    let fan = || (1..n - 1).map(|i| [0, i, i + 1]).collect();
+    if n == 3 {
+        return fan();
+    }
+
+    let mut normal = Vector3::zero();
+    for i in 0..n {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        normal = normal + Vector3::new((a.y - b.y) * (a.z + b.z), (a.z - b.z) * (a.x + b.x), (a.x - b.x) * (a.y + b.y));
+    }
+    if normal.length_squared() == 0.0 {
+        return fan();
+    }
+
+    let side = |a: usize, b: usize, p: usize| Vector3::dot(Vector3::cross(points[b] - points[a], points[p] - points[a]), normal);
+
+    let mut remaining: Vec<usize> = (0..n).collect();
+    let mut triangles = Vec::with_capacity(n - 2);
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        // Starting from the second vertex makes convex polygons come out as a fan from the first.
+        let ear = (1..count + 1).map(|i| i % count).find(|&i| {
+            let (a, b, c) = (remaining[(i + count - 1) % count], remaining[i], remaining[(i + 1) % count]);
+            if side(a, b, c) <= 0.0 {
+                return false;
+            }
+
+            remaining.iter()
+                .filter(|&&p| p != a && p != b && p != c)
+                .all(|&p| side(a, b, p) < 0.0 || side(b, c, p) < 0.0 || side(c, a, p) < 0.0)
+        });
+
+        // Self intersecting polygons may have no ears, so clip the first vertex anyway.
+        let i = ear.unwrap_or(0);
+        triangles.push([remaining[(i + count - 1) % count], remaining[i], remaining[(i + 1) % count]]);
+        remaining.remove(i);
+    }
+
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    triangles
+}
+
+#[cfg(test)]
+mod tests {
+    use mesh::{TriangleMesh, triangulate_polygon};
+    use vector2::Vector2;
+    use vector3::Vector3;
+    use ray::Ray;
+
+    fn quad() -> TriangleMesh {
+        let positions = vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0),
+                             Vector3::new(1.0, 1.0, 0.0), Vector3::new(0.0, 1.0, 0.0)];
+        TriangleMesh::new(positions, vec![[0, 1, 2], [0, 2, 3]]).unwrap()
+    }
+
+    fn grid(n: usize) -> TriangleMesh {
+        let mut positions = Vec::new();
+        for y in 0..n + 1 {
+            for x in 0..n + 1 {
+                positions.push(Vector3::new(x as f32, y as f32, ((x * y) % 3) as f32 * 0.1));
+            }
+        }
+
+        let mut indices = Vec::new();
+        for y in 0..n {
+            for x in 0..n {
+                let i = y * (n + 1) + x;
+                indices.push([i, i + 1, i + n + 2]);
+                indices.push([i, i + n + 2, i + n + 1]);
+            }
+        }
+
+        TriangleMesh::new(positions, indices).unwrap()
+    }
+
+    #[test]
+    fn test_mesh_properties() {
+        let mesh = quad();
+
+        assert_eq!(mesh.surface_area(), 1.0);
+        assert_eq!(mesh.bounding_box().max.y, 1.0);
+        assert!(TriangleMesh::new(vec![Vector3::zero()], vec![[0, 0, 1]]).is_none());
+    }
+
+    #[test]
+    fn test_mesh_normals() {
+        let mut mesh = quad();
+        mesh.compute_smooth_normals();
+        for n in mesh.normals().unwrap() {
+            assert!((*n - Vector3::forward()).length() < 0.0001);
+        }
+
+        mesh.compute_flat_normals();
+        assert_eq!(mesh.positions().len(), 6);
+        assert_eq!(mesh.normals().unwrap().len(), 6);
+        assert!(mesh.intersect_ray(&Ray::new(Vector3::new(0.5, 0.5, 1.0), -Vector3::forward(), 0.0)).is_some());
+    }
+
+    #[test]
+    fn test_mesh_tangents() {
+        let mut mesh = quad();
+        mesh.compute_smooth_normals();
+        assert!(mesh.compute_tangents().is_none());
+
+        mesh.set_uvs(vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0), Vector2::new(1.0, 1.0), Vector2::new(0.0, 1.0)]);
+        for t in mesh.compute_tangents().unwrap() {
+            assert!((t.xyz() - Vector3::new(1.0, 0.0, 0.0)).length() < 0.0001);
+            assert_eq!(t.w, 1.0);
+        }
+
+        // Mirroring u flips the tangent and the handedness.
+        mesh.set_uvs(vec![Vector2::new(1.0, 0.0), Vector2::new(0.0, 0.0), Vector2::new(0.0, 1.0), Vector2::new(1.0, 1.0)]);
+        for t in mesh.compute_tangents().unwrap() {
+            assert!((t.xyz() - Vector3::new(-1.0, 0.0, 0.0)).length() < 0.0001);
+            assert_eq!(t.w, -1.0);
+        }
+    }
+
+    #[test]
+    fn test_mesh_intersect_ray() {
+        let mesh = grid(16);
+
+        for i in 0..50 {
+            let origin = Vector3::new(i as f32 * 0.31 % 16.0, i as f32 * 0.17 % 16.0, 5.0);
+            let ray = Ray::new(origin, Vector3::new(0.05, 0.02, -1.0), 0.0);
+
+            let brute = (0..mesh.triangle_count())
+                .filter_map(|t| mesh.triangle(t).intersect_ray(&ray).map(|hit| hit.t))
+                .fold(f32::INFINITY, f32::min);
+            match mesh.intersect_ray(&ray) {
+                Some(hit) => assert!((hit.t - brute).abs() < 0.0001),
+                None => assert_eq!(brute, f32::INFINITY),
+            }
+        }
+
+        assert!(mesh.intersect_ray(&Ray::new(Vector3::new(8.0, 8.0, 5.0), Vector3::forward(), 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_triangulate_polygon() {
+        // An L shape with a reflex vertex at index 3.
+        let points = [Vector3::new(0.0, 0.0, 0.0), Vector3::new(2.0, 0.0, 0.0), Vector3::new(2.0, 1.0, 0.0),
+                      Vector3::new(1.0, 1.0, 0.0), Vector3::new(1.0, 2.0, 0.0), Vector3::new(0.0, 2.0, 0.0)];
+        let triangles = triangulate_polygon(&points);
+
+        assert_eq!(triangles.len(), 4);
+        let area: f32 = triangles.iter()
+            .map(|t| Vector3::cross(points[t[1]] - points[t[0]], points[t[2]] - points[t[0]]).z * 0.5)
+            .sum();
+        assert!((area - 3.0).abs() < 0.0001);
+        for t in &triangles {
+            assert!(Vector3::cross(points[t[1]] - points[t[0]], points[t[2]] - points[t[0]]).z > 0.0);
+        }
+    }
+}
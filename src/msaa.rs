@@ -0,0 +1,239 @@
+use vector2::Vector2;
+use vector2i::Vector2i;
+use vector3::Vector3;
+use rect2i::{Rect2i, Rect2iPixels};
+use raster::{RasterOptions, TriangleSetup};
+use std::ops::{Add, Mul};
+
+/// The number of samples taken per pixel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SampleCount {
+    X1,
+    X2,
+    X4,
+    X8,
+    X16,
+}
+
+const PATTERN_1: [Vector2i; 1] = [Vector2i { x: 0, y: 0 }];
+
+const PATTERN_2: [Vector2i; 2] = [Vector2i { x: 4, y: 4 }, Vector2i { x: -4, y: -4 }];
+
+const PATTERN_4: [Vector2i; 4] = [Vector2i { x: -2, y: -6 }, Vector2i { x: 6, y: -2 },
+                                  Vector2i { x: -6, y: 2 }, Vector2i { x: 2, y: 6 }];
+
+const PATTERN_8: [Vector2i; 8] = [Vector2i { x: 1, y: -3 }, Vector2i { x: -1, y: 3 },
+                                  Vector2i { x: 5, y: 1 }, Vector2i { x: -3, y: -5 },
+                                  Vector2i { x: -5, y: 5 }, Vector2i { x: -7, y: -1 },
+                                  Vector2i { x: 3, y: 7 }, Vector2i { x: 7, y: -7 }];
+
+const PATTERN_16: [Vector2i; 16] = [Vector2i { x: 1, y: 1 }, Vector2i { x: -1, y: -3 },
+                                    Vector2i { x: -3, y: 2 }, Vector2i { x: 4, y: -1 },
+                                    Vector2i { x: -5, y: -2 }, Vector2i { x: 2, y: 5 },
+                                    Vector2i { x: 5, y: 3 }, Vector2i { x: 3, y: -5 },
+                                    Vector2i { x: -2, y: 6 }, Vector2i { x: 0, y: -7 },
+                                    Vector2i { x: -4, y: -6 }, Vector2i { x: -6, y: 4 },
+                                    Vector2i { x: -8, y: 0 }, Vector2i { x: 7, y: -4 },
+                                    Vector2i { x: 6, y: 7 }, Vector2i { x: -7, y: -8 }];
+
+impl SampleCount {
+    /// Returns the number of samples.
+    pub fn count(&self) -> usize {
+        self.positions().len()
+    }
+
+    /// Returns the standard Direct3D sample positions in sixteenths of a pixel from the pixel center, with y pointing down.
+    pub fn positions(&self) -> &'static [Vector2i] {
+        match *self {
+            SampleCount::X1 => &PATTERN_1,
+            SampleCount::X2 => &PATTERN_2,
+            SampleCount::X4 => &PATTERN_4,
+            SampleCount::X8 => &PATTERN_8,
+            SampleCount::X16 => &PATTERN_16,
+        }
+    }
+
+    /// Returns the position of sample `i` in pixels relative to the pixel's top left corner.
+    pub fn offset(&self, i: usize) -> Vector2 {
+        let p = self.positions()[i];
+        Vector2::new(0.5 + p.x as f32 / 16.0, 0.5 + p.y as f32 / 16.0)
+    }
+
+    /// Returns a mask with a bit set for every sample.
+    pub fn full_mask(&self) -> u16 {
+        ((1u32 << self.count()) - 1) as u16
+    }
+}
+
+/// Returns the samples of `pixel` covered by `setup`, with bit `i` set for sample `i`.
+/// Sample positions are rounded to the setup's sub-pixel precision, so at least 4 fractional bits keep them exact.
+pub fn sample_mask(setup: &TriangleSetup, pixel: Vector2i, samples: SampleCount) -> u16 {
+    if !setup.touched_bounds().map_or(false, |b| b.contains(pixel)) {
+        return 0;
+    }
+    covered_samples(setup, pixel, samples)
+}
+
+/// Returns the samples of `pixel` covered by `setup`, for a pixel already known to be inside its touched bounds.
+fn covered_samples(setup: &TriangleSetup, pixel: Vector2i, samples: SampleCount) -> u16 {
+    let center = setup.pixel_center(pixel);
+    let bits = setup.subpixel_bits() as i32;
+    let mut mask = 0;
+    for (i, p) in samples.positions().iter().enumerate() {
+        let offset = if bits >= 4 {
+            Vector2i::new(p.x << (bits - 4), p.y << (bits - 4))
+        } else {
+            Vector2i::new(p.x >> (4 - bits), p.y >> (4 - bits))
+        };
+        if setup.covers(center + offset) {
+            mask |= 1 << i;
+        }
+    }
+    mask
+}
+
+/// A pixel with at least one sample covered by a triangle.
+#[derive(Debug, Copy, Clone)]
+pub struct MsaaFragment {
+    pub position: Vector2i,
+    /// The covered samples, with bit `i` set for sample `i`.
+    pub mask: u16,
+    /// The weights of the three vertices at the pixel center, in the order they were given.
+    pub barycentric: Vector3,
+}
+
+/// An iterator over the pixels with samples covered by a triangle, in row-major order.
+#[derive(Debug, Clone)]
+pub struct MsaaRaster {
+    setup: Option<TriangleSetup>,
+    samples: SampleCount,
+    pixels: Rect2iPixels,
+}
+
+impl Iterator for MsaaRaster {
+    type Item = MsaaFragment;
+
+    fn next(&mut self) -> Option<MsaaFragment> {
+        let setup = self.setup?;
+        for position in self.pixels.by_ref() {
+            let mask = covered_samples(&setup, position, self.samples);
+            if mask != 0 {
+                return Some(MsaaFragment {
+                    position,
+                    mask,
+                    barycentric: setup.barycentric(setup.pixel_center(position)),
+                });
+            }
+        }
+        None
+    }
+}
+
+/// Returns an iterator over the pixels where the screen space triangle `v0`, `v1`, `v2` covers any of `samples`,
+/// with per-pixel coverage masks. Samples on an edge shared by two triangles are covered by exactly one of them.
+pub fn rasterize_triangle_msaa(v0: Vector2, v1: Vector2, v2: Vector2, samples: SampleCount, options: &RasterOptions) -> MsaaRaster {
+    let setup = TriangleSetup::new(v0, v1, v2, options);
+    let bounds = setup.and_then(|s| s.touched_bounds()).unwrap_or_else(|| Rect2i::new(Vector2i::new(0, 0), Vector2i::new(0, 0)));
+
+    MsaaRaster {
+        setup,
+        samples,
+        pixels: bounds.pixels(),
+    }
+}
+
+/// Returns the fraction of `samples` set in `mask`.
+pub fn mask_coverage(mask: u16, samples: SampleCount) -> f32 {
+    (mask & samples.full_mask()).count_ones() as f32 / samples.count() as f32
+}
+
+/// Returns the average of the samples of each pixel in `buffer`, which holds `samples.count()` consecutive samples per pixel.
+/// Panics if the buffer length isn't a multiple of the sample count.
+pub fn resolve<T>(buffer: &[T], samples: SampleCount) -> Vec<T> where T: Copy + Add<Output = T> + Mul<f32, Output = T> {
+    let count = samples.count();
+    assert_eq!(buffer.len() % count, 0, "buffer length must be a multiple of the sample count");
+
+    let weight = 1.0 / count as f32;
+    buffer.chunks(count)
+        .map(|pixel| pixel[1..].iter().fold(pixel[0] * weight, |sum, &s| sum + s * weight))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use msaa::{mask_coverage, rasterize_triangle_msaa, resolve, sample_mask, SampleCount};
+    use raster::{RasterOptions, TriangleSetup};
+    use std::collections::HashMap;
+    use vector2::Vector2;
+    use vector2i::Vector2i;
+    use vector3::Vector3;
+
+    const COUNTS: [SampleCount; 5] = [SampleCount::X1, SampleCount::X2, SampleCount::X4, SampleCount::X8, SampleCount::X16];
+
+    #[test]
+    fn test_msaa_patterns() {
+        for samples in COUNTS.iter() {
+            let positions = samples.positions();
+            assert_eq!(positions.len(), samples.count());
+            assert_eq!(samples.full_mask().count_ones() as usize, samples.count());
+            for (i, p) in positions.iter().enumerate() {
+                assert!(p.x >= -8 && p.x < 8 && p.y >= -8 && p.y < 8);
+                assert!(!positions[..i].contains(p));
+            }
+        }
+        let offset = SampleCount::X4.offset(0);
+        assert_eq!((offset.x, offset.y), (0.375, 0.125));
+    }
+
+    #[test]
+    fn test_msaa_sample_mask() {
+        let options = RasterOptions::default();
+        // A triangle whose vertical edge at x = 10.5 splits pixel 10 through its center.
+        let setup = TriangleSetup::new(Vector2::new(10.5, 0.0), Vector2::new(30.0, 20.0), Vector2::new(10.5, 20.0), &options).unwrap();
+
+        for samples in COUNTS.iter() {
+            assert_eq!(sample_mask(&setup, Vector2i::new(12, 18), *samples), samples.full_mask());
+            assert_eq!(sample_mask(&setup, Vector2i::new(9, 18), *samples), 0);
+
+            let mask = sample_mask(&setup, Vector2i::new(10, 18), *samples);
+            for (i, p) in samples.positions().iter().enumerate() {
+                assert_eq!(mask & (1 << i) != 0, p.x >= 0, "{:?} sample {}", samples, i);
+            }
+        }
+        assert_eq!(mask_coverage(sample_mask(&setup, Vector2i::new(10, 18), SampleCount::X16), SampleCount::X16), 0.5);
+    }
+
+    #[test]
+    fn test_msaa_shared_edge() {
+        let options = RasterOptions::default();
+        let a = Vector2::new(0.3, 0.7);
+        let b = Vector2::new(9.6, 1.2);
+        let c = Vector2::new(8.1, 9.9);
+        let d = Vector2::new(0.9, 7.4);
+
+        for samples in COUNTS.iter() {
+            let mut masks: HashMap<(i32, i32), u16> = HashMap::new();
+            for fragment in rasterize_triangle_msaa(a, b, c, *samples, &options).chain(rasterize_triangle_msaa(a, c, d, *samples, &options)) {
+                let mask = masks.entry((fragment.position.x, fragment.position.y)).or_insert(0);
+                assert_eq!(*mask & fragment.mask, 0);
+                *mask |= fragment.mask;
+            }
+            // Pixels well inside the quad have every sample covered once.
+            for y in 2..7 {
+                for x in 2..7 {
+                    assert_eq!(masks[&(x, y)], samples.full_mask());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_msaa_resolve() {
+        let buffer = [Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 0.0),
+                      Vector3::new(0.5, 0.5, 0.5), Vector3::new(0.5, 0.5, 0.5), Vector3::new(0.5, 0.5, 0.5), Vector3::new(0.5, 0.5, 0.5)];
+        let resolved = resolve(&buffer, SampleCount::X4);
+        assert_eq!(resolved.len(), 2);
+        assert!((resolved[0] - Vector3::new(0.5, 0.5, 0.0)).length() < 1e-6);
+        assert!((resolved[1] - Vector3::new(0.5, 0.5, 0.5)).length() < 1e-6);
+    }
+}
@@ -0,0 +1,301 @@
+use vector3::Vector3;
+use matrix4::Matrix4;
+use quaternion::Quaternion;
+use ray::Ray;
+use aabb::Aabb;
+use sphere::Sphere;
+
+/// An oriented bounding box defined by a center, half extents along its local axes and an orientation.
+#[derive(Debug, Copy, Clone)]
+pub struct Obb {
+    pub center: Vector3,
+    pub half_extents: Vector3,
+    pub orientation: Quaternion,
+}
+
+impl Obb {
+    /// Returns a new `Obb`.
+    pub fn new(center: Vector3, half_extents: Vector3, orientation: Quaternion) -> Obb {
+        Obb { center, half_extents, orientation }
+    }
+
+    /// Returns an `Obb` with the same bounds as `aabb`.
+    pub fn from_aabb(aabb: &Aabb) -> Obb {
+        Obb::new(aabb.center(), aabb.half_extents(), Quaternion::identity())
+    }
+
+    /// Returns an `Obb` containing all of `points` aligned to their principal components.
+    pub fn from_points(points: &[Vector3]) -> Obb {
+        if points.is_empty() {
+            return Obb::new(Vector3::zero(), Vector3::zero(), Quaternion::identity());
+        }
+
+        let mean = points.iter().fold(Vector3::zero(), |sum, p| sum + *p) / points.len() as f32;
+        let mut covariance = [[0.0f32; 3]; 3];
+        for p in points.iter() {
+            let d = *p - mean;
+            for (i, row) in covariance.iter_mut().enumerate() {
+                for (j, c) in row.iter_mut().enumerate() {
+                    *c += d[i] * d[j];
+                }
+            }
+        }
+
+        let v = jacobi_eigenvectors(covariance);
+        let x_axis = Vector3::new(v[0][0], v[1][0], v[2][0]).normalized();
+        let y_axis = Vector3::new(v[0][1], v[1][1], v[2][1]).normalized();
+        let z_axis = Vector3::cross(x_axis, y_axis).normalized();
+        let axes = [x_axis, y_axis, z_axis];
+
+        let mut min = Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut max = Vector3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for p in points.iter() {
+            let local = Vector3::new(Vector3::dot(*p, axes[0]), Vector3::dot(*p, axes[1]), Vector3::dot(*p, axes[2]));
+            min = Vector3::min(min, local);
+            max = Vector3::max(max, local);
+        }
+
+        let c = (min + max) * 0.5;
+        Obb {
+            center: axes[0] * c.x + axes[1] * c.y + axes[2] * c.z,
+            half_extents: (max - min) * 0.5,
+            orientation: Quaternion::from_axes(axes[0], axes[1], axes[2]),
+        }
+    }
+
+    /// Returns the local `x`, `y` and `z` axes of the box in world space.
+    pub fn axes(&self) -> [Vector3; 3] {
+        let m = Matrix4::rotation(self.orientation);
+        [Vector3::new(m[0][0], m[1][0], m[2][0]),
+         Vector3::new(m[0][1], m[1][1], m[2][1]),
+         Vector3::new(m[0][2], m[1][2], m[2][2])]
+    }
+
+    /// Returns the matrix that transforms a unit cube centered at the origin onto the box.
+    pub fn to_matrix(&self) -> Matrix4 {
+        Matrix4::translation(self.center.x, self.center.y, self.center.z) *
+        Matrix4::rotation(self.orientation) *
+        Matrix4::scale(self.half_extents.x, self.half_extents.y, self.half_extents.z)
+    }
+
+    /// Returns the 8 corners of the box.
+    pub fn corners(&self) -> [Vector3; 8] {
+        let a = self.axes();
+        let e = self.half_extents;
+        let mut corners = [Vector3::zero(); 8];
+        for (i, c) in corners.iter_mut().enumerate() {
+            let sx = if i & 1 == 0 { -1.0 } else { 1.0 };
+            let sy = if i & 2 == 0 { -1.0 } else { 1.0 };
+            let sz = if i & 4 == 0 { -1.0 } else { 1.0 };
+            *c = self.center + a[0] * (sx * e.x) + a[1] * (sy * e.y) + a[2] * (sz * e.z);
+        }
+
+        corners
+    }
+
+    /// Returns the smallest `Aabb` containing the box.
+    pub fn bounding_box(&self) -> Aabb {
+        let a = self.axes();
+        let e = a[0].abs() * self.half_extents.x + a[1].abs() * self.half_extents.y + a[2].abs() * self.half_extents.z;
+        Aabb::new(self.center - e, self.center + e)
+    }
+
+    /// Returns the closest point inside the box to `point`.
+    pub fn closest_point(&self, point: Vector3) -> Vector3 {
+        let d = point - self.center;
+        let mut result = self.center;
+        for (i, axis) in self.axes().iter().enumerate() {
+            let e = self.half_extents[i];
+            let dist = Vector3::dot(d, *axis).max(-e).min(e);
+            result = result + *axis * dist;
+        }
+
+        result
+    }
+
+    /// Returns true if `point` is inside the box.
+    pub fn contains_point(&self, point: Vector3) -> bool {
+        let d = point - self.center;
+        self.axes().iter().enumerate().all(|(i, axis)| Vector3::dot(d, *axis).abs() <= self.half_extents[i])
+    }
+
+    /// Returns true if the box overlaps `sphere`.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        sphere.intersects_obb(self)
+    }
+
+    /// Returns true if the box overlaps `other` using the separating axis test.
+    pub fn intersects_obb(&self, other: &Obb) -> bool {
+        // Relax the cross product axes slightly so nearly parallel edges do not produce false separations.
+        const EPSILON: f32 = 1e-6;
+
+        let a = self.axes();
+        let b = other.axes();
+        let ea = self.half_extents;
+        let eb = other.half_extents;
+
+        let mut r = [[0.0f32; 3]; 3];
+        let mut abs_r = [[0.0f32; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                r[i][j] = Vector3::dot(a[i], b[j]);
+                abs_r[i][j] = r[i][j].abs() + EPSILON;
+            }
+        }
+
+        let d = other.center - self.center;
+        let t = [Vector3::dot(d, a[0]), Vector3::dot(d, a[1]), Vector3::dot(d, a[2])];
+
+        for i in 0..3 {
+            let rb = eb[0] * abs_r[i][0] + eb[1] * abs_r[i][1] + eb[2] * abs_r[i][2];
+            if t[i].abs() > ea[i] + rb {
+                return false;
+            }
+        }
+
+        for j in 0..3 {
+            let ra = ea[0] * abs_r[0][j] + ea[1] * abs_r[1][j] + ea[2] * abs_r[2][j];
+            let tb = t[0] * r[0][j] + t[1] * r[1][j] + t[2] * r[2][j];
+            if tb.abs() > ra + eb[j] {
+                return false;
+            }
+        }
+
+        for i in 0..3 {
+            let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+            for j in 0..3 {
+                let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+                let ra = ea[i1] * abs_r[i2][j] + ea[i2] * abs_r[i1][j];
+                let rb = eb[j1] * abs_r[i][j2] + eb[j2] * abs_r[i][j1];
+                if (t[i2] * r[i1][j] - t[i1] * r[i2][j]).abs() > ra + rb {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns the time along `ray` where it enters the box, or 0 if the ray starts inside.
+    /// Returns `None` if the ray misses the box.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<f32> {
+        let inverse = self.orientation.conjugate();
+        let local = Ray::new(inverse * (ray.origin() - self.center), inverse * ray.direction(), ray.time());
+        Aabb::new(-self.half_extents, self.half_extents).intersect_ray(&local)
+    }
+}
+
+/// Returns the eigenvectors of the symmetric matrix `a` as columns, sorted by descending eigenvalue.
+fn jacobi_eigenvectors(a: [[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    let mut a = a;
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+    for _ in 0..50 {
+        // Find the largest off diagonal element to rotate away.
+        let (mut p, mut q) = (0, 1);
+        for &(i, j) in [(0, 2), (1, 2)].iter() {
+            if a[i][j].abs() > a[p][q].abs() {
+                p = i;
+                q = j;
+            }
+        }
+
+        let scale = a[0][0].abs() + a[1][1].abs() + a[2][2].abs();
+        if a[p][q].abs() <= 1e-9 * scale.max(f32::MIN_POSITIVE) {
+            break;
+        }
+
+        let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+        let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+        let c = 1.0 / (t * t + 1.0).sqrt();
+        let s = t * c;
+
+        for row in a.iter_mut() {
+            let akp = row[p];
+            let akq = row[q];
+            row[p] = c * akp - s * akq;
+            row[q] = s * akp + c * akq;
+        }
+        let (row_p, row_q) = (a[p], a[q]);
+        for (k, (apk, aqk)) in row_p.iter().zip(row_q.iter()).enumerate() {
+            a[p][k] = c * apk - s * aqk;
+            a[q][k] = s * apk + c * aqk;
+        }
+        for row in v.iter_mut() {
+            let vp = row[p];
+            let vq = row[q];
+            row[p] = c * vp - s * vq;
+            row[q] = s * vp + c * vq;
+        }
+    }
+
+    let mut order = [0, 1, 2];
+    order.sort_by(|i, j| a[*j][*j].partial_cmp(&a[*i][*i]).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut sorted = [[0.0; 3]; 3];
+    for (row, v_row) in sorted.iter_mut().zip(v.iter()) {
+        for (col, o) in order.iter().enumerate() {
+            row[col] = v_row[*o];
+        }
+    }
+
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use obb::Obb;
+    use sphere::Sphere;
+    use vector3::Vector3;
+    use quaternion::Quaternion;
+    use ray::Ray;
+    use std::f32::consts::FRAC_PI_4;
+
+    #[test]
+    fn test_obb_from_points() {
+        let q = Quaternion::from_axis_angle(Vector3::new(1.0, 1.0, 0.0), 0.6);
+        let mut points = Vec::new();
+        for i in 0..8 {
+            let local = Vector3::new(if i & 1 == 0 { -4.0 } else { 4.0 },
+                                     if i & 2 == 0 { -2.0 } else { 2.0 },
+                                     if i & 4 == 0 { -1.0 } else { 1.0 });
+            points.push(q * local + Vector3::new(3.0, 1.0, -2.0));
+        }
+
+        let obb = Obb::from_points(&points);
+        assert!((obb.center - Vector3::new(3.0, 1.0, -2.0)).length() < 0.001);
+        assert!((obb.half_extents - Vector3::new(4.0, 2.0, 1.0)).length() < 0.001);
+        for p in points.iter() {
+            assert!((obb.closest_point(*p) - *p).length() < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_obb_intersects_obb() {
+        let a = Obb::new(Vector3::zero(), Vector3::new(1.0, 1.0, 1.0), Quaternion::identity());
+        let rotated = Quaternion::from_axis_angle(Vector3::forward(), FRAC_PI_4);
+
+        // The corner of the rotated box reaches sqrt(2) along x.
+        assert!(a.intersects_obb(&Obb::new(Vector3::new(2.3, 0.0, 0.0), Vector3::new(1.0, 1.0, 1.0), rotated)));
+        assert!(!a.intersects_obb(&Obb::new(Vector3::new(2.5, 0.0, 0.0), Vector3::new(1.0, 1.0, 1.0), rotated)));
+    }
+
+    #[test]
+    fn test_obb_intersects_sphere() {
+        let rotated = Quaternion::from_axis_angle(Vector3::forward(), FRAC_PI_4);
+        let a = Obb::new(Vector3::zero(), Vector3::new(1.0, 1.0, 1.0), rotated);
+
+        assert!(a.intersects_sphere(&Sphere::new(Vector3::new(1.8, 0.0, 0.0), 0.5)));
+        assert!(!a.intersects_sphere(&Sphere::new(Vector3::new(2.0, 0.0, 0.0), 0.5)));
+    }
+
+    #[test]
+    fn test_obb_intersect_ray() {
+        let rotated = Quaternion::from_axis_angle(Vector3::forward(), FRAC_PI_4);
+        let a = Obb::new(Vector3::new(5.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 1.0), rotated);
+        let t = a.intersect_ray(&Ray::new(Vector3::zero(), Vector3::left(), 0.0)).unwrap();
+
+        assert!((t - (5.0 - 2.0f32.sqrt())).abs() < 0.0001);
+        assert!(a.intersect_ray(&Ray::new(Vector3::zero(), Vector3::up(), 0.0)).is_none());
+    }
+}
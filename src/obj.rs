@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use vector2::Vector2;
+use vector3::Vector3;
+use mesh::{TriangleMesh, triangulate_polygon};
+
+/// An error from reading a Wavefront OBJ file.
+#[derive(Debug)]
+pub enum ObjError {
+    /// Reading from the source failed.
+    Io(io::Error),
+    /// A statement is missing some of its values.
+    MissingValue { line: usize, keyword: String },
+    /// A value could not be parsed as a number.
+    InvalidNumber { line: usize, value: String },
+    /// A face refers to an element that has not been defined, or uses an index of 0.
+    InvalidIndex { line: usize, index: String },
+    /// A face has fewer than 3 vertices.
+    TooFewVertices { line: usize },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ObjError::Io(ref e) => write!(f, "failed to read OBJ: {}", e),
+            ObjError::MissingValue { line, ref keyword } => write!(f, "line {}: `{}` is missing values", line, keyword),
+            ObjError::InvalidNumber { line, ref value } => write!(f, "line {}: `{}` is not a number", line, value),
+            ObjError::InvalidIndex { line, ref index } => write!(f, "line {}: face index `{}` is out of range", line, index),
+            ObjError::TooFewVertices { line } => write!(f, "line {}: face has fewer than 3 vertices", line),
+        }
+    }
+}
+
+impl Error for ObjError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ObjError::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ObjError {
+    fn from(e: io::Error) -> ObjError {
+        ObjError::Io(e)
+    }
+}
+
+/// A named range of triangles in an `ObjMesh`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjGroup {
+    pub name: String,
+    /// The index of the group's first triangle.
+    pub start: usize,
+    /// The number of triangles in the group.
+    pub count: usize,
+}
+
+/// Triangles read from a Wavefront OBJ file.
+/// Each distinct combination of position, texture coordinate and normal in the faces becomes one vertex.
+#[derive(Debug, Clone)]
+pub struct ObjMesh {
+    pub positions: Vec<Vector3>,
+    /// The vertex normals, either empty or one per position.
+    pub normals: Vec<Vector3>,
+    /// The vertex texture coordinates, either empty or one per position.
+    pub uvs: Vec<Vector2>,
+    pub indices: Vec<[usize; 3]>,
+    pub groups: Vec<ObjGroup>,
+}
+
+impl ObjMesh {
+    /// Returns a `TriangleMesh` with the positions, normals and texture coordinates of the OBJ mesh.
+    /// Returns `None` if an index is outside the positions.
+    pub fn to_triangle_mesh(&self) -> Option<TriangleMesh> {
+        TriangleMesh::with_attributes(self.positions.clone(), self.normals.clone(), self.uvs.clone(), self.indices.clone())
+    }
+}
+
+/// Returns the floats after the keyword of a statement, requiring at least `count` of them.
+fn parse_floats<'a, I: Iterator<Item = &'a str>>(values: I, count: usize, line: usize, keyword: &str) -> Result<Vec<f32>, ObjError> {
+    let floats = values
+        .map(|v| v.parse::<f32>().map_err(|_| ObjError::InvalidNumber { line, value: v.to_string() }))
+        .collect::<Result<Vec<f32>, ObjError>>()?;
+
+    if floats.len() < count {
+        return Err(ObjError::MissingValue { line, keyword: keyword.to_string() });
+    }
+
+    Ok(floats)
+}
+
+/// Returns the zero based index for the one based or negative relative OBJ `index` into `len` elements.
+fn resolve_index(index: &str, len: usize, line: usize) -> Result<usize, ObjError> {
+    let invalid = || ObjError::InvalidIndex { line, index: index.to_string() };
+    let value = index.parse::<i64>().map_err(|_| ObjError::InvalidNumber { line, value: index.to_string() })?;
+
+    let resolved = if value > 0 { value - 1 } else if value < 0 { len as i64 + value } else { return Err(invalid()) };
+    if resolved < 0 || resolved >= len as i64 {
+        return Err(invalid());
+    }
+
+    Ok(resolved as usize)
+}
+
+/// Reads triangles from the Wavefront OBJ data in `reader`.
+/// Polygons are triangulated and statements other than vertices, faces and groups are ignored.
+pub fn read_obj<R: BufRead>(reader: R) -> Result<ObjMesh, ObjError> {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+
+    let mut mesh = ObjMesh { positions: Vec::new(), normals: Vec::new(), uvs: Vec::new(), indices: Vec::new(), groups: Vec::new() };
+    let mut has_normals = false;
+    let mut has_uvs = false;
+    let mut vertices: HashMap<(usize, Option<usize>, Option<usize>), usize> = HashMap::new();
+    let mut group = String::from("default");
+
+    for (number, text) in reader.lines().enumerate() {
+        let text = text?;
+        let line = number + 1;
+        let text = match text.find('#') {
+            Some(i) => &text[..i],
+            None => &text[..],
+        };
+
+        let mut values = text.split_whitespace();
+        let keyword = match values.next() {
+            Some(k) => k,
+            None => continue,
+        };
+
+        match keyword {
+            "v" => {
+                let v = parse_floats(values, 3, line, keyword)?;
+                positions.push(Vector3::new(v[0], v[1], v[2]));
+            },
+            "vn" => {
+                let v = parse_floats(values, 3, line, keyword)?;
+                normals.push(Vector3::new(v[0], v[1], v[2]));
+            },
+            "vt" => {
+                let v = parse_floats(values, 1, line, keyword)?;
+                uvs.push(Vector2::new(v[0], v.get(1).cloned().unwrap_or(0.0)));
+            },
+            "g" | "o" => {
+                let name = values.collect::<Vec<&str>>().join(" ");
+                group = if name.is_empty() { String::from("default") } else { name };
+            },
+            "f" => {
+                let mut face = Vec::new();
+                for corner in values {
+                    let mut parts = corner.split('/');
+                    let p = resolve_index(parts.next().unwrap_or(""), positions.len(), line)?;
+                    let t = match parts.next() {
+                        Some(t) if !t.is_empty() => Some(resolve_index(t, uvs.len(), line)?),
+                        _ => None,
+                    };
+                    let n = match parts.next() {
+                        Some(n) if !n.is_empty() => Some(resolve_index(n, normals.len(), line)?),
+                        _ => None,
+                    };
+
+                    has_uvs |= t.is_some();
+                    has_normals |= n.is_some();
+                    let next = mesh.positions.len();
+                    let index = *vertices.entry((p, t, n)).or_insert(next);
+                    if index == next {
+                        mesh.positions.push(positions[p]);
+                        mesh.uvs.push(t.map_or(Vector2::zero(), |t| uvs[t]));
+                        mesh.normals.push(n.map_or(Vector3::zero(), |n| normals[n]));
+                    }
+                    face.push(index);
+                }
+
+                if face.len() < 3 {
+                    return Err(ObjError::TooFewVertices { line });
+                }
+
+                let start = mesh.indices.len();
+                let points: Vec<Vector3> = face.iter().map(|&i| mesh.positions[i]).collect();
+                for t in triangulate_polygon(&points) {
+                    mesh.indices.push([face[t[0]], face[t[1]], face[t[2]]]);
+                }
+
+                let added = mesh.indices.len() - start;
+                match mesh.groups.last_mut() {
+                    Some(ref mut last) if last.name == group => last.count += added,
+                    _ => mesh.groups.push(ObjGroup { name: group.clone(), start, count: added }),
+                }
+            },
+            _ => {},
+        }
+    }
+
+    if !has_normals {
+        mesh.normals.clear();
+    }
+    if !has_uvs {
+        mesh.uvs.clear();
+    }
+
+    Ok(mesh)
+}
+
+/// Writes `mesh` to `writer` as a Wavefront OBJ file, keeping the order of its triangles.
+/// Returns an `InvalidInput` error if the groups are not ordered, disjoint ranges of the triangles.
+pub fn write_obj<W: Write>(writer: &mut W, mesh: &ObjMesh) -> io::Result<()> {
+    for p in &mesh.positions {
+        writeln!(writer, "v {} {} {}", p.x, p.y, p.z)?;
+    }
+    for uv in &mesh.uvs {
+        writeln!(writer, "vt {} {}", uv.x, uv.y)?;
+    }
+    for n in &mesh.normals {
+        writeln!(writer, "vn {} {} {}", n.x, n.y, n.z)?;
+    }
+
+    let has_uvs = !mesh.uvs.is_empty();
+    let has_normals = !mesh.normals.is_empty();
+    let corner = |i: usize| match (has_uvs, has_normals) {
+        (true, true) => format!("{0}/{0}/{0}", i + 1),
+        (true, false) => format!("{0}/{0}", i + 1),
+        (false, true) => format!("{0}//{0}", i + 1),
+        (false, false) => format!("{}", i + 1),
+    };
+
+    // Groups are written in the order of their triangles, so they must be ordered, disjoint and in range.
+    let mut end = 0;
+    for group in &mesh.groups {
+        if group.start < end || group.start > mesh.indices.len() || group.count > mesh.indices.len() - group.start {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("OBJ group {} is out of order or out of range", group.name)));
+        }
+        end = group.start + group.count;
+    }
+
+    let mut groups = mesh.groups.iter().filter(|group| group.count > 0).peekable();
+    let mut group_end = None;
+    for (i, face) in mesh.indices.iter().enumerate() {
+        if let Some(group) = groups.next_if(|group| group.start == i) {
+            writeln!(writer, "g {}", group.name)?;
+            group_end = Some(group.start + group.count);
+        } else if group_end == Some(i) {
+            // An empty group name returns the following triangles to the default group.
+            writeln!(writer, "g")?;
+            group_end = None;
+        }
+        writeln!(writer, "f {} {} {}", corner(face[0]), corner(face[1]), corner(face[2]))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use obj::{read_obj, write_obj, ObjError};
+    use std::io;
+    use vector3::Vector3;
+
+    const CUBE_SIDE: &str = "# two faces of a cube
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 1 0 -1
+v 1 1 -1
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+vn 1 0 0
+g front
+f 1/1/1 2/2/1 3/3/1 4/4/1
+g right
+f -5/1/2 -2/2/2 -1/3/2 -4/4/2
+";
+
+    #[test]
+    fn test_read_obj() {
+        let mesh = read_obj(CUBE_SIDE.as_bytes()).unwrap();
+
+        assert_eq!(mesh.indices.len(), 4);
+        assert_eq!(mesh.positions.len(), 8);
+        assert_eq!(mesh.normals.len(), 8);
+        assert_eq!(mesh.groups.len(), 2);
+        assert_eq!(mesh.groups[1].name, "right");
+        assert_eq!(mesh.groups[1].start, 2);
+        assert!((mesh.to_triangle_mesh().unwrap().surface_area() - 2.0).abs() < 0.0001);
+        assert!((mesh.normals[mesh.indices[2][0]] - Vector3::new(1.0, 0.0, 0.0)).length() < 0.0001);
+    }
+
+    #[test]
+    fn test_read_obj_errors() {
+        match read_obj("v 0 0 0\nv 1 0 0\nf 1 2 3\n".as_bytes()) {
+            Err(ObjError::InvalidIndex { line: 3, .. }) => {},
+            other => panic!("unexpected {:?}", other),
+        }
+        match read_obj("v 0 zero 0\n".as_bytes()) {
+            Err(ObjError::InvalidNumber { line: 1, ref value }) => assert_eq!(value, "zero"),
+            other => panic!("unexpected {:?}", other),
+        }
+        match read_obj("v 0 0\n".as_bytes()) {
+            Err(ObjError::MissingValue { line: 1, .. }) => {},
+            other => panic!("unexpected {:?}", other),
+        }
+        match read_obj("v 0 0 0\nv 1 0 0\nf 1 2\n".as_bytes()) {
+            Err(ObjError::TooFewVertices { line: 3 }) => {},
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_write_obj_round_trip() {
+        let mesh = read_obj(CUBE_SIDE.as_bytes()).unwrap();
+        let mut data = Vec::new();
+        write_obj(&mut data, &mesh).unwrap();
+        let copy = read_obj(&data[..]).unwrap();
+
+        assert_eq!(copy.indices, mesh.indices);
+        assert_eq!(copy.groups, mesh.groups);
+        assert_eq!(copy.uvs.len(), mesh.uvs.len());
+
+        // Triangles outside every group keep their place between the groups.
+        let mut mesh = read_obj("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\ng a\nf 2 3 1\ng\nf 3 1 2\n".as_bytes()).unwrap();
+        mesh.groups.retain(|group| group.name == "a");
+        let mut data = Vec::new();
+        write_obj(&mut data, &mesh).unwrap();
+        let copy = read_obj(&data[..]).unwrap();
+        assert_eq!(copy.indices, mesh.indices);
+        assert_eq!(copy.groups.iter().map(|group| (group.name.as_str(), group.start, group.count)).collect::<Vec<_>>(),
+                   vec![("default", 0, 1), ("a", 1, 1), ("default", 2, 1)]);
+
+        mesh.groups[0].count = 3;
+        assert_eq!(write_obj(&mut Vec::new(), &mesh).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+}
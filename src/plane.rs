@@ -0,0 +1,223 @@
+use vector3::Vector3;
+use vector4::Vector4;
+use matrix4::Matrix4;
+use ray::Ray;
+
+/// A plane defined by the equation `dot(normal, p) + d = 0`.
+#[derive(Debug, Copy, Clone)]
+pub struct Plane {
+    pub normal: Vector3,
+    pub d: f32,
+}
+
+impl Plane {
+    /// Returns a new `Plane`. `normal` is expected to be normalized.
+    pub fn new(normal: Vector3, d: f32) -> Plane {
+        Plane { normal, d }
+    }
+
+    /// Returns a `Plane` passing through `point` facing along `normal`.
+    pub fn from_point_normal(point: Vector3, normal: Vector3) -> Plane {
+        let normal = normal.normalized();
+        Plane { normal, d: -Vector3::dot(normal, point) }
+    }
+
+    /// Returns the `Plane` passing through the points `v0`, `v1` and `v2`.
+    /// The normal faces towards the side the points appear counter-clockwise from.
+    /// Returns `None` if the points are collinear.
+    pub fn from_points(v0: Vector3, v1: Vector3, v2: Vector3) -> Option<Plane> {
+        let n = Vector3::cross(v1 - v0, v2 - v0);
+        if n.length_squared() <= f32::EPSILON * f32::EPSILON {
+            return None;
+        }
+
+        Some(Plane::from_point_normal(v0, n))
+    }
+
+    /// Returns the plane equation coefficients `(a, b, c, d)` as a `Vector4`.
+    pub fn to_vector4(&self) -> Vector4 {
+        self.normal.to_vector4(self.d)
+    }
+
+    /// Returns a copy of the plane scaled so the normal has a length of 1.
+    pub fn normalized(&self) -> Plane {
+        let l = self.normal.length();
+        Plane { normal: self.normal / l, d: self.d / l }
+    }
+
+    /// Returns the plane facing the opposite direction.
+    pub fn flipped(&self) -> Plane {
+        Plane { normal: -self.normal, d: -self.d }
+    }
+
+    /// Returns the signed distance from the plane to `point`.
+    /// The distance is positive on the side the normal faces.
+    pub fn distance(&self, point: Vector3) -> f32 {
+        Vector3::dot(self.normal, point) + self.d
+    }
+
+    /// Returns the closest point on the plane to `point`.
+    pub fn project_point(&self, point: Vector3) -> Vector3 {
+        point - self.normal * self.distance(point)
+    }
+
+    /// Returns the point on the plane closest to the origin.
+    pub fn origin(&self) -> Vector3 {
+        self.normal * -self.d
+    }
+
+    /// Returns a matrix that reflects points across the plane.
+    pub fn reflection(&self) -> Matrix4 {
+        let n = self.normal;
+        let d = self.d;
+
+        Matrix4 {data: [[1.0 - 2.0 * n.x * n.x,      -2.0 * n.x * n.y,      -2.0 * n.x * n.z, -2.0 * n.x * d],
+                        [     -2.0 * n.y * n.x, 1.0 - 2.0 * n.y * n.y,      -2.0 * n.y * n.z, -2.0 * n.y * d],
+                        [     -2.0 * n.z * n.x,      -2.0 * n.z * n.y, 1.0 - 2.0 * n.z * n.z, -2.0 * n.z * d],
+                        [                  0.0,                   0.0,                   0.0,            1.0]]}
+    }
+
+    /// Returns the plane transformed by the matrix `m`.
+    /// Returns `None` if `m` is not invertible.
+    pub fn transform(&self, m: Matrix4) -> Option<Plane> {
+        let p = m.inverse()?.transpose() * self.to_vector4();
+        Some(Plane::new(p.xyz(), p.w).normalized())
+    }
+
+    /// Returns the time along `ray` where it crosses the plane.
+    /// Returns `None` if the ray is parallel to the plane or points away from it.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<f32> {
+        let denom = Vector3::dot(self.normal, ray.direction());
+        if denom.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let t = -self.distance(ray.origin()) / denom;
+        if t < 0.0 {
+            return None;
+        }
+
+        Some(t)
+    }
+
+    /// Returns the point where the infinite line through `p0` and `p1` crosses the plane.
+    /// Returns `None` if the line is parallel to the plane.
+    pub fn intersect_line(&self, p0: Vector3, p1: Vector3) -> Option<Vector3> {
+        let direction = p1 - p0;
+        let denom = Vector3::dot(self.normal, direction);
+        if denom.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        Some(p0 + direction * (-self.distance(p0) / denom))
+    }
+
+    /// Returns the point where the segment from `p0` to `p1` crosses the plane.
+    /// Returns `None` if both end points are on the same side of the plane.
+    pub fn intersect_segment(&self, p0: Vector3, p1: Vector3) -> Option<Vector3> {
+        let d0 = self.distance(p0);
+        let d1 = self.distance(p1);
+        if (d0 > 0.0 && d1 > 0.0) || (d0 < 0.0 && d1 < 0.0) || d0 == d1 {
+            return None;
+        }
+
+        Some(p0 + (p1 - p0) * (d0 / (d0 - d1)))
+    }
+
+    /// Returns the line where the planes `p0` and `p1` meet as (Point, Direction).
+    /// Returns `None` if the planes are parallel.
+    pub fn intersect_plane(p0: &Plane, p1: &Plane) -> Option<(Vector3, Vector3)> {
+        let direction = Vector3::cross(p0.normal, p1.normal);
+        let denom = direction.length_squared();
+        if denom <= f32::EPSILON {
+            return None;
+        }
+
+        let point = Vector3::cross(p0.normal * p1.d - p1.normal * p0.d, direction) / denom;
+        Some((point, direction))
+    }
+
+    /// Returns the point where the planes `p0`, `p1` and `p2` meet.
+    /// Returns `None` if any two of the planes are parallel.
+    pub fn intersect_planes(p0: &Plane, p1: &Plane, p2: &Plane) -> Option<Vector3> {
+        let n12 = Vector3::cross(p1.normal, p2.normal);
+        let denom = Vector3::dot(p0.normal, n12);
+        if denom.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let n20 = Vector3::cross(p2.normal, p0.normal);
+        let n01 = Vector3::cross(p0.normal, p1.normal);
+
+        Some((n12 * -p0.d + n20 * -p1.d + n01 * -p2.d) / denom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plane::Plane;
+    use vector3::Vector3;
+    use matrix4::Matrix4;
+    use ray::Ray;
+
+    fn assert_near(a: Vector3, b: Vector3) {
+        assert!((a - b).length() < 0.0001, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_plane_from_points() {
+        let p = Plane::from_points(Vector3::new(0.0, 2.0, 0.0),
+                                   Vector3::new(0.0, 2.0, 1.0),
+                                   Vector3::new(1.0, 2.0, 0.0)).unwrap();
+
+        assert_near(p.normal, Vector3::up());
+        assert_eq!(p.d, -2.0);
+        assert_eq!(p.distance(Vector3::new(5.0, 5.0, 5.0)), 3.0);
+        assert_near(p.project_point(Vector3::new(5.0, 5.0, 5.0)), Vector3::new(5.0, 2.0, 5.0));
+
+        assert!(Plane::from_points(Vector3::zero(), Vector3::left(), Vector3::left() * 2.0).is_none());
+    }
+
+    #[test]
+    fn test_plane_reflection() {
+        let p = Plane::from_point_normal(Vector3::new(0.0, 1.0, 0.0), Vector3::up());
+        let v = p.reflection() * Vector3::new(3.0, 4.0, -1.0);
+
+        assert_near(v, Vector3::new(3.0, -2.0, -1.0));
+    }
+
+    #[test]
+    fn test_plane_transform() {
+        let p = Plane::from_point_normal(Vector3::zero(), Vector3::up());
+        let m = Matrix4::translation(0.0, 3.0, 0.0) * Matrix4::scale(1.0, 2.0, 1.0);
+        let t = p.transform(m).unwrap();
+
+        assert_near(t.normal, Vector3::up());
+        assert!((t.d + 3.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_plane_intersect_ray() {
+        let p = Plane::from_point_normal(Vector3::new(0.0, 0.0, 5.0), Vector3::forward());
+        let r = Ray::new(Vector3::zero(), Vector3::forward(), 0.0);
+
+        assert_eq!(p.intersect_ray(&r), Some(5.0));
+        assert!(p.intersect_ray(&Ray::new(Vector3::zero(), -Vector3::forward(), 0.0)).is_none());
+        assert!(p.intersect_ray(&Ray::new(Vector3::zero(), Vector3::up(), 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_plane_intersect_planes() {
+        let x = Plane::from_point_normal(Vector3::new(1.0, 0.0, 0.0), Vector3::left());
+        let y = Plane::from_point_normal(Vector3::new(0.0, 2.0, 0.0), Vector3::up());
+        let z = Plane::from_point_normal(Vector3::new(0.0, 0.0, 3.0), Vector3::forward());
+
+        assert_near(Plane::intersect_planes(&x, &y, &z).unwrap(), Vector3::new(1.0, 2.0, 3.0));
+        assert!(Plane::intersect_planes(&x, &x, &z).is_none());
+
+        let (point, direction) = Plane::intersect_plane(&x, &y).unwrap();
+        assert!(x.distance(point).abs() < 0.0001);
+        assert!(y.distance(point).abs() < 0.0001);
+        assert_near(direction.normalized(), Vector3::forward());
+    }
+}
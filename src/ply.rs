@@ -0,0 +1,501 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+use vector2::Vector2;
+use vector3::Vector3;
+use mesh::{TriangleMesh, triangulate_polygon};
+
+/// An error from reading a PLY file.
+#[derive(Debug)]
+pub enum PlyError {
+    /// Reading from the source failed.
+    Io(io::Error),
+    /// The data does not start with the `ply` magic line.
+    MissingMagic,
+    /// The header names a format other than ascii, binary_little_endian or binary_big_endian.
+    UnsupportedFormat(String),
+    /// A header line could not be understood.
+    InvalidHeader { line: usize, message: String },
+    /// The vertex element is missing a required property such as `x` or `vertex_indices`.
+    MissingProperty(String),
+    /// An ascii value could not be parsed as a number.
+    InvalidNumber(String),
+    /// The data ended before every element was read.
+    UnexpectedEof,
+    /// A face refers to a vertex that does not exist.
+    InvalidIndex { face: usize, index: i64 },
+    /// A face has fewer than 3 vertices.
+    TooFewVertices { face: usize },
+}
+
+impl fmt::Display for PlyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PlyError::Io(ref e) => write!(f, "failed to read PLY: {}", e),
+            PlyError::MissingMagic => write!(f, "data does not start with `ply`"),
+            PlyError::UnsupportedFormat(ref format) => write!(f, "unsupported PLY format `{}`", format),
+            PlyError::InvalidHeader { line, ref message } => write!(f, "header line {}: {}", line, message),
+            PlyError::MissingProperty(ref name) => write!(f, "missing property `{}`", name),
+            PlyError::InvalidNumber(ref value) => write!(f, "`{}` is not a number", value),
+            PlyError::UnexpectedEof => write!(f, "data ended before all elements were read"),
+            PlyError::InvalidIndex { face, index } => write!(f, "face {} refers to missing vertex {}", face, index),
+            PlyError::TooFewVertices { face } => write!(f, "face {} has fewer than 3 vertices", face),
+        }
+    }
+}
+
+impl Error for PlyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            PlyError::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PlyError {
+    fn from(e: io::Error) -> PlyError {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            return PlyError::UnexpectedEof;
+        }
+
+        PlyError::Io(e)
+    }
+}
+
+/// The encoding of the elements in a PLY file.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+/// Triangles read from a PLY file.
+#[derive(Debug, Clone)]
+pub struct PlyMesh {
+    pub positions: Vec<Vector3>,
+    /// The vertex normals, either empty or one per position.
+    pub normals: Vec<Vector3>,
+    /// The vertex texture coordinates, either empty or one per position.
+    pub uvs: Vec<Vector2>,
+    pub indices: Vec<[usize; 3]>,
+}
+
+impl PlyMesh {
+    /// Returns a `TriangleMesh` with the positions, normals and texture coordinates of the PLY mesh.
+    /// Returns `None` if an index is outside the positions.
+    pub fn to_triangle_mesh(&self) -> Option<TriangleMesh> {
+        TriangleMesh::with_attributes(self.positions.clone(), self.normals.clone(), self.uvs.clone(), self.indices.clone())
+    }
+}
+
+/// The type of a PLY property value.
+#[derive(Debug, Copy, Clone)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    /// Returns the type with the PLY name `name`.
+    fn parse(name: &str) -> Option<Scalar> {
+        match name {
+            "char" | "int8" => Some(Scalar::I8),
+            "uchar" | "uint8" => Some(Scalar::U8),
+            "short" | "int16" => Some(Scalar::I16),
+            "ushort" | "uint16" => Some(Scalar::U16),
+            "int" | "int32" => Some(Scalar::I32),
+            "uint" | "uint32" => Some(Scalar::U32),
+            "float" | "float32" => Some(Scalar::F32),
+            "double" | "float64" => Some(Scalar::F64),
+            _ => None,
+        }
+    }
+
+    /// Returns the size of the type in bytes.
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+
+    /// Returns the value of the type stored in `bytes`.
+    fn decode(self, bytes: &[u8], little_endian: bool) -> f64 {
+        let mut b = [0u8; 8];
+        b[..self.size()].copy_from_slice(&bytes[..self.size()]);
+        if !little_endian {
+            b[..self.size()].reverse();
+        }
+
+        match self {
+            Scalar::I8 => b[0] as i8 as f64,
+            Scalar::U8 => b[0] as f64,
+            Scalar::I16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+            Scalar::U16 => u16::from_le_bytes([b[0], b[1]]) as f64,
+            Scalar::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::F64 => f64::from_le_bytes(b),
+        }
+    }
+}
+
+/// A property of a PLY element, either a single value or a list of values.
+#[derive(Debug)]
+struct Property {
+    name: String,
+    count: Option<Scalar>,
+    value: Scalar,
+}
+
+/// An element declared in a PLY header.
+#[derive(Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// A source of PLY values in either encoding.
+enum Values<'a, R: 'a> {
+    Ascii(::std::vec::IntoIter<String>),
+    Binary(&'a mut R, bool),
+}
+
+impl<'a, R: Read> Values<'a, R> {
+    /// Returns the next value of type `scalar`.
+    fn next(&mut self, scalar: Scalar) -> Result<f64, PlyError> {
+        match *self {
+            Values::Ascii(ref mut tokens) => {
+                let token = tokens.next().ok_or(PlyError::UnexpectedEof)?;
+                token.parse::<f64>().map_err(|_| PlyError::InvalidNumber(token))
+            },
+            Values::Binary(ref mut reader, little_endian) => {
+                let mut bytes = [0u8; 8];
+                reader.read_exact(&mut bytes[..scalar.size()])?;
+                Ok(scalar.decode(&bytes, little_endian))
+            },
+        }
+    }
+}
+
+/// Returns the index of the first property of `element` named one of `names`.
+fn find_property(element: &Element, names: &[&str]) -> Option<usize> {
+    element.properties.iter().position(|p| p.count.is_none() && names.contains(&p.name.as_str()))
+}
+
+/// Reads the header lines of a PLY file, returning the format and the declared elements.
+fn read_header<R: BufRead>(reader: &mut R) -> Result<(PlyFormat, Vec<Element>), PlyError> {
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+
+    let mut line = 0;
+    let mut text = String::new();
+    loop {
+        text.clear();
+        if reader.read_line(&mut text)? == 0 {
+            return Err(PlyError::UnexpectedEof);
+        }
+        line += 1;
+
+        let mut words = text.split_whitespace();
+        let keyword = words.next().unwrap_or("");
+        if line == 1 {
+            if keyword != "ply" {
+                return Err(PlyError::MissingMagic);
+            }
+            continue;
+        }
+
+        let invalid = |message: &str| PlyError::InvalidHeader { line, message: message.to_string() };
+        match keyword {
+            "format" => {
+                format = Some(match words.next() {
+                    Some("ascii") => PlyFormat::Ascii,
+                    Some("binary_little_endian") => PlyFormat::BinaryLittleEndian,
+                    Some("binary_big_endian") => PlyFormat::BinaryBigEndian,
+                    other => return Err(PlyError::UnsupportedFormat(other.unwrap_or("").to_string())),
+                });
+            },
+            "element" => {
+                let name = words.next().ok_or_else(|| invalid("element has no name"))?;
+                let count = words.next()
+                    .and_then(|c| c.parse::<usize>().ok())
+                    .ok_or_else(|| invalid("element has no count"))?;
+                elements.push(Element { name: name.to_string(), count, properties: Vec::new() });
+            },
+            "property" => {
+                let element = elements.last_mut().ok_or_else(|| invalid("property before any element"))?;
+                let kind = words.next().unwrap_or("");
+                let property = if kind == "list" {
+                    let count = words.next().and_then(Scalar::parse).ok_or_else(|| invalid("unknown list count type"))?;
+                    let value = words.next().and_then(Scalar::parse).ok_or_else(|| invalid("unknown list value type"))?;
+                    let name = words.next().ok_or_else(|| invalid("property has no name"))?;
+                    Property { name: name.to_string(), count: Some(count), value }
+                } else {
+                    let value = Scalar::parse(kind).ok_or_else(|| invalid("unknown property type"))?;
+                    let name = words.next().ok_or_else(|| invalid("property has no name"))?;
+                    Property { name: name.to_string(), count: None, value }
+                };
+                element.properties.push(property);
+            },
+            "end_header" => break,
+            "comment" | "obj_info" | "" => {},
+            _ => return Err(invalid("unknown header keyword")),
+        }
+    }
+
+    let format = format.ok_or(PlyError::InvalidHeader { line, message: "missing format".to_string() })?;
+    Ok((format, elements))
+}
+
+/// Reads triangles from the PLY data in `reader`.
+/// Faces are triangulated and elements other than `vertex` and `face` are skipped.
+pub fn read_ply<R: BufRead>(mut reader: R) -> Result<PlyMesh, PlyError> {
+    let (format, elements) = read_header(&mut reader)?;
+
+    let mut values = match format {
+        PlyFormat::Ascii => {
+            let mut text = String::new();
+            reader.read_to_string(&mut text)?;
+            let tokens: Vec<String> = text.split_whitespace().map(|t| t.to_string()).collect();
+            Values::Ascii(tokens.into_iter())
+        },
+        PlyFormat::BinaryLittleEndian => Values::Binary(&mut reader, true),
+        PlyFormat::BinaryBigEndian => Values::Binary(&mut reader, false),
+    };
+
+    let mut mesh = PlyMesh { positions: Vec::new(), normals: Vec::new(), uvs: Vec::new(), indices: Vec::new() };
+    let mut faces: Vec<Vec<i64>> = Vec::new();
+    for element in &elements {
+        let is_vertex = element.name == "vertex";
+        let is_face = element.name == "face";
+
+        let (position, normal, uv) = if is_vertex {
+            let mut position = [0; 3];
+            for (slot, name) in position.iter_mut().zip(["x", "y", "z"].iter()) {
+                *slot = find_property(element, &[name]).ok_or_else(|| PlyError::MissingProperty(name.to_string()))?;
+            }
+
+            let normal = match (find_property(element, &["nx"]), find_property(element, &["ny"]), find_property(element, &["nz"])) {
+                (Some(x), Some(y), Some(z)) => Some([x, y, z]),
+                _ => None,
+            };
+            let uv = match (find_property(element, &["u", "s", "texture_u", "texture_s"]),
+                            find_property(element, &["v", "t", "texture_v", "texture_t"])) {
+                (Some(u), Some(v)) => Some([u, v]),
+                _ => None,
+            };
+            (Some(position), normal, uv)
+        } else {
+            (None, None, None)
+        };
+
+        let list = if is_face {
+            let list = element.properties.iter().position(|p| p.count.is_some() && (p.name == "vertex_indices" || p.name == "vertex_index"));
+            Some(list.ok_or_else(|| PlyError::MissingProperty("vertex_indices".to_string()))?)
+        } else {
+            None
+        };
+
+        let mut scalars = Vec::with_capacity(element.properties.len());
+        for _ in 0..element.count {
+            scalars.clear();
+            let mut indices = Vec::new();
+            for (p, property) in element.properties.iter().enumerate() {
+                match property.count {
+                    Some(count) => {
+                        let count = values.next(count)? as usize;
+                        for _ in 0..count {
+                            let value = values.next(property.value)?;
+                            if list == Some(p) {
+                                indices.push(value as i64);
+                            }
+                        }
+                        scalars.push(0.0);
+                    },
+                    None => scalars.push(values.next(property.value)?),
+                }
+            }
+
+            if let Some(p) = position {
+                mesh.positions.push(Vector3::new(scalars[p[0]] as f32, scalars[p[1]] as f32, scalars[p[2]] as f32));
+            }
+            if let Some(n) = normal {
+                mesh.normals.push(Vector3::new(scalars[n[0]] as f32, scalars[n[1]] as f32, scalars[n[2]] as f32));
+            }
+            if let Some(uv) = uv {
+                mesh.uvs.push(Vector2::new(scalars[uv[0]] as f32, scalars[uv[1]] as f32));
+            }
+            if is_face {
+                faces.push(indices);
+            }
+        }
+    }
+
+    for (f, face) in faces.iter().enumerate() {
+        if face.len() < 3 {
+            return Err(PlyError::TooFewVertices { face: f });
+        }
+        if let Some(&index) = face.iter().find(|&&i| i < 0 || i as usize >= mesh.positions.len()) {
+            return Err(PlyError::InvalidIndex { face: f, index });
+        }
+
+        let points: Vec<Vector3> = face.iter().map(|&i| mesh.positions[i as usize]).collect();
+        for t in triangulate_polygon(&points) {
+            mesh.indices.push([face[t[0]] as usize, face[t[1]] as usize, face[t[2]] as usize]);
+        }
+    }
+
+    Ok(mesh)
+}
+
+/// Writes `mesh` to `writer` as a PLY file in `format`.
+/// Vertex values are written as floats and face indices as a uchar counted list of ints.
+pub fn write_ply<W: Write>(writer: &mut W, mesh: &PlyMesh, format: PlyFormat) -> io::Result<()> {
+    let has_normals = !mesh.normals.is_empty() && mesh.normals.len() == mesh.positions.len();
+    let has_uvs = !mesh.uvs.is_empty() && mesh.uvs.len() == mesh.positions.len();
+
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format {} 1.0", match format {
+        PlyFormat::Ascii => "ascii",
+        PlyFormat::BinaryLittleEndian => "binary_little_endian",
+        PlyFormat::BinaryBigEndian => "binary_big_endian",
+    })?;
+    writeln!(writer, "element vertex {}", mesh.positions.len())?;
+    writeln!(writer, "property float x\nproperty float y\nproperty float z")?;
+    if has_normals {
+        writeln!(writer, "property float nx\nproperty float ny\nproperty float nz")?;
+    }
+    if has_uvs {
+        writeln!(writer, "property float u\nproperty float v")?;
+    }
+    writeln!(writer, "element face {}", mesh.indices.len())?;
+    writeln!(writer, "property list uchar int vertex_indices")?;
+    writeln!(writer, "end_header")?;
+
+    let mut vertex = Vec::with_capacity(8);
+    for i in 0..mesh.positions.len() {
+        let p = mesh.positions[i];
+        vertex.clear();
+        vertex.extend_from_slice(&[p.x, p.y, p.z]);
+        if has_normals {
+            let n = mesh.normals[i];
+            vertex.extend_from_slice(&[n.x, n.y, n.z]);
+        }
+        if has_uvs {
+            vertex.extend_from_slice(&[mesh.uvs[i].x, mesh.uvs[i].y]);
+        }
+
+        match format {
+            PlyFormat::Ascii => {
+                let text: Vec<String> = vertex.iter().map(|v| v.to_string()).collect();
+                writeln!(writer, "{}", text.join(" "))?;
+            },
+            PlyFormat::BinaryLittleEndian => for v in &vertex { writer.write_all(&v.to_le_bytes())?; },
+            PlyFormat::BinaryBigEndian => for v in &vertex { writer.write_all(&v.to_be_bytes())?; },
+        }
+    }
+
+    for face in &mesh.indices {
+        match format {
+            PlyFormat::Ascii => writeln!(writer, "3 {} {} {}", face[0], face[1], face[2])?,
+            PlyFormat::BinaryLittleEndian => {
+                writer.write_all(&[3])?;
+                for &i in face { writer.write_all(&(i as i32).to_le_bytes())?; }
+            },
+            PlyFormat::BinaryBigEndian => {
+                writer.write_all(&[3])?;
+                for &i in face { writer.write_all(&(i as i32).to_be_bytes())?; }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ply::{read_ply, write_ply, PlyError, PlyFormat};
+
+    const QUAD: &str = "ply
+format ascii 1.0
+comment a unit quad with an extra element
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+element face 1
+property list uchar int vertex_indices
+element edge 1
+property int vertex1
+property int vertex2
+end_header
+0 0 0 255
+1 0 0 255
+1 1 0 255
+0 1 0 255
+4 0 1 2 3
+0 2
+";
+
+    #[test]
+    fn test_read_ply_ascii() {
+        let mesh = read_ply(QUAD.as_bytes()).unwrap();
+
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.indices.len(), 2);
+        assert!(mesh.normals.is_empty());
+        assert_eq!(mesh.to_triangle_mesh().unwrap().surface_area(), 1.0);
+    }
+
+    #[test]
+    fn test_ply_round_trip() {
+        let mesh = read_ply(QUAD.as_bytes()).unwrap();
+
+        for &format in [PlyFormat::Ascii, PlyFormat::BinaryLittleEndian, PlyFormat::BinaryBigEndian].iter() {
+            let mut data = Vec::new();
+            write_ply(&mut data, &mesh, format).unwrap();
+            let copy = read_ply(&data[..]).unwrap();
+
+            assert_eq!(copy.indices, mesh.indices);
+            assert_eq!(copy.positions[2].x, 1.0);
+            assert_eq!(copy.positions[2].y, 1.0);
+        }
+    }
+
+    #[test]
+    fn test_read_ply_errors() {
+        match read_ply("obj\n".as_bytes()) {
+            Err(PlyError::MissingMagic) => {},
+            other => panic!("unexpected {:?}", other),
+        }
+        match read_ply("ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nend_header\n0\n".as_bytes()) {
+            Err(PlyError::MissingProperty(ref name)) => assert_eq!(name, "y"),
+            other => panic!("unexpected {:?}", other),
+        }
+        match read_ply(QUAD.replace("4 0 1 2 3", "3 0 1 7").as_bytes()) {
+            Err(PlyError::InvalidIndex { face: 0, index: 7 }) => {},
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let mut data = Vec::new();
+        write_ply(&mut data, &read_ply(QUAD.as_bytes()).unwrap(), PlyFormat::BinaryLittleEndian).unwrap();
+        data.truncate(data.len() - 3);
+        match read_ply(&data[..]) {
+            Err(PlyError::UnexpectedEof) => {},
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
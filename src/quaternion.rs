@@ -1,6 +1,7 @@
 use std::f32;
 use std::ops::{Mul};
 use vector3::Vector3;
+use matrix4::Matrix4;
 
 /// A quaternion of `f32` values.
 #[derive(Debug, Copy, Clone)]
@@ -38,6 +39,70 @@ impl Quaternion {
     pub fn identity() -> Quaternion {
         Quaternion { x: 0.0, y: 0.0, z: 0.0, w: 1.0 }
     }
+
+    /// Returns a `Quaternion` that rotates `angle` radians around `axis`.
+    pub fn from_axis_angle(axis: Vector3, angle: f32) -> Quaternion {
+        let a = axis.normalized() * (angle / 2.0).sin();
+        Quaternion { x: a.x, y: a.y, z: a.z, w: (angle / 2.0).cos() }
+    }
+
+    /// Returns the `Quaternion` for the rotation part of the matrix `m`.
+    /// The upper 3x3 part of `m` is expected to be orthonormal.
+    pub fn from_rotation_matrix(m: Matrix4) -> Quaternion {
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quaternion { x: (m[2][1] - m[1][2]) / s,
+                         y: (m[0][2] - m[2][0]) / s,
+                         z: (m[1][0] - m[0][1]) / s,
+                         w: 0.25 * s }
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+            Quaternion { x: 0.25 * s,
+                         y: (m[0][1] + m[1][0]) / s,
+                         z: (m[0][2] + m[2][0]) / s,
+                         w: (m[2][1] - m[1][2]) / s }
+        } else if m[1][1] > m[2][2] {
+            let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+            Quaternion { x: (m[0][1] + m[1][0]) / s,
+                         y: 0.25 * s,
+                         z: (m[1][2] + m[2][1]) / s,
+                         w: (m[0][2] - m[2][0]) / s }
+        } else {
+            let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+            Quaternion { x: (m[0][2] + m[2][0]) / s,
+                         y: (m[1][2] + m[2][1]) / s,
+                         z: 0.25 * s,
+                         w: (m[1][0] - m[0][1]) / s }
+        };
+
+        q.normalized()
+    }
+
+    /// Returns the `Quaternion` that rotates the `x`, `y` and `z` axes onto `x_axis`, `y_axis` and `z_axis`.
+    /// The axes are expected to be orthonormal and right handed.
+    pub fn from_axes(x_axis: Vector3, y_axis: Vector3, z_axis: Vector3) -> Quaternion {
+        Quaternion::from_rotation_matrix(Matrix4 {data: [[x_axis.x, y_axis.x, z_axis.x, 0.0],
+                                                         [x_axis.y, y_axis.y, z_axis.y, 0.0],
+                                                         [x_axis.z, y_axis.z, z_axis.z, 0.0],
+                                                         [     0.0,      0.0,      0.0, 1.0]]})
+    }
+
+    /// Returns the length of the quaternion.
+    pub fn length(&self) -> f32 {
+        (self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w).sqrt()
+    }
+
+    /// Returns a new normalized `Quaternion` of the quaternion.
+    pub fn normalized(&self) -> Quaternion {
+        let l = self.length();
+        Quaternion { x: self.x / l, y: self.y / l, z: self.z / l, w: self.w / l }
+    }
+
+    /// Returns the conjugate of the quaternion, which is its inverse rotation when normalized.
+    pub fn conjugate(&self) -> Quaternion {
+        Quaternion { x: -self.x, y: -self.y, z: -self.z, w: self.w }
+    }
 }
 
 impl Mul for Quaternion {
@@ -59,15 +124,46 @@ impl Mul for Quaternion {
     }
 }
 
+impl Mul<Vector3> for Quaternion {
+    type Output = Vector3;
+
+    fn mul(self, other: Vector3) -> Vector3 {
+        let qv = Vector3::new(self.x, self.y, self.z);
+        let t = Vector3::cross(qv, other) * 2.0;
+
+        other + t * self.w + Vector3::cross(qv, t)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use quaternion::Quaternion;
-    use std::f32::consts::FRAC_PI_2;
+    use vector3::Vector3;
+    use matrix4::Matrix4;
+    use std::f32::consts::{FRAC_PI_2, FRAC_1_SQRT_2};
 
     #[test]
     fn test_quaternion_new() {
         let q = Quaternion::new(0.0, FRAC_PI_2, 0.0);
-        assert!((q.y - 0.7071).abs() < 0.001);
-        assert!((q.w - 0.7071).abs() < 0.001);
+        assert!((q.y - FRAC_1_SQRT_2).abs() < 0.001);
+        assert!((q.w - FRAC_1_SQRT_2).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_quaternion_rotate_vector() {
+        let q = Quaternion::from_axis_angle(Vector3::up(), FRAC_PI_2);
+        let v = q * Vector3::left();
+        assert!((v - Vector3::new(0.0, 0.0, -1.0)).length() < 0.0001);
+
+        let m = Matrix4::rotation(q) * Vector3::left();
+        assert!((v - m).length() < 0.0001);
+    }
+
+    #[test]
+    fn test_quaternion_from_rotation_matrix() {
+        let q = Quaternion::new(0.3, -1.2, 2.5);
+        let r = Quaternion::from_rotation_matrix(Matrix4::rotation(q));
+        let v = Vector3::new(1.0, 2.0, 3.0);
+        assert!((q * v - r * v).length() < 0.0001);
     }
 }
\ No newline at end of file
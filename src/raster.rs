@@ -0,0 +1,720 @@
+use vector2::Vector2;
+use vector2i::Vector2i;
+use rect2i::Rect2i;
+use vector3::Vector3;
+use vector4::Vector4;
+use viewport::Viewport;
+use std::ops::{Add, Mul};
+
+/// The winding order of a triangle as it appears on a screen with y pointing down.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Winding {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Which triangles are discarded before rasterization.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CullMode {
+    None,
+    Front,
+    Back,
+}
+
+/// Options controlling how a triangle is rasterized.
+#[derive(Debug, Copy, Clone)]
+pub struct RasterOptions {
+    /// The winding of triangles that face the viewer.
+    pub front_face: Winding,
+    pub cull: CullMode,
+    /// The number of fractional bits vertices are snapped to, at most 16.
+    pub subpixel_bits: u32,
+    /// Pixels outside this rectangle are never covered.
+    pub scissor: Option<Rect2i>,
+}
+
+impl Default for RasterOptions {
+    fn default() -> RasterOptions {
+        RasterOptions {
+            front_face: Winding::CounterClockwise,
+            cull: CullMode::None,
+            subpixel_bits: 8,
+            scissor: None,
+        }
+    }
+}
+
+/// The largest supported number of fractional bits.
+const MAX_SUBPIXEL_BITS: u32 = 16;
+
+/// The largest magnitude of a fixed point coordinate, small enough that edge functions never overflow an `i64`.
+const FIXED_RANGE: i64 = 1 << 28;
+
+/// Returns `v` snapped to fixed point with `subpixel_bits` fractional bits.
+/// Returns `None` if a coordinate is not finite or is too far from the origin for exact edge functions.
+/// Panics if `subpixel_bits` is greater than 16.
+pub(crate) fn snap_to_fixed(v: Vector2, subpixel_bits: u32) -> Option<(i64, i64)> {
+    assert!(subpixel_bits <= MAX_SUBPIXEL_BITS, "at most 16 subpixel bits are supported");
+    let scale = (1i64 << subpixel_bits) as f32;
+    let (x, y) = ((v.x * scale).round(), (v.y * scale).round());
+    // Written so that NaN fails the comparison too.
+    if !(x.abs() <= FIXED_RANGE as f32 && y.abs() <= FIXED_RANGE as f32) {
+        return None;
+    }
+
+    Some((x as i64, y as i64))
+}
+
+/// A pixel covered by a triangle.
+#[derive(Debug, Copy, Clone)]
+pub struct Fragment {
+    pub position: Vector2i,
+    /// The weights of the three vertices at the pixel center, in the order they were given.
+    pub barycentric: Vector3,
+}
+
+/// How much of a rectangle of pixels a triangle covers, judged at pixel centers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Coverage {
+    Outside,
+    Partial,
+    Full,
+}
+
+/// An edge function `a * x + b * y + c` over fixed point positions, positive inside the triangle.
+#[derive(Debug, Copy, Clone)]
+struct Edge {
+    a: i64,
+    b: i64,
+    c: i64,
+    bias: i64,
+}
+
+impl Edge {
+    fn new(v0: (i64, i64), v1: (i64, i64)) -> Edge {
+        let dx = v1.0 - v0.0;
+        let dy = v1.1 - v0.1;
+
+        // Samples exactly on an edge belong to the triangle only for top and left edges.
+        let top_left = (dy == 0 && dx > 0) || dy < 0;
+
+        Edge {
+            a: -dy,
+            b: dx,
+            c: dy * v0.0 - dx * v0.1,
+            bias: if top_left { 0 } else { -1 },
+        }
+    }
+
+    fn evaluate(&self, x: i64, y: i64) -> i64 {
+        self.a * x + self.b * y + self.c
+    }
+}
+
+/// A screen space triangle snapped to fixed point, ready for coverage tests.
+/// Fixed point positions are `Vector2i`s in units of `1 / 2^subpixel_bits` pixels.
+#[derive(Debug, Copy, Clone)]
+pub struct TriangleSetup {
+    vertices: [(i64, i64); 3],
+    edges: [Edge; 3],
+    area: i64,
+    swapped: bool,
+    winding: Winding,
+    subpixel_bits: u32,
+    scissor: Option<Rect2i>,
+}
+
+impl TriangleSetup {
+    /// Returns the setup of the screen space triangle `v0`, `v1`, `v2` snapped to `options.subpixel_bits` fractional bits.
+    /// Returns `None` if the triangle is degenerate after snapping, is culled, or has a vertex outside the fixed point range.
+    /// Panics if `options.subpixel_bits` is greater than 16.
+    pub fn new(v0: Vector2, v1: Vector2, v2: Vector2, options: &RasterOptions) -> Option<TriangleSetup> {
+        let bits = options.subpixel_bits;
+        TriangleSetup::from_points([snap_to_fixed(v0, bits)?, snap_to_fixed(v1, bits)?, snap_to_fixed(v2, bits)?], options)
+    }
+
+    /// Returns the setup of a triangle with fixed point vertices `v0`, `v1`, `v2` at `options.subpixel_bits` fractional bits.
+    /// Returns `None` if the triangle is degenerate, is culled, or has a coordinate beyond 2^28 in magnitude.
+    /// Panics if `options.subpixel_bits` is greater than 16.
+    pub fn from_fixed(v0: Vector2i, v1: Vector2i, v2: Vector2i, options: &RasterOptions) -> Option<TriangleSetup> {
+        let p = |v: Vector2i| (v.x as i64, v.y as i64);
+        TriangleSetup::from_points([p(v0), p(v1), p(v2)], options)
+    }
+
+    fn from_points(mut p: [(i64, i64); 3], options: &RasterOptions) -> Option<TriangleSetup> {
+        assert!(options.subpixel_bits <= MAX_SUBPIXEL_BITS, "at most 16 subpixel bits are supported");
+        if p.iter().any(|&(x, y)| x.abs() > FIXED_RANGE || y.abs() > FIXED_RANGE) {
+            return None;
+        }
+
+        let area = (p[1].0 - p[0].0) * (p[2].1 - p[0].1) - (p[1].1 - p[0].1) * (p[2].0 - p[0].0);
+        if area == 0 {
+            return None;
+        }
+
+        let winding = if area > 0 { Winding::Clockwise } else { Winding::CounterClockwise };
+        let culled = match options.cull {
+            CullMode::None => false,
+            CullMode::Front => winding == options.front_face,
+            CullMode::Back => winding != options.front_face,
+        };
+        if culled {
+            return None;
+        }
+
+        // Edge functions are positive inside once the triangle is clockwise.
+        let swapped = area < 0;
+        if swapped {
+            p.swap(1, 2);
+        }
+
+        Some(TriangleSetup {
+            vertices: p,
+            edges: [Edge::new(p[0], p[1]), Edge::new(p[1], p[2]), Edge::new(p[2], p[0])],
+            area: area.abs(),
+            swapped,
+            winding,
+            subpixel_bits: options.subpixel_bits,
+            scissor: options.scissor,
+        })
+    }
+
+    /// Returns the winding of the triangle on screen.
+    pub fn winding(&self) -> Winding {
+        self.winding
+    }
+
+    /// Returns the number of fractional bits in fixed point positions.
+    pub fn subpixel_bits(&self) -> u32 {
+        self.subpixel_bits
+    }
+
+    /// Returns the fixed point position of the center of `pixel`.
+    /// Positions beyond the range of `i32` saturate, which keeps them outside the triangle.
+    pub fn pixel_center(&self, pixel: Vector2i) -> Vector2i {
+        let scale = 1i64 << self.subpixel_bits;
+        let center = |a: i32| (a as i64 * scale + scale / 2).max(i32::MIN as i64).min(i32::MAX as i64) as i32;
+        Vector2i::new(center(pixel.x), center(pixel.y))
+    }
+
+    /// Returns the rectangle of pixels whose centers lie inside the triangle's bounding box and scissor rectangle.
+    /// Returns `None` if there are no such pixels.
+    pub fn bounds(&self) -> Option<Rect2i> {
+        let scale = 1i64 << self.subpixel_bits;
+        let half = scale / 2;
+        let first = |a: i64| (a - half + scale - 1).div_euclid(scale) as i32;
+        let last = |a: i64| (a - half).div_euclid(scale) as i32 + 1;
+        let p = &self.vertices;
+        let bounds = Rect2i::new(Vector2i::new(first(p[0].0.min(p[1].0).min(p[2].0)), first(p[0].1.min(p[1].1).min(p[2].1))),
+                                 Vector2i::new(last(p[0].0.max(p[1].0).max(p[2].0)), last(p[0].1.max(p[1].1).max(p[2].1))));
+
+        match self.scissor {
+            Some(scissor) => bounds.intersect(&scissor),
+            None if bounds.is_empty() => None,
+            None => Some(bounds),
+        }
+    }
+
+    /// Returns the rectangle of pixels overlapping the triangle's bounding box and scissor rectangle.
+    /// Returns `None` if there are no such pixels.
+    pub fn touched_bounds(&self) -> Option<Rect2i> {
+        let scale = 1i64 << self.subpixel_bits;
+        let p = &self.vertices;
+        let cell = |a: i64| a.div_euclid(scale) as i32;
+        let bounds = Rect2i::new(Vector2i::new(cell(p[0].0.min(p[1].0).min(p[2].0)), cell(p[0].1.min(p[1].1).min(p[2].1))),
+                                 Vector2i::new(cell(p[0].0.max(p[1].0).max(p[2].0)) + 1, cell(p[0].1.max(p[1].1).max(p[2].1)) + 1));
+
+        match self.scissor {
+            Some(scissor) => bounds.intersect(&scissor),
+            None => Some(bounds),
+        }
+    }
+
+    /// Returns true if the fixed point position `sample` is inside the triangle under the top-left fill rule.
+    pub fn covers(&self, sample: Vector2i) -> bool {
+        let (x, y) = (sample.x as i64, sample.y as i64);
+        self.edges.iter().all(|e| e.evaluate(x, y) + e.bias >= 0)
+    }
+
+    /// Returns true if the triangle covers the center of `pixel` and the pixel is inside the scissor rectangle.
+    pub fn covers_pixel(&self, pixel: Vector2i) -> bool {
+        self.scissor.map_or(true, |scissor| scissor.contains(pixel)) && self.covers(self.pixel_center(pixel))
+    }
+
+    /// Returns true if the triangle overlaps the inside of `pixel`, which is the overestimated conservative coverage.
+    /// Pixels that only touch the triangle along their border or are outside the scissor rectangle are not overlapped.
+    pub fn overlaps_pixel(&self, pixel: Vector2i) -> bool {
+        if !self.scissor.map_or(true, |scissor| scissor.contains(pixel)) {
+            return false;
+        }
+
+        let scale = 1i64 << self.subpixel_bits;
+        let (x0, y0) = (pixel.x as i64 * scale, pixel.y as i64 * scale);
+        let p = &self.vertices;
+
+        // Separating axes are the pixel's sides and the triangle's edge normals.
+        if x0 >= p[0].0.max(p[1].0).max(p[2].0) || x0 + scale <= p[0].0.min(p[1].0).min(p[2].0) ||
+           y0 >= p[0].1.max(p[1].1).max(p[2].1) || y0 + scale <= p[0].1.min(p[1].1).min(p[2].1) {
+            return false;
+        }
+
+        // Edge functions are doubled so the half-pixel offset to the pixel center stays in exact integers.
+        self.edges.iter().all(|e| 2 * e.evaluate(x0, y0) + (e.a + e.b) * scale + (e.a.abs() + e.b.abs()) * scale > 0)
+    }
+
+    /// Returns true if all of `pixel` is inside the triangle and the scissor rectangle,
+    /// which is the underestimated conservative coverage.
+    pub fn contains_pixel(&self, pixel: Vector2i) -> bool {
+        // Pixels outside the bounding box are never inside, and testing them could overflow the edge functions.
+        if !self.touched_bounds().map_or(false, |bounds| bounds.contains(pixel)) {
+            return false;
+        }
+
+        let scale = 1i64 << self.subpixel_bits;
+        let (x0, y0) = (pixel.x as i64 * scale, pixel.y as i64 * scale);
+        self.edges.iter().all(|e| 2 * e.evaluate(x0, y0) + (e.a + e.b) * scale - (e.a.abs() + e.b.abs()) * scale >= 0)
+    }
+
+    /// Returns the weights of the three vertices, in the order they were given, at the fixed point position `sample`.
+    pub fn barycentric(&self, sample: Vector2i) -> Vector3 {
+        let (x, y) = (sample.x as i64, sample.y as i64);
+        self.weights([self.edges[0].evaluate(x, y), self.edges[1].evaluate(x, y), self.edges[2].evaluate(x, y)])
+    }
+
+    fn weights(&self, w: [i64; 3]) -> Vector3 {
+        let area = self.area as f32;
+        let (b0, b1, b2) = (w[1] as f32 / area, w[2] as f32 / area, w[0] as f32 / area);
+        if self.swapped {
+            Vector3::new(b0, b2, b1)
+        } else {
+            Vector3::new(b0, b1, b2)
+        }
+    }
+
+    /// Returns how much of `rect` the triangle covers, testing each edge at the rectangle's extreme pixel centers.
+    /// Pixels outside the scissor rectangle count as uncovered.
+    pub fn classify(&self, rect: &Rect2i) -> Coverage {
+        // Pixels with centers outside the bounds are never covered, so only the rest of the rectangle is tested.
+        let inside = match self.bounds().and_then(|bounds| rect.intersect(&bounds)) {
+            Some(inside) => inside,
+            None => return Coverage::Outside,
+        };
+        let clipped = inside != *rect;
+
+        let lo = self.pixel_center(inside.min);
+        let hi = self.pixel_center(inside.max - Vector2i::new(1, 1));
+        let mut full = true;
+        for e in self.edges.iter() {
+            let (x_in, x_out) = if e.a >= 0 { (hi.x, lo.x) } else { (lo.x, hi.x) };
+            let (y_in, y_out) = if e.b >= 0 { (hi.y, lo.y) } else { (lo.y, hi.y) };
+            if e.evaluate(x_in as i64, y_in as i64) + e.bias < 0 {
+                return Coverage::Outside;
+            }
+            if e.evaluate(x_out as i64, y_out as i64) + e.bias < 0 {
+                full = false;
+            }
+        }
+
+        if full && !clipped {
+            Coverage::Full
+        } else {
+            Coverage::Partial
+        }
+    }
+
+    /// Returns an iterator over the pixels whose centers the triangle covers, in row-major order.
+    pub fn raster(&self) -> TriangleRaster {
+        let bounds = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return TriangleRaster::empty(),
+        };
+
+        let scale = 1i64 << self.subpixel_bits;
+        let origin = self.pixel_center(bounds.min);
+        let mut row = [0; 3];
+        for (value, e) in row.iter_mut().zip(self.edges.iter()) {
+            *value = e.evaluate(origin.x as i64, origin.y as i64);
+        }
+
+        TriangleRaster {
+            setup: Some(*self),
+            step_x: [self.edges[0].a * scale, self.edges[1].a * scale, self.edges[2].a * scale],
+            step_y: [self.edges[0].b * scale, self.edges[1].b * scale, self.edges[2].b * scale],
+            row,
+            value: row,
+            bounds,
+            x: bounds.min.x,
+            y: bounds.min.y,
+        }
+    }
+}
+
+/// An iterator over the pixels covered by a triangle, in row-major order.
+#[derive(Debug, Clone)]
+pub struct TriangleRaster {
+    setup: Option<TriangleSetup>,
+    step_x: [i64; 3],
+    step_y: [i64; 3],
+    row: [i64; 3],
+    value: [i64; 3],
+    bounds: Rect2i,
+    x: i32,
+    y: i32,
+}
+
+impl TriangleRaster {
+    fn empty() -> TriangleRaster {
+        TriangleRaster {
+            setup: None,
+            step_x: [0; 3],
+            step_y: [0; 3],
+            row: [0; 3],
+            value: [0; 3],
+            bounds: Rect2i::new(Vector2i::new(0, 0), Vector2i::new(0, 0)),
+            x: 0,
+            y: 0,
+        }
+    }
+}
+
+impl Iterator for TriangleRaster {
+    type Item = Fragment;
+
+    fn next(&mut self) -> Option<Fragment> {
+        let setup = self.setup?;
+
+        while self.y < self.bounds.max.y {
+            while self.x < self.bounds.max.x {
+                let position = Vector2i::new(self.x, self.y);
+                let w = self.value;
+
+                self.x += 1;
+                for (value, step) in self.value.iter_mut().zip(self.step_x.iter()) {
+                    *value += step;
+                }
+
+                let e = &setup.edges;
+                if ((w[0] + e[0].bias) | (w[1] + e[1].bias) | (w[2] + e[2].bias)) >= 0 {
+                    return Some(Fragment { position, barycentric: setup.weights(w) });
+                }
+            }
+
+            self.x = self.bounds.min.x;
+            self.y += 1;
+            for ((row, value), step) in self.row.iter_mut().zip(self.value.iter_mut()).zip(self.step_y.iter()) {
+                *row += step;
+                *value = *row;
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns the winding of the triangle `v0`, `v1`, `v2` in screen space.
+/// Returns `None` if the triangle is degenerate.
+pub fn triangle_winding(v0: Vector2, v1: Vector2, v2: Vector2) -> Option<Winding> {
+    let area = Vector2::cross(v1 - v0, v2 - v0);
+    if area > 0.0 {
+        Some(Winding::Clockwise)
+    } else if area < 0.0 {
+        Some(Winding::CounterClockwise)
+    } else {
+        None
+    }
+}
+
+/// Returns an iterator over the pixels covered by the screen space triangle `v0`, `v1`, `v2`.
+/// Pixel centers lie at half-integer coordinates, and pixels on an edge shared by two triangles
+/// are covered by exactly one of them. Degenerate and culled triangles, and triangles with a vertex outside the
+/// fixed point range, cover no pixels.
+/// Panics if `options.subpixel_bits` is greater than 16.
+pub fn rasterize_triangle(v0: Vector2, v1: Vector2, v2: Vector2, options: &RasterOptions) -> TriangleRaster {
+    match TriangleSetup::new(v0, v1, v2, options) {
+        Some(setup) => setup.raster(),
+        None => TriangleRaster::empty(),
+    }
+}
+
+/// A pixel covered by a clip space triangle, with perspective-correct values at its center.
+#[derive(Debug, Copy, Clone)]
+pub struct PerspectiveFragment<T> {
+    pub position: Vector2i,
+    /// The depth mapped to the viewport depth range.
+    pub depth: f32,
+    /// The perspective-correct weights of the three vertices.
+    pub barycentric: Vector3,
+    pub attributes: T,
+}
+
+/// An iterator over the pixels covered by a clip space triangle, in row-major order.
+#[derive(Debug, Clone)]
+pub struct PerspectiveRaster<T> {
+    raster: TriangleRaster,
+    inv_w: Vector3,
+    depth: Vector3,
+    attributes: [T; 3],
+}
+
+impl<T> Iterator for PerspectiveRaster<T> where T: Copy + Add<Output = T> + Mul<f32, Output = T> {
+    type Item = PerspectiveFragment<T>;
+
+    fn next(&mut self) -> Option<PerspectiveFragment<T>> {
+        self.raster.next().map(|fragment| {
+            let barycentric = perspective_barycentric(fragment.barycentric, self.inv_w);
+            PerspectiveFragment {
+                position: fragment.position,
+                depth: Vector3::dot(fragment.barycentric, self.depth),
+                barycentric,
+                attributes: interpolate(barycentric, self.attributes),
+            }
+        })
+    }
+}
+
+/// Returns perspective-correct weights from screen space weights and the reciprocal clip `w` of each vertex.
+pub fn perspective_barycentric(screen: Vector3, inv_w: Vector3) -> Vector3 {
+    let weighted = screen * inv_w;
+    weighted / (weighted.x + weighted.y + weighted.z)
+}
+
+/// Returns the sum of `attributes` weighted by `barycentric`.
+pub fn interpolate<T>(barycentric: Vector3, attributes: [T; 3]) -> T where T: Copy + Add<Output = T> + Mul<f32, Output = T> {
+    attributes[0] * barycentric.x + attributes[1] * barycentric.y + attributes[2] * barycentric.z
+}
+
+/// Returns an iterator over the pixels of `viewport` covered by the clip space triangle `clip`,
+/// with `attributes` and depth interpolated with perspective correction.
+/// Triangles with a vertex at or behind the eye (`w <= 0`), or so near it that the vertex leaves the fixed point range,
+/// cover no pixels and must be clipped first.
+pub fn rasterize_perspective<T>(clip: [Vector4; 3], attributes: [T; 3], viewport: &Viewport, options: &RasterOptions) -> PerspectiveRaster<T> {
+    if clip.iter().any(|v| v.w <= 0.0) {
+        return PerspectiveRaster {
+            raster: TriangleRaster::empty(),
+            inv_w: Vector3::zero(),
+            depth: Vector3::zero(),
+            attributes,
+        };
+    }
+
+    let inv_w = Vector3::new(1.0 / clip[0].w, 1.0 / clip[1].w, 1.0 / clip[2].w);
+    let screen = [viewport.ndc_to_screen(clip[0].xyz() * inv_w.x),
+                  viewport.ndc_to_screen(clip[1].xyz() * inv_w.y),
+                  viewport.ndc_to_screen(clip[2].xyz() * inv_w.z)];
+    let xy = |i: usize| Vector2::new(screen[i].x, screen[i].y);
+
+    PerspectiveRaster {
+        raster: rasterize_triangle(xy(0), xy(1), xy(2), options),
+        inv_w,
+        depth: Vector3::new(screen[0].z, screen[1].z, screen[2].z),
+        attributes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raster::{rasterize_triangle, rasterize_perspective, perspective_barycentric, triangle_winding, Coverage, CullMode, RasterOptions, TriangleSetup, Winding};
+    use std::collections::HashSet;
+    use matrix4::Matrix4;
+    use rect2i::Rect2i;
+    use vector2::Vector2;
+    use vector2i::Vector2i;
+    use vector3::Vector3;
+    use vector4::Vector4;
+    use viewport::Viewport;
+
+    fn pixels(v0: Vector2, v1: Vector2, v2: Vector2, options: &RasterOptions) -> Vec<(i32, i32)> {
+        rasterize_triangle(v0, v1, v2, options).map(|f| (f.position.x, f.position.y)).collect()
+    }
+
+    #[test]
+    fn test_raster_shared_edge() {
+        let options = RasterOptions::default();
+        let a = Vector2::new(0.3, 0.7);
+        let b = Vector2::new(9.6, 1.2);
+        let c = Vector2::new(8.1, 9.9);
+        let d = Vector2::new(0.9, 7.4);
+
+        let mut seen = HashSet::new();
+        for p in pixels(a, b, c, &options).into_iter().chain(pixels(a, c, d, &options)) {
+            assert!(seen.insert(p), "pixel {:?} covered twice", p);
+        }
+
+        // Every pixel center strictly inside the quad is covered.
+        for y in 0..10 {
+            for x in 0..10 {
+                let p = Vector2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let quad = [a, b, c, d];
+                let inside = (0..4).all(|i| Vector2::cross(quad[(i + 1) % 4] - quad[i], p - quad[i]) > 0.01);
+                if inside {
+                    assert!(seen.contains(&(x, y)), "pixel {:?} missed", (x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_raster_top_left_rule() {
+        // A 4x4 pixel square split along a diagonal with vertices on pixel corners.
+        let options = RasterOptions::default();
+        let a = Vector2::new(0.0, 0.0);
+        let b = Vector2::new(4.0, 0.0);
+        let c = Vector2::new(4.0, 4.0);
+        let d = Vector2::new(0.0, 4.0);
+        let mut covered = pixels(a, b, c, &options);
+        covered.extend(pixels(a, c, d, &options));
+        covered.sort();
+        let expected: Vec<(i32, i32)> = (0..4).flat_map(|x| (0..4).map(move |y| (x, y))).collect();
+        assert_eq!(covered, expected);
+
+        // Pixel centers on a top edge are covered and those on a bottom edge are not.
+        let top = pixels(Vector2::new(0.0, 0.5), Vector2::new(4.0, 0.5), Vector2::new(0.0, 2.5), &options);
+        assert!(top.contains(&(0, 0)));
+        let bottom = pixels(Vector2::new(0.0, 0.5), Vector2::new(4.0, 2.5), Vector2::new(0.0, 2.5), &options);
+        assert!(!bottom.contains(&(1, 2)));
+        assert!(bottom.contains(&(1, 1)));
+    }
+
+    #[test]
+    fn test_raster_winding_and_culling() {
+        let a = Vector2::new(1.0, 1.0);
+        let b = Vector2::new(7.0, 2.0);
+        let c = Vector2::new(3.0, 6.0);
+        assert_eq!(triangle_winding(a, b, c), Some(Winding::Clockwise));
+        assert_eq!(triangle_winding(a, c, b), Some(Winding::CounterClockwise));
+
+        let mut options = RasterOptions::default();
+        let mut cw = pixels(a, b, c, &options);
+        let mut ccw = pixels(a, c, b, &options);
+        cw.sort();
+        ccw.sort();
+        assert!(!cw.is_empty());
+        assert_eq!(cw, ccw);
+
+        options.cull = CullMode::Back;
+        assert!(pixels(a, b, c, &options).is_empty());
+        assert_eq!(pixels(a, c, b, &options).len(), cw.len());
+
+        options.front_face = Winding::Clockwise;
+        assert!(pixels(a, c, b, &options).is_empty());
+        assert_eq!(pixels(a, b, c, &options).len(), cw.len());
+
+        options.cull = CullMode::Front;
+        assert!(pixels(a, b, c, &options).is_empty());
+    }
+
+    #[test]
+    fn test_raster_barycentric() {
+        let options = RasterOptions::default();
+        let v = [Vector2i::new(1, 1), Vector2i::new(3, 12), Vector2i::new(11, 4)];
+        let f = |p: Vector2i| Vector2::new(p.x as f32, p.y as f32);
+        for fragment in rasterize_triangle(f(v[0]), f(v[1]), f(v[2]), &options) {
+            let b = fragment.barycentric;
+            assert!((b.x + b.y + b.z - 1.0).abs() < 1e-5);
+            assert!(b.x >= 0.0 && b.y >= 0.0 && b.z >= 0.0);
+            let center = Vector2::new(fragment.position.x as f32 + 0.5, fragment.position.y as f32 + 0.5);
+            let p = f(v[0]) * b.x + f(v[1]) * b.y + f(v[2]) * b.z;
+            assert!((p.x - center.x).abs() < 1e-4 && (p.y - center.y).abs() < 1e-4);
+        }
+
+        // Integer barycentric coordinates work for both windings.
+        let p = Vector2i::new(4, 5);
+        let cw = Vector2i::barycentric(p, v[0], v[1], v[2]).unwrap();
+        let ccw = Vector2i::barycentric(p, v[0], v[2], v[1]).unwrap();
+        assert!((cw.x - ccw.x).abs() < 1e-5);
+        assert!((cw.y - ccw.z).abs() < 1e-5);
+        assert!((cw.z - ccw.y).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_raster_subpixel_and_degenerate() {
+        let mut options = RasterOptions::default();
+        // A thin sliver that only covers a pixel center with sub-pixel precision.
+        let a = Vector2::new(0.4, 0.0);
+        let b = Vector2::new(0.6, 0.0);
+        let c = Vector2::new(0.5, 2.0);
+        assert_eq!(pixels(a, b, c, &options), vec![(0, 0), (0, 1)]);
+
+        // With a single fractional bit the vertices snap onto a vertical line.
+        options.subpixel_bits = 1;
+        assert!(pixels(a, b, c, &options).is_empty());
+
+        options.subpixel_bits = 8;
+        let d = Vector2::new(2.0, 2.0);
+        assert!(pixels(a, a, d, &options).is_empty());
+        assert!(pixels(Vector2::zero(), Vector2::new(1.0, 1.0), d, &options).is_empty());
+    }
+
+    #[test]
+    fn test_raster_scissor() {
+        let mut options = RasterOptions::default();
+        let a = Vector2::new(0.0, 0.0);
+        let b = Vector2::new(16.0, 0.0);
+        let c = Vector2::new(0.0, 16.0);
+        let all = pixels(a, b, c, &options);
+
+        let scissor = Rect2i::from_size(Vector2i::new(2, 3), 5, 4);
+        options.scissor = Some(scissor);
+        let clipped = pixels(a, b, c, &options);
+        let expected: Vec<(i32, i32)> = all.into_iter().filter(|&(x, y)| scissor.contains(Vector2i::new(x, y))).collect();
+        assert_eq!(clipped.len(), 20);
+        assert_eq!(clipped, expected);
+    }
+
+    #[test]
+    fn test_raster_perspective_interpolation() {
+        let view_proj = Matrix4::perpective(90.0, 1.0, 0.1, 100.0);
+        let world = [Vector3::new(-4.0, -1.0, -2.0), Vector3::new(4.0, -1.0, -2.0), Vector3::new(0.0, -1.0, -40.0)];
+        let clip = [view_proj * world[0].to_vector4(1.0), view_proj * world[1].to_vector4(1.0), view_proj * world[2].to_vector4(1.0)];
+        let viewport = Viewport::new(0.0, 0.0, 64.0, 64.0);
+
+        let mut count = 0;
+        for fragment in rasterize_perspective(clip, world, &viewport, &RasterOptions::default()) {
+            // The interpolated world position projects back onto the pixel center at the interpolated depth.
+            let screen = viewport.project(fragment.attributes, view_proj).unwrap();
+            assert!((screen.x - (fragment.position.x as f32 + 0.5)).abs() < 1e-2);
+            assert!((screen.y - (fragment.position.y as f32 + 0.5)).abs() < 1e-2);
+            assert!((screen.z - fragment.depth).abs() < 1e-4);
+            assert!((fragment.attributes.y + 1.0).abs() < 1e-4);
+            count += 1;
+        }
+        assert!(count > 100);
+
+        // Equal `w` leaves screen space weights unchanged.
+        let b = perspective_barycentric(Vector3::new(0.2, 0.3, 0.5), Vector3::new(0.5, 0.5, 0.5));
+        assert!((b.x - 0.2).abs() < 1e-6 && (b.y - 0.3).abs() < 1e-6 && (b.z - 0.5).abs() < 1e-6);
+
+        // Vertices behind the eye are rejected.
+        let behind = [clip[0], clip[1], view_proj * Vector3::new(0.0, -1.0, 5.0).to_vector4(1.0)];
+        assert_eq!(rasterize_perspective(behind, world, &viewport, &RasterOptions::default()).count(), 0);
+
+        // So are vertices so near the eye that they leave the fixed point range.
+        let near = [clip[0], clip[1], Vector4::new(1.0, 1.0, 0.0, 1e-7)];
+        assert_eq!(rasterize_perspective(near, world, &viewport, &RasterOptions::default()).count(), 0);
+    }
+
+    #[test]
+    fn test_raster_fixed_point_range() {
+        let options = RasterOptions::default();
+        let far = Vector2::new(1e8, 10.0);
+        assert!(pixels(Vector2::zero(), far, Vector2::new(0.0, 10.0), &options).is_empty());
+        assert!(pixels(Vector2::zero(), Vector2::new(f32::NAN, 1.0), Vector2::new(0.0, 10.0), &options).is_empty());
+
+        let max = Vector2i::new(i32::MAX, i32::MAX);
+        assert!(TriangleSetup::from_fixed(Vector2i::new(0, 0), Vector2i::new(i32::MAX, 0), max, &options).is_none());
+
+        // Pixel centers far outside the range saturate instead of wrapping around onto the triangle.
+        let setup = TriangleSetup::new(Vector2::zero(), Vector2::new(16.0, 0.0), Vector2::new(0.0, 16.0), &options).unwrap();
+        assert!(setup.covers_pixel(Vector2i::new(1, 1)));
+        assert!(!setup.covers_pixel(Vector2i::new(1 << 24, 1)));
+        assert!(!setup.contains_pixel(Vector2i::new(i32::MAX, 1)));
+        assert_eq!(setup.classify(&Rect2i::new(Vector2i::new(1 << 24, 0), Vector2i::new(i32::MAX, 4))), Coverage::Outside);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_raster_too_many_subpixel_bits() {
+        let options = RasterOptions { subpixel_bits: 32, ..RasterOptions::default() };
+        rasterize_triangle(Vector2::zero(), Vector2::new(4.0, 0.0), Vector2::new(0.0, 4.0), &options);
+    }
+}
@@ -0,0 +1,211 @@
+use vector2i::Vector2i;
+use std::cmp::{min, max};
+
+/// An axis aligned rectangle of pixels from `min` inclusive to `max` exclusive.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rect2i {
+    pub min: Vector2i,
+    pub max: Vector2i,
+}
+
+impl Rect2i {
+    /// Returns a new `Rect2i` covering `min` up to but not including `max`.
+    pub fn new(min: Vector2i, max: Vector2i) -> Rect2i {
+        Rect2i { min, max }
+    }
+
+    /// Returns a new `Rect2i` of `width` by `height` pixels starting at `origin`.
+    pub fn from_size(origin: Vector2i, width: i32, height: i32) -> Rect2i {
+        Rect2i::new(origin, Vector2i::new(origin.x + width, origin.y + height))
+    }
+
+    /// Returns the smallest `Rect2i` containing all of `points`.
+    /// Returns `None` if `points` is empty.
+    pub fn from_points(points: &[Vector2i]) -> Option<Rect2i> {
+        let first = *points.first()?;
+        let mut rect = Rect2i::from_size(first, 1, 1);
+        for p in &points[1..] {
+            rect.min = Vector2i::new(min(rect.min.x, p.x), min(rect.min.y, p.y));
+            rect.max = Vector2i::new(max(rect.max.x, p.x + 1), max(rect.max.y, p.y + 1));
+        }
+        Some(rect)
+    }
+
+    /// Returns the width of the rectangle, or 0 if it is empty.
+    pub fn width(&self) -> i32 {
+        max(self.max.x - self.min.x, 0)
+    }
+
+    /// Returns the height of the rectangle, or 0 if it is empty.
+    pub fn height(&self) -> i32 {
+        max(self.max.y - self.min.y, 0)
+    }
+
+    /// Returns the number of pixels in the rectangle.
+    pub fn area(&self) -> i64 {
+        self.width() as i64 * self.height() as i64
+    }
+
+    /// Returns true if the rectangle contains no pixels.
+    pub fn is_empty(&self) -> bool {
+        self.max.x <= self.min.x || self.max.y <= self.min.y
+    }
+
+    /// Returns true if the pixel `p` is inside the rectangle.
+    pub fn contains(&self, p: Vector2i) -> bool {
+        p.x >= self.min.x && p.x < self.max.x && p.y >= self.min.y && p.y < self.max.y
+    }
+
+    /// Returns the pixels inside both `self` and `other`.
+    /// Returns `None` if the rectangles don't overlap.
+    pub fn intersect(&self, other: &Rect2i) -> Option<Rect2i> {
+        let rect = Rect2i::new(Vector2i::new(max(self.min.x, other.min.x), max(self.min.y, other.min.y)),
+                               Vector2i::new(min(self.max.x, other.max.x), min(self.max.y, other.max.y)));
+        if rect.is_empty() {
+            None
+        } else {
+            Some(rect)
+        }
+    }
+
+    /// Returns the smallest rectangle containing both `self` and `other`.
+    pub fn union(&self, other: &Rect2i) -> Rect2i {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+
+        Rect2i::new(Vector2i::new(min(self.min.x, other.min.x), min(self.min.y, other.min.y)),
+                    Vector2i::new(max(self.max.x, other.max.x), max(self.max.y, other.max.y)))
+    }
+
+    /// Returns the part of the rectangle inside a `width` by `height` framebuffer.
+    /// Returns `None` if the rectangle is entirely outside it.
+    pub fn clamp_to_framebuffer(&self, width: i32, height: i32) -> Option<Rect2i> {
+        self.intersect(&Rect2i::from_size(Vector2i::new(0, 0), width, height))
+    }
+
+    /// Returns an iterator over the pixels in the rectangle, in row-major order.
+    pub fn pixels(&self) -> Rect2iPixels {
+        Rect2iPixels {
+            rect: *self,
+            next: self.min,
+        }
+    }
+
+    /// Returns an iterator over `tile_width` by `tile_height` tiles covering the rectangle, in row-major order.
+    /// Tiles along the right and bottom edges are cut to fit.
+    /// Panics if either tile size isn't positive.
+    pub fn tiles(&self, tile_width: i32, tile_height: i32) -> Rect2iTiles {
+        assert!(tile_width > 0 && tile_height > 0, "tile size must be positive");
+        Rect2iTiles {
+            rect: *self,
+            tile_width,
+            tile_height,
+            next: self.min,
+        }
+    }
+}
+
+/// An iterator over the pixels of a `Rect2i`.
+#[derive(Debug, Clone)]
+pub struct Rect2iPixels {
+    rect: Rect2i,
+    next: Vector2i,
+}
+
+impl Iterator for Rect2iPixels {
+    type Item = Vector2i;
+
+    fn next(&mut self) -> Option<Vector2i> {
+        if self.rect.is_empty() || self.next.y >= self.rect.max.y {
+            return None;
+        }
+
+        let p = self.next;
+        self.next.x += 1;
+        if self.next.x >= self.rect.max.x {
+            self.next = Vector2i::new(self.rect.min.x, self.next.y + 1);
+        }
+        Some(p)
+    }
+}
+
+/// An iterator over the tiles of a `Rect2i`.
+#[derive(Debug, Clone)]
+pub struct Rect2iTiles {
+    rect: Rect2i,
+    tile_width: i32,
+    tile_height: i32,
+    next: Vector2i,
+}
+
+impl Iterator for Rect2iTiles {
+    type Item = Rect2i;
+
+    fn next(&mut self) -> Option<Rect2i> {
+        if self.rect.is_empty() || self.next.y >= self.rect.max.y {
+            return None;
+        }
+
+        let tile = Rect2i::new(self.next,
+                               Vector2i::new(min(self.next.x + self.tile_width, self.rect.max.x),
+                                             min(self.next.y + self.tile_height, self.rect.max.y)));
+        self.next.x = tile.max.x;
+        if self.next.x >= self.rect.max.x {
+            self.next = Vector2i::new(self.rect.min.x, tile.max.y);
+        }
+        Some(tile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rect2i::Rect2i;
+    use vector2i::Vector2i;
+
+    #[test]
+    fn test_rect2i_from_points() {
+        let rect = Rect2i::from_points(&[Vector2i::new(3, 7), Vector2i::new(-2, 4), Vector2i::new(5, 5)]).unwrap();
+        assert_eq!(rect, Rect2i::new(Vector2i::new(-2, 4), Vector2i::new(6, 8)));
+        assert_eq!((rect.width(), rect.height(), rect.area()), (8, 4, 32));
+        assert!(rect.contains(Vector2i::new(5, 7)));
+        assert!(!rect.contains(Vector2i::new(6, 7)));
+        assert!(Rect2i::from_points(&[]).is_none());
+    }
+
+    #[test]
+    fn test_rect2i_intersect_union() {
+        let a = Rect2i::from_size(Vector2i::new(0, 0), 10, 10);
+        let b = Rect2i::from_size(Vector2i::new(5, -5), 10, 10);
+        assert_eq!(a.intersect(&b), Some(Rect2i::new(Vector2i::new(5, 0), Vector2i::new(10, 5))));
+        assert_eq!(a.union(&b), Rect2i::new(Vector2i::new(0, -5), Vector2i::new(15, 10)));
+
+        let c = Rect2i::from_size(Vector2i::new(10, 0), 4, 4);
+        assert!(a.intersect(&c).is_none());
+
+        let empty = Rect2i::from_size(Vector2i::new(-50, -50), 0, 3);
+        assert!(empty.is_empty());
+        assert_eq!(a.union(&empty), a);
+
+        assert_eq!(b.clamp_to_framebuffer(8, 8), Some(Rect2i::new(Vector2i::new(5, 0), Vector2i::new(8, 5))));
+        assert!(b.clamp_to_framebuffer(4, 4).is_none());
+    }
+
+    #[test]
+    fn test_rect2i_pixels_and_tiles() {
+        let rect = Rect2i::from_size(Vector2i::new(1, 2), 3, 2);
+        let pixels: Vec<(i32, i32)> = rect.pixels().map(|p| (p.x, p.y)).collect();
+        assert_eq!(pixels, vec![(1, 2), (2, 2), (3, 2), (1, 3), (2, 3), (3, 3)]);
+        assert_eq!(Rect2i::from_size(Vector2i::new(0, 0), 0, 5).pixels().count(), 0);
+
+        let rect = Rect2i::from_size(Vector2i::new(0, 0), 70, 40);
+        let tiles: Vec<Rect2i> = rect.tiles(32, 32).collect();
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(tiles[2], Rect2i::new(Vector2i::new(64, 0), Vector2i::new(70, 32)));
+        assert_eq!(tiles[5], Rect2i::new(Vector2i::new(64, 32), Vector2i::new(70, 40)));
+        assert_eq!(tiles.iter().map(|t| t.area()).sum::<i64>(), rect.area());
+    }
+}
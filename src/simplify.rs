@@ -0,0 +1,446 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::ops::{Add, Mul};
+use vector3::Vector3;
+use vector4::Vector4;
+use matrix4::Matrix4;
+use plane::Plane;
+use half_edge::{HalfEdgeMesh, HalfEdgeError};
+
+/// The weight of the planes that hold boundary edges in place, relative to the faces' own planes.
+const BOUNDARY_WEIGHT: f32 = 100.0;
+
+/// A symmetric 4x4 matrix measuring the sum of squared distances from a point to a set of planes.
+#[derive(Debug, Copy, Clone)]
+pub struct Quadric {
+    /// The upper triangle of the matrix in row order.
+    data: [f32; 10],
+}
+
+impl Quadric {
+    /// Returns a `Quadric` with no planes.
+    pub fn zero() -> Quadric {
+        Quadric { data: [0.0; 10] }
+    }
+
+    /// Returns the `Quadric` of the squared distance to `plane`, which should have a unit normal.
+    pub fn from_plane(plane: &Plane) -> Quadric {
+        let (a, b, c, d) = (plane.normal.x, plane.normal.y, plane.normal.z, plane.d);
+        Quadric { data: [a * a, a * b, a * c, a * d,
+                                b * b, b * c, b * d,
+                                       c * c, c * d,
+                                              d * d] }
+    }
+
+    /// Returns the quadric as a full `Matrix4`.
+    pub fn to_matrix4(&self) -> Matrix4 {
+        let q = &self.data;
+        Matrix4 { data: [[q[0], q[1], q[2], q[3]],
+                         [q[1], q[4], q[5], q[6]],
+                         [q[2], q[5], q[7], q[8]],
+                         [q[3], q[6], q[8], q[9]]] }
+    }
+
+    /// Returns the sum of squared distances from `point` to the quadric's planes.
+    pub fn error(&self, point: Vector3) -> f32 {
+        let q = &self.data;
+        let (x, y, z) = (point.x, point.y, point.z);
+        q[0] * x * x + 2.0 * q[1] * x * y + 2.0 * q[2] * x * z + 2.0 * q[3] * x
+            + q[4] * y * y + 2.0 * q[5] * y * z + 2.0 * q[6] * y
+            + q[7] * z * z + 2.0 * q[8] * z
+            + q[9]
+    }
+
+    /// Returns the point with the smallest error.
+    /// Returns `None` if the planes do not pin down a single point, such as when they are all parallel.
+    pub fn optimal_point(&self) -> Option<Vector3> {
+        let mut m = self.to_matrix4();
+        m.data[3] = [0.0, 0.0, 0.0, 1.0];
+
+        let scale = self.data[0] + self.data[4] + self.data[7];
+        if m.determinant().abs() <= 1e-5 * scale * scale * scale {
+            return None;
+        }
+
+        m.inverse().map(|inverse| (inverse * Vector4::new(0.0, 0.0, 0.0, 1.0)).xyz())
+    }
+}
+
+impl Add for Quadric {
+    type Output = Quadric;
+
+    fn add(self, other: Quadric) -> Quadric {
+        let mut data = self.data;
+        for (d, o) in data.iter_mut().zip(other.data.iter()) {
+            *d += *o;
+        }
+        Quadric { data }
+    }
+}
+
+impl Mul<f32> for Quadric {
+    type Output = Quadric;
+
+    fn mul(self, other: f32) -> Quadric {
+        let mut data = self.data;
+        for d in data.iter_mut() {
+            *d *= other;
+        }
+        Quadric { data }
+    }
+}
+
+/// The limits and constraints of `simplify`.
+#[derive(Debug, Copy, Clone)]
+pub struct SimplifyOptions {
+    /// Stop once the mesh has this many triangles or fewer.
+    pub target_triangles: usize,
+    /// Stop once every remaining collapse would add more than this error.
+    pub max_error: f32,
+    /// Keep every boundary vertex where it is.
+    pub preserve_boundary: bool,
+    /// Keep every vertex that shares its position with another vertex, as texture seams do.
+    /// Otherwise the two sides of a seam are simplified separately and may pull apart.
+    pub preserve_uv_seams: bool,
+}
+
+impl Default for SimplifyOptions {
+    fn default() -> SimplifyOptions {
+        SimplifyOptions { target_triangles: 0, max_error: f32::INFINITY, preserve_boundary: false, preserve_uv_seams: true }
+    }
+}
+
+/// A mesh produced by `simplify`.
+#[derive(Debug, Clone)]
+pub struct SimplifiedMesh {
+    pub positions: Vec<Vector3>,
+    pub indices: Vec<[usize; 3]>,
+    /// The index in the original mesh of each remaining vertex, for carrying over other vertex attributes.
+    pub source_vertices: Vec<usize>,
+    /// The largest error of any collapse that was made.
+    pub error: f32,
+}
+
+/// A possible edge collapse waiting in the queue.
+struct Candidate {
+    cost: f32,
+    half_edge: usize,
+    position: Vector3,
+    /// The ends of the edge and their versions when the candidate was made, so outdated candidates can be skipped.
+    vertices: (usize, usize),
+    versions: (usize, usize),
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Candidate) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Candidate) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    // Reversed so the heap pops the cheapest collapse first.
+    fn cmp(&self, other: &Candidate) -> Ordering {
+        other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// The state of a simplification in progress.
+struct Simplifier {
+    mesh: HalfEdgeMesh,
+    quadrics: Vec<Quadric>,
+    locked: Vec<bool>,
+    versions: Vec<usize>,
+}
+
+impl Simplifier {
+    /// Returns the best collapse of the edge of `h`, or `None` if its ends cannot move.
+    fn candidate(&self, h: usize) -> Option<Candidate> {
+        let t = self.mesh.half_edge(h).twin;
+        let (a, b) = (self.mesh.half_edge(h).origin, self.mesh.half_edge(t).origin);
+        let q = self.quadrics[a] + self.quadrics[b];
+
+        // Collapses merge the origin into the target, so a locked vertex must be the target and stay put.
+        let (half_edge, position) = match (self.locked[a], self.locked[b]) {
+            (true, true) => return None,
+            (true, false) => (t, self.mesh.position(a)),
+            (false, true) => (h, self.mesh.position(b)),
+            (false, false) => {
+                let (pa, pb) = (self.mesh.position(a), self.mesh.position(b));
+                let position = q.optimal_point().unwrap_or_else(|| {
+                    let mid = (pa + pb) * 0.5;
+                    [pa, pb, mid].iter().cloned()
+                        .fold((f32::INFINITY, mid), |best, p| if q.error(p) < best.0 { (q.error(p), p) } else { best })
+                        .1
+                });
+                (h, position)
+            },
+        };
+
+        let (from, to) = (self.mesh.half_edge(half_edge).origin, self.mesh.target(half_edge));
+        Some(Candidate {
+            cost: q.error(position).max(0.0),
+            half_edge,
+            position,
+            vertices: (from, to),
+            versions: (self.versions[from], self.versions[to]),
+        })
+    }
+
+    /// Returns true if moving both ends of the edge of `h` to `position` would flip a surrounding triangle.
+    fn flips_faces(&self, h: usize, position: Vector3) -> bool {
+        let t = self.mesh.half_edge(h).twin;
+        let (a, b) = (self.mesh.half_edge(h).origin, self.mesh.half_edge(t).origin);
+        let removed = [self.mesh.half_edge(h).face, self.mesh.half_edge(t).face];
+
+        for &v in [a, b].iter() {
+            for f in self.mesh.vertex_faces(v) {
+                if removed.contains(&Some(f)) {
+                    continue;
+                }
+
+                let vertices = self.mesh.face_vertices(f);
+                let corners: Vec<Vector3> = vertices.iter().map(|&u| self.mesh.position(u)).collect();
+                let moved: Vec<Vector3> = vertices.iter()
+                    .map(|&u| if u == a || u == b { position } else { self.mesh.position(u) })
+                    .collect();
+
+                let before = Vector3::cross(corners[1] - corners[0], corners[2] - corners[0]);
+                let after = Vector3::cross(moved[1] - moved[0], moved[2] - moved[0]);
+                if Vector3::dot(before, after) <= 0.0 {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+}
+
+/// Returns the triangles `indices` into `positions` simplified by Garland–Heckbert quadric error edge collapses.
+/// Collapses are made cheapest first until the target triangle count or the error limit in `options` is reached.
+/// Collapses that would flip a triangle or break the manifold are skipped.
+/// Returns an error if the triangles do not form a manifold.
+pub fn simplify(positions: &[Vector3], indices: &[[usize; 3]], options: &SimplifyOptions) -> Result<SimplifiedMesh, HalfEdgeError> {
+    let mesh = HalfEdgeMesh::new(positions.to_vec(), indices)?;
+
+    let mut quadrics = vec![Quadric::zero(); positions.len()];
+    for f in mesh.faces() {
+        let [v0, v1, v2] = mesh.face_vertices(f);
+        let n = Vector3::cross(positions[v1] - positions[v0], positions[v2] - positions[v0]);
+        let area = n.length() * 0.5;
+        if area <= 0.0 {
+            continue;
+        }
+
+        let q = Quadric::from_plane(&Plane::from_point_normal(positions[v0], n)) * area;
+        for &v in [v0, v1, v2].iter() {
+            quadrics[v] = quadrics[v] + q;
+        }
+    }
+
+    // Planes through boundary edges, perpendicular to their face, keep the outline from shrinking.
+    for h in mesh.edges() {
+        if !mesh.is_boundary_edge(h) {
+            continue;
+        }
+
+        let inner = if mesh.half_edge(h).face.is_some() { h } else { mesh.half_edge(h).twin };
+        let f = mesh.half_edge(inner).face.unwrap();
+        let [v0, v1, v2] = mesh.face_vertices(f);
+        let n = Vector3::cross(positions[v1] - positions[v0], positions[v2] - positions[v0]);
+
+        let (a, b) = (mesh.half_edge(inner).origin, mesh.target(inner));
+        let edge = positions[b] - positions[a];
+        let side = Vector3::cross(edge, n);
+        if side.length_squared() <= 0.0 {
+            continue;
+        }
+
+        let q = Quadric::from_plane(&Plane::from_point_normal(positions[a], side)) * (BOUNDARY_WEIGHT * edge.length_squared());
+        quadrics[a] = quadrics[a] + q;
+        quadrics[b] = quadrics[b] + q;
+    }
+
+    let mut locked = vec![false; positions.len()];
+    if options.preserve_boundary {
+        for (v, l) in locked.iter_mut().enumerate() {
+            *l = mesh.is_boundary_vertex(v);
+        }
+    }
+    if options.preserve_uv_seams {
+        let mut seen: HashMap<[u32; 3], usize> = HashMap::new();
+        for (v, p) in positions.iter().enumerate() {
+            let key = [(p.x + 0.0).to_bits(), (p.y + 0.0).to_bits(), (p.z + 0.0).to_bits()];
+            if let Some(&other) = seen.get(&key) {
+                locked[v] = true;
+                locked[other] = true;
+            } else {
+                seen.insert(key, v);
+            }
+        }
+    }
+
+    let mut simplifier = Simplifier { mesh, quadrics, locked, versions: vec![0; positions.len()] };
+    let mut heap: BinaryHeap<Candidate> = simplifier.mesh.edges().into_iter()
+        .filter_map(|h| simplifier.candidate(h))
+        .collect();
+
+    let mut triangles = simplifier.mesh.faces().len();
+    let mut error = 0.0f32;
+    let mut collapses = 0;
+    while triangles > options.target_triangles {
+        let candidate = match heap.pop() {
+            Some(c) => c,
+            None => break,
+        };
+        if candidate.cost > options.max_error {
+            break;
+        }
+
+        let h = candidate.half_edge;
+        let t = simplifier.mesh.half_edge(h).twin;
+        let (a, b) = (simplifier.mesh.half_edge(h).origin, simplifier.mesh.half_edge(t).origin);
+        if simplifier.mesh.is_vertex_removed(a) || simplifier.mesh.is_vertex_removed(b) || candidate.vertices != (a, b)
+            || candidate.versions != (simplifier.versions[a], simplifier.versions[b]) {
+            continue;
+        }
+
+        if simplifier.flips_faces(h, candidate.position) {
+            continue;
+        }
+
+        let faces = [simplifier.mesh.half_edge(h).face, simplifier.mesh.half_edge(t).face].iter().filter(|f| f.is_some()).count();
+        let kept = match simplifier.mesh.collapse_edge(h, candidate.position) {
+            Some(kept) => kept,
+            None => continue,
+        };
+
+        triangles -= faces;
+        error = error.max(candidate.cost);
+        simplifier.quadrics[kept] = simplifier.quadrics[a] + simplifier.quadrics[b];
+        collapses += 1;
+        simplifier.versions[kept] = collapses;
+
+        let outgoing: Vec<usize> = simplifier.mesh.outgoing(kept).collect();
+        for h in outgoing {
+            if let Some(c) = simplifier.candidate(h) {
+                heap.push(c);
+            }
+        }
+    }
+
+    let mesh = simplifier.mesh;
+    let (positions, indices) = mesh.to_indexed();
+    let source_vertices = (0..mesh.vertex_count()).filter(|&v| !mesh.is_vertex_removed(v)).collect();
+    Ok(SimplifiedMesh { positions, indices, source_vertices, error })
+}
+
+#[cfg(test)]
+mod tests {
+    use simplify::{simplify, Quadric, SimplifyOptions};
+    use vector3::Vector3;
+    use plane::Plane;
+
+    fn grid(n: usize, height: fn(usize, usize) -> f32) -> (Vec<Vector3>, Vec<[usize; 3]>) {
+        let mut positions = Vec::new();
+        for y in 0..n + 1 {
+            for x in 0..n + 1 {
+                positions.push(Vector3::new(x as f32, y as f32, height(x, y)));
+            }
+        }
+
+        let mut indices = Vec::new();
+        for y in 0..n {
+            for x in 0..n {
+                let i = y * (n + 1) + x;
+                indices.push([i, i + 1, i + n + 2]);
+                indices.push([i, i + n + 2, i + n + 1]);
+            }
+        }
+
+        (positions, indices)
+    }
+
+    #[test]
+    fn test_quadric() {
+        let q = Quadric::from_plane(&Plane::from_point_normal(Vector3::zero(), Vector3::new(1.0, 0.0, 0.0)))
+            + Quadric::from_plane(&Plane::from_point_normal(Vector3::new(0.0, 2.0, 0.0), Vector3::new(0.0, 1.0, 0.0)))
+            + Quadric::from_plane(&Plane::from_point_normal(Vector3::new(0.0, 0.0, 3.0), Vector3::new(0.0, 0.0, 1.0)));
+
+        assert!((q.optimal_point().unwrap() - Vector3::new(0.0, 2.0, 3.0)).length() < 0.0001);
+        assert!(q.error(Vector3::new(0.0, 2.0, 3.0)).abs() < 0.0001);
+        assert!((q.error(Vector3::new(1.0, 2.0, 5.0)) - 5.0).abs() < 0.0001);
+        assert_eq!(q.to_matrix4().data[1][3], -2.0);
+
+        let flat = Quadric::from_plane(&Plane::from_point_normal(Vector3::zero(), Vector3::up()));
+        assert!(flat.optimal_point().is_none());
+    }
+
+    #[test]
+    fn test_simplify_plane() {
+        let (positions, indices) = grid(8, |_, _| 0.0);
+        let options = SimplifyOptions { target_triangles: 10, ..SimplifyOptions::default() };
+        let result = simplify(&positions, &indices, &options).unwrap();
+
+        assert!(result.indices.len() <= 10);
+        assert!(result.error < 0.0001);
+        assert_eq!(result.positions.len(), result.source_vertices.len());
+        for p in &result.positions {
+            assert!(p.z.abs() < 0.0001);
+        }
+
+        // The corners of the square survive because the boundary planes hold them.
+        let area: f32 = result.indices.iter()
+            .map(|t| Vector3::cross(result.positions[t[1]] - result.positions[t[0]], result.positions[t[2]] - result.positions[t[0]]).length() * 0.5)
+            .sum();
+        assert!((area - 64.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_simplify_error_threshold() {
+        let (positions, indices) = grid(8, |x, y| if x == 4 && y == 4 { 2.0 } else { 0.0 });
+        let options = SimplifyOptions { max_error: 0.0001, ..SimplifyOptions::default() };
+        let result = simplify(&positions, &indices, &options).unwrap();
+
+        assert!(result.indices.len() < indices.len());
+        assert!(result.positions.iter().any(|p| (p.z - 2.0).abs() < 0.0001));
+    }
+
+    #[test]
+    fn test_simplify_preserve_boundary() {
+        let (positions, indices) = grid(6, |x, y| ((x * y) % 3) as f32 * 0.2);
+        let options = SimplifyOptions { target_triangles: 4, preserve_boundary: true, ..SimplifyOptions::default() };
+        let result = simplify(&positions, &indices, &options).unwrap();
+
+        let boundary = |p: &Vector3| p.x == 0.0 || p.y == 0.0 || p.x == 6.0 || p.y == 6.0;
+        assert_eq!(result.positions.iter().filter(|p| boundary(p)).count(), 24);
+        for (p, &source) in result.positions.iter().zip(result.source_vertices.iter()) {
+            if boundary(&positions[source]) {
+                assert_eq!(p.x, positions[source].x);
+                assert_eq!(p.y, positions[source].y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_simplify_preserve_uv_seams() {
+        // Two flat halves whose shared column of vertices is duplicated.
+        let (mut positions, mut indices) = grid(4, |_, _| 0.0);
+        let (right, right_indices) = grid(4, |_, _| 0.0);
+        let offset = positions.len();
+        positions.extend(right.iter().map(|p| *p + Vector3::new(4.0, 0.0, 0.0)));
+        indices.extend(right_indices.iter().map(|t| [t[0] + offset, t[1] + offset, t[2] + offset]));
+
+        let options = SimplifyOptions { target_triangles: 0, ..SimplifyOptions::default() };
+        let result = simplify(&positions, &indices, &options).unwrap();
+        assert_eq!(result.positions.iter().filter(|p| p.x == 4.0).count(), 10);
+    }
+}
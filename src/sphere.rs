@@ -0,0 +1,248 @@
+extern crate rand;
+
+use self::rand::seq::SliceRandom;
+use vector3::Vector3;
+use ray::Ray;
+use aabb::Aabb;
+use obb::Obb;
+
+/// A bounding sphere defined by a center and radius.
+#[derive(Debug, Copy, Clone)]
+pub struct Sphere {
+    pub center: Vector3,
+    pub radius: f32,
+}
+
+impl Sphere {
+    /// Returns a new `Sphere`.
+    pub fn new(center: Vector3, radius: f32) -> Sphere {
+        Sphere { center, radius }
+    }
+
+    /// Returns a sphere containing all of `points` using Ritter's algorithm.
+    /// The sphere is fast to compute but is usually 5-20% larger than the minimal sphere.
+    pub fn from_points_ritter(points: &[Vector3]) -> Sphere {
+        if points.is_empty() {
+            return Sphere::new(Vector3::zero(), 0.0);
+        }
+
+        let farthest = |from: Vector3| {
+            points.iter().cloned().fold(from, |best, p| {
+                if (p - from).length_squared() > (best - from).length_squared() { p } else { best }
+            })
+        };
+
+        let y = farthest(points[0]);
+        let z = farthest(y);
+        let mut sphere = Sphere::new((y + z) * 0.5, (z - y).length() * 0.5);
+
+        for p in points.iter() {
+            let d = (*p - sphere.center).length();
+            if d > sphere.radius {
+                let radius = (sphere.radius + d) * 0.5;
+                sphere.center = sphere.center + (*p - sphere.center) * ((radius - sphere.radius) / d);
+                sphere.radius = radius;
+            }
+        }
+
+        sphere
+    }
+
+    /// Returns the minimal sphere containing all of `points` using Welzl's algorithm.
+    pub fn from_points_welzl(points: &[Vector3]) -> Sphere {
+        let mut points = points.to_vec();
+        points.shuffle(&mut rand::thread_rng());
+
+        let mut sphere = Sphere::new(Vector3::zero(), -1.0);
+        for i in 0..points.len() {
+            if sphere.encloses(points[i]) {
+                continue;
+            }
+
+            sphere = Sphere::new(points[i], 0.0);
+            for j in 0..i {
+                if sphere.encloses(points[j]) {
+                    continue;
+                }
+
+                sphere = Sphere::from_two(points[i], points[j]);
+                for k in 0..j {
+                    if sphere.encloses(points[k]) {
+                        continue;
+                    }
+
+                    sphere = Sphere::from_three(points[i], points[j], points[k]);
+                    for l in 0..k {
+                        if !sphere.encloses(points[l]) {
+                            sphere = Sphere::from_four(points[i], points[j], points[k], points[l]);
+                        }
+                    }
+                }
+            }
+        }
+
+        if sphere.radius < 0.0 {
+            sphere.radius = 0.0;
+        }
+
+        sphere
+    }
+
+    /// Returns true if `point` is inside the sphere, allowing a small tolerance for rounding.
+    fn encloses(&self, point: Vector3) -> bool {
+        let tolerance = self.radius.abs() * 1e-5 + 1e-6;
+        (point - self.center).length() <= self.radius + tolerance
+    }
+
+    /// Returns the smallest sphere with `a` and `b` on its surface.
+    fn from_two(a: Vector3, b: Vector3) -> Sphere {
+        Sphere::new((a + b) * 0.5, (b - a).length() * 0.5)
+    }
+
+    /// Returns the smallest sphere with `a`, `b` and `c` on its surface.
+    fn from_three(a: Vector3, b: Vector3, c: Vector3) -> Sphere {
+        let ab = b - a;
+        let ac = c - a;
+        let n = Vector3::cross(ab, ac);
+        let denom = 2.0 * n.length_squared();
+
+        if denom <= f32::EPSILON * (ab.length_squared() + ac.length_squared()) {
+            // Collinear points, the farthest pair bounds the others.
+            let candidates = [Sphere::from_two(a, b), Sphere::from_two(a, c), Sphere::from_two(b, c)];
+            return candidates.iter().cloned().fold(candidates[0], |best, s| if s.radius > best.radius { s } else { best });
+        }
+
+        let offset = (Vector3::cross(n, ab) * ac.length_squared() + Vector3::cross(ac, n) * ab.length_squared()) / denom;
+        Sphere::new(a + offset, offset.length())
+    }
+
+    /// Returns the smallest sphere with `a`, `b`, `c` and `d` on its surface.
+    fn from_four(a: Vector3, b: Vector3, c: Vector3, d: Vector3) -> Sphere {
+        let ab = b - a;
+        let ac = c - a;
+        let ad = d - a;
+        let denom = 2.0 * Vector3::dot(ab, Vector3::cross(ac, ad));
+        let scale = ab.length_squared() + ac.length_squared() + ad.length_squared();
+
+        if denom.abs() <= f32::EPSILON * scale * scale.sqrt() {
+            // Coplanar points, use the smallest circle through three of them that holds the fourth.
+            let candidates = [Sphere::from_three(a, b, c), Sphere::from_three(a, b, d),
+                              Sphere::from_three(a, c, d), Sphere::from_three(b, c, d)];
+            let points = [a, b, c, d];
+            return candidates.iter()
+                .cloned()
+                .filter(|s| points.iter().all(|p| s.encloses(*p)))
+                .fold(None, |best: Option<Sphere>, s| match best {
+                    Some(b) if b.radius <= s.radius => Some(b),
+                    _ => Some(s),
+                })
+                .unwrap_or(candidates[0]);
+        }
+
+        let offset = (Vector3::cross(ac, ad) * ab.length_squared() +
+                      Vector3::cross(ad, ab) * ac.length_squared() +
+                      Vector3::cross(ab, ac) * ad.length_squared()) / denom;
+        Sphere::new(a + offset, offset.length())
+    }
+
+    /// Returns true if `point` is inside the sphere.
+    pub fn contains_point(&self, point: Vector3) -> bool {
+        (point - self.center).length_squared() <= self.radius * self.radius
+    }
+
+    /// Returns true if the sphere overlaps `other`.
+    pub fn intersects_sphere(&self, other: &Sphere) -> bool {
+        let r = self.radius + other.radius;
+        (other.center - self.center).length_squared() <= r * r
+    }
+
+    /// Returns true if the sphere overlaps `aabb`.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.contains_point(aabb.closest_point(self.center))
+    }
+
+    /// Returns true if the sphere overlaps `obb`.
+    pub fn intersects_obb(&self, obb: &Obb) -> bool {
+        self.contains_point(obb.closest_point(self.center))
+    }
+
+    /// Returns the time along `ray` where it enters the sphere, or 0 if the ray starts inside.
+    /// Returns `None` if the ray misses the sphere.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<f32> {
+        let oc = ray.origin() - self.center;
+        let a = ray.direction().length_squared();
+        let b = Vector3::dot(oc, ray.direction());
+        let c = oc.length_squared() - self.radius * self.radius;
+
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+        if b > 0.0 || a == 0.0 {
+            return None;
+        }
+
+        let discriminant = b * b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        Some((-b - discriminant.sqrt()) / a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sphere::Sphere;
+    use vector3::Vector3;
+    use ray::Ray;
+
+    fn cube_points() -> Vec<Vector3> {
+        let mut points = Vec::new();
+        for i in 0..8 {
+            points.push(Vector3::new((i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32));
+        }
+        points.push(Vector3::new(0.5, 0.5, 0.5));
+        points.push(Vector3::new(0.2, 0.7, 0.1));
+        points
+    }
+
+    #[test]
+    fn test_sphere_welzl() {
+        let points = cube_points();
+        let s = Sphere::from_points_welzl(&points);
+
+        assert!((s.center - Vector3::new(0.5, 0.5, 0.5)).length() < 0.001);
+        assert!((s.radius - 0.75f32.sqrt()).abs() < 0.001);
+
+        let s = Sphere::from_points_welzl(&[Vector3::new(-1.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.1, 0.0)]);
+        assert!((s.radius - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_sphere_ritter() {
+        let points = cube_points();
+        let s = Sphere::from_points_ritter(&points);
+
+        assert!(s.radius >= 0.75f32.sqrt() - 0.001);
+        for p in points.iter() {
+            assert!((*p - s.center).length() <= s.radius + 0.001);
+        }
+    }
+
+    #[test]
+    fn test_sphere_intersect_ray() {
+        let s = Sphere::new(Vector3::new(0.0, 0.0, 5.0), 1.0);
+
+        assert_eq!(s.intersect_ray(&Ray::new(Vector3::zero(), Vector3::forward(), 0.0)), Some(4.0));
+        assert_eq!(s.intersect_ray(&Ray::new(Vector3::new(0.0, 0.0, 5.0), Vector3::forward(), 0.0)), Some(0.0));
+        assert!(s.intersect_ray(&Ray::new(Vector3::zero(), Vector3::up(), 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_sphere_intersects_sphere() {
+        let a = Sphere::new(Vector3::zero(), 1.0);
+
+        assert!(a.intersects_sphere(&Sphere::new(Vector3::new(1.5, 0.0, 0.0), 0.5)));
+        assert!(!a.intersects_sphere(&Sphere::new(Vector3::new(1.5, 0.0, 0.0), 0.4)));
+    }
+}
@@ -0,0 +1,480 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+use vector3::Vector3;
+use triangle::Triangle;
+use mesh::TriangleMesh;
+
+/// An error from reading an STL file.
+#[derive(Debug)]
+pub enum StlError {
+    /// Reading from the source failed.
+    Io(io::Error),
+    /// An ascii file has a different keyword than the one expected.
+    UnexpectedToken { line: usize, expected: &'static str, found: String },
+    /// An ascii value could not be parsed as a number.
+    InvalidNumber { line: usize, value: String },
+    /// The data ended before the last facet was complete.
+    UnexpectedEof,
+    /// A binary file's size does not match its facet count.
+    SizeMismatch { facets: u32, bytes: usize },
+}
+
+impl fmt::Display for StlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StlError::Io(ref e) => write!(f, "failed to read STL: {}", e),
+            StlError::UnexpectedToken { line, expected, ref found } => {
+                write!(f, "line {}: expected `{}` but found `{}`", line, expected, found)
+            },
+            StlError::InvalidNumber { line, ref value } => write!(f, "line {}: `{}` is not a number", line, value),
+            StlError::UnexpectedEof => write!(f, "data ended in the middle of a facet"),
+            StlError::SizeMismatch { facets, bytes } => {
+                write!(f, "binary STL with {} facets cannot be {} bytes long", facets, bytes)
+            },
+        }
+    }
+}
+
+impl Error for StlError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            StlError::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StlError {
+    fn from(e: io::Error) -> StlError {
+        StlError::Io(e)
+    }
+}
+
+/// The encoding of an STL file.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StlFormat {
+    Ascii,
+    Binary,
+}
+
+/// A triangle in an STL file with its stored normal.
+#[derive(Debug, Copy, Clone)]
+pub struct StlFacet {
+    pub normal: Vector3,
+    pub triangle: Triangle,
+}
+
+/// The triangle soup of an STL file.
+#[derive(Debug, Clone)]
+pub struct StlMesh {
+    /// The solid name of an ascii file or the header text of a binary file.
+    pub name: String,
+    pub facets: Vec<StlFacet>,
+}
+
+/// The topology of a triangle soup after welding identical vertex positions.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TopologyReport {
+    /// The number of distinct vertex positions.
+    pub vertices: usize,
+    /// The number of distinct edges.
+    pub edges: usize,
+    /// The number of facets with no area.
+    pub degenerate_facets: usize,
+    /// The number of edges used by only one facet.
+    pub boundary_edges: usize,
+    /// The number of edges used by more than two facets.
+    pub non_manifold_edges: usize,
+    /// The number of edges whose two facets traverse it in the same direction.
+    pub inconsistent_edges: usize,
+    /// The number of vertices whose facets do not form a single connected fan.
+    pub non_manifold_vertices: usize,
+}
+
+impl TopologyReport {
+    /// Returns true if every edge is shared by exactly two facets.
+    pub fn is_watertight(&self) -> bool {
+        self.boundary_edges == 0 && self.non_manifold_edges == 0
+    }
+
+    /// Returns true if every edge has at most two facets and the facets around every vertex are connected.
+    pub fn is_manifold(&self) -> bool {
+        self.non_manifold_edges == 0 && self.non_manifold_vertices == 0
+    }
+
+    /// Returns true if adjacent facets agree on their winding.
+    pub fn is_consistently_oriented(&self) -> bool {
+        self.inconsistent_edges == 0
+    }
+}
+
+/// Returns the index of the set containing `i` in `parents`.
+fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+
+    i
+}
+
+impl StlMesh {
+    /// Returns an `StlMesh` of `triangles` with their computed normals.
+    pub fn from_triangles(name: &str, triangles: &[Triangle]) -> StlMesh {
+        let facets = triangles.iter().map(|&triangle| StlFacet { normal: triangle.normal(), triangle }).collect();
+        StlMesh { name: name.to_string(), facets }
+    }
+
+    /// Returns the distinct vertex positions and the indices of each facet's vertices into them.
+    /// Only exactly equal positions are merged.
+    pub fn weld(&self) -> (Vec<Vector3>, Vec<[usize; 3]>) {
+        let mut positions = Vec::new();
+        let mut lookup: HashMap<[u32; 3], usize> = HashMap::new();
+        let mut indices = Vec::with_capacity(self.facets.len());
+
+        for facet in &self.facets {
+            let mut face = [0; 3];
+            for (corner, index) in face.iter_mut().enumerate() {
+                let v = facet.triangle.vertex(corner);
+                // Adding 0 turns -0 into +0 so both weld together.
+                let key = [(v.x + 0.0).to_bits(), (v.y + 0.0).to_bits(), (v.z + 0.0).to_bits()];
+                let next = positions.len();
+                *index = *lookup.entry(key).or_insert(next);
+                if *index == next {
+                    positions.push(v);
+                }
+            }
+            indices.push(face);
+        }
+
+        (positions, indices)
+    }
+
+    /// Returns a `TriangleMesh` of the welded facets.
+    pub fn to_triangle_mesh(&self) -> TriangleMesh {
+        let (positions, indices) = self.weld();
+        TriangleMesh::new(positions, indices).expect("welded indices are in range")
+    }
+
+    /// Returns the topology of the welded facets, describing whether they form a closed manifold surface.
+    pub fn check_topology(&self) -> TopologyReport {
+        let (positions, indices) = self.weld();
+
+        // Each undirected edge maps to its facets and the number of times it is traversed from low to high index.
+        let mut edges: HashMap<(usize, usize), (Vec<usize>, usize)> = HashMap::new();
+        let mut degenerate_facets = 0;
+        for (f, face) in indices.iter().enumerate() {
+            if face[0] == face[1] || face[1] == face[2] || face[2] == face[0] || self.facets[f].triangle.is_degenerate() {
+                degenerate_facets += 1;
+            }
+
+            for corner in 0..3 {
+                let (a, b) = (face[corner], face[(corner + 1) % 3]);
+                if a == b {
+                    continue;
+                }
+
+                let entry = edges.entry((a.min(b), a.max(b))).or_insert((Vec::new(), 0));
+                entry.0.push(f);
+                if a < b {
+                    entry.1 += 1;
+                }
+            }
+        }
+
+        let mut report = TopologyReport {
+            vertices: positions.len(),
+            edges: edges.len(),
+            degenerate_facets,
+            boundary_edges: 0,
+            non_manifold_edges: 0,
+            inconsistent_edges: 0,
+            non_manifold_vertices: 0,
+        };
+
+        for &(ref facets, forward) in edges.values() {
+            match facets.len() {
+                1 => report.boundary_edges += 1,
+                2 => if forward != 1 { report.inconsistent_edges += 1 },
+                _ => report.non_manifold_edges += 1,
+            }
+        }
+
+        // Join the facets around each vertex that share another vertex and count the separate fans.
+        let mut vertex_facets: Vec<Vec<usize>> = vec![Vec::new(); positions.len()];
+        for (f, face) in indices.iter().enumerate() {
+            for &v in face.iter() {
+                if !vertex_facets[v].contains(&f) {
+                    vertex_facets[v].push(f);
+                }
+            }
+        }
+
+        for (v, facets) in vertex_facets.iter().enumerate() {
+            let mut local: Vec<usize> = (0..facets.len()).collect();
+            for i in 0..facets.len() {
+                for j in i + 1..facets.len() {
+                    let (fi, fj) = (indices[facets[i]], indices[facets[j]]);
+                    let shared = fi.iter().filter(|&&x| x != v && fj.contains(&x)).count();
+                    if shared > 0 {
+                        let (r0, r1) = (find(&mut local, i), find(&mut local, j));
+                        local[r0] = r1;
+                    }
+                }
+            }
+
+            let fans = (0..facets.len()).filter(|&i| find(&mut local, i) == i).count();
+            if fans > 1 {
+                report.non_manifold_vertices += 1;
+            }
+        }
+
+        report
+    }
+}
+
+/// Splits ascii STL text into tokens with their line numbers.
+fn tokenize(text: &str) -> Vec<(usize, &str)> {
+    text.lines()
+        .enumerate()
+        .flat_map(|(i, line)| line.split_whitespace().map(move |token| (i + 1, token)))
+        .collect()
+}
+
+/// Reads an ascii STL file from `text`.
+fn read_ascii(text: &str) -> Result<StlMesh, StlError> {
+    let tokens = tokenize(text);
+    let mut position = 0;
+
+    let mut next = || -> Result<(usize, &str), StlError> {
+        let token = tokens.get(position).cloned().ok_or(StlError::UnexpectedEof);
+        position += 1;
+        token
+    };
+
+    fn expect(token: (usize, &str), expected: &'static str) -> Result<(), StlError> {
+        if token.1 != expected {
+            return Err(StlError::UnexpectedToken { line: token.0, expected, found: token.1.to_string() });
+        }
+        Ok(())
+    }
+
+    fn number(token: (usize, &str)) -> Result<f32, StlError> {
+        token.1.parse::<f32>().map_err(|_| StlError::InvalidNumber { line: token.0, value: token.1.to_string() })
+    }
+
+    let solid = next()?;
+    expect(solid, "solid")?;
+    let name = text.lines().nth(solid.0 - 1).unwrap_or("").trim().trim_start_matches("solid").trim().to_string();
+
+    // Skip the rest of the name on the solid line.
+    let mut token = next()?;
+    while token.0 == solid.0 {
+        token = next()?;
+    }
+
+    let mut facets = Vec::new();
+    loop {
+        if token.1 == "endsolid" {
+            break;
+        }
+        expect(token, "facet")?;
+        expect(next()?, "normal")?;
+        let normal = Vector3::new(number(next()?)?, number(next()?)?, number(next()?)?);
+        expect(next()?, "outer")?;
+        expect(next()?, "loop")?;
+
+        let mut vertices = [Vector3::zero(); 3];
+        for v in vertices.iter_mut() {
+            expect(next()?, "vertex")?;
+            *v = Vector3::new(number(next()?)?, number(next()?)?, number(next()?)?);
+        }
+
+        expect(next()?, "endloop")?;
+        expect(next()?, "endfacet")?;
+        facets.push(StlFacet { normal, triangle: Triangle::new(vertices[0], vertices[1], vertices[2]) });
+        token = next()?;
+    }
+
+    Ok(StlMesh { name, facets })
+}
+
+/// Reads a binary STL file from `data`.
+fn read_binary(data: &[u8]) -> Result<StlMesh, StlError> {
+    if data.len() < 84 {
+        return Err(StlError::UnexpectedEof);
+    }
+
+    let count = u32::from_le_bytes([data[80], data[81], data[82], data[83]]);
+    if data.len() != 84 + count as usize * 50 {
+        return Err(StlError::SizeMismatch { facets: count, bytes: data.len() });
+    }
+
+    let header = String::from_utf8_lossy(&data[..80]);
+    let name = header.trim_end_matches('\0').trim().to_string();
+
+    let float = |offset: usize| f32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]);
+    let vector = |offset: usize| Vector3::new(float(offset), float(offset + 4), float(offset + 8));
+
+    let facets = (0..count as usize)
+        .map(|i| {
+            let offset = 84 + i * 50;
+            StlFacet {
+                normal: vector(offset),
+                triangle: Triangle::new(vector(offset + 12), vector(offset + 24), vector(offset + 36)),
+            }
+        })
+        .collect();
+
+    Ok(StlMesh { name, facets })
+}
+
+/// Reads an ascii or binary STL file from `reader`.
+/// Files are read as binary when their size matches their facet count, since binary headers may also start with `solid`.
+pub fn read_stl<R: Read>(mut reader: R) -> Result<StlMesh, StlError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    if data.len() >= 84 {
+        let count = u32::from_le_bytes([data[80], data[81], data[82], data[83]]) as usize;
+        if data.len() == 84 + count * 50 {
+            return read_binary(&data);
+        }
+    }
+
+    let start = data.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(data.len());
+    if data[start..].starts_with(b"solid") {
+        if let Ok(text) = ::std::str::from_utf8(&data) {
+            return read_ascii(text);
+        }
+    }
+
+    read_binary(&data)
+}
+
+/// Writes `mesh` to `writer` as an STL file in `format`.
+/// Binary files keep the first 80 bytes of the name as their header.
+pub fn write_stl<W: Write>(writer: &mut W, mesh: &StlMesh, format: StlFormat) -> io::Result<()> {
+    match format {
+        StlFormat::Ascii => {
+            writeln!(writer, "solid {}", mesh.name)?;
+            for facet in &mesh.facets {
+                let n = facet.normal;
+                writeln!(writer, "  facet normal {:e} {:e} {:e}", n.x, n.y, n.z)?;
+                writeln!(writer, "    outer loop")?;
+                for i in 0..3 {
+                    let v = facet.triangle.vertex(i);
+                    writeln!(writer, "      vertex {:e} {:e} {:e}", v.x, v.y, v.z)?;
+                }
+                writeln!(writer, "    endloop")?;
+                writeln!(writer, "  endfacet")?;
+            }
+            writeln!(writer, "endsolid {}", mesh.name)?;
+        },
+        StlFormat::Binary => {
+            let mut header = [0u8; 80];
+            let name = mesh.name.as_bytes();
+            let length = name.len().min(80);
+            header[..length].copy_from_slice(&name[..length]);
+            writer.write_all(&header)?;
+            writer.write_all(&(mesh.facets.len() as u32).to_le_bytes())?;
+
+            for facet in &mesh.facets {
+                let t = facet.triangle;
+                for v in [facet.normal, t.v0, t.v1, t.v2].iter() {
+                    writer.write_all(&v.x.to_le_bytes())?;
+                    writer.write_all(&v.y.to_le_bytes())?;
+                    writer.write_all(&v.z.to_le_bytes())?;
+                }
+                writer.write_all(&[0, 0])?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use stl::{read_stl, write_stl, StlError, StlFormat, StlMesh};
+    use vector3::Vector3;
+    use triangle::Triangle;
+
+    fn tetrahedron() -> StlMesh {
+        let (a, b, c, d) = (Vector3::zero(), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+        let triangles = [Triangle::new(a, c, b), Triangle::new(a, b, d), Triangle::new(a, d, c), Triangle::new(b, c, d)];
+        StlMesh::from_triangles("tetrahedron", &triangles)
+    }
+
+    #[test]
+    fn test_stl_round_trip() {
+        let mesh = tetrahedron();
+
+        for &format in [StlFormat::Ascii, StlFormat::Binary].iter() {
+            let mut data = Vec::new();
+            write_stl(&mut data, &mesh, format).unwrap();
+            let copy = read_stl(&data[..]).unwrap();
+
+            assert_eq!(copy.name, "tetrahedron");
+            assert_eq!(copy.facets.len(), 4);
+            for (f0, f1) in mesh.facets.iter().zip(copy.facets.iter()) {
+                assert!((f0.normal - f1.normal).length() < 0.0001);
+                assert!((f0.triangle.v2 - f1.triangle.v2).length() < 0.0001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_stl_errors() {
+        match read_stl("solid x\n facet normal 0 0 1\n outer loop\n vertex 0 0 0\n endloop".as_bytes()) {
+            Err(StlError::UnexpectedToken { line: 5, expected: "vertex", .. }) => {},
+            other => panic!("unexpected {:?}", other),
+        }
+        match read_stl("solid x\n facet normal 0 zero 1".as_bytes()) {
+            Err(StlError::InvalidNumber { line: 2, .. }) => {},
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let mut data = Vec::new();
+        write_stl(&mut data, &tetrahedron(), StlFormat::Binary).unwrap();
+        data.pop();
+        match read_stl(&data[..]) {
+            Err(StlError::SizeMismatch { facets: 4, .. }) => {},
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_stl_check_topology() {
+        let mut mesh = tetrahedron();
+        let report = mesh.check_topology();
+        assert_eq!(report.vertices, 4);
+        assert_eq!(report.edges, 6);
+        assert!(report.is_watertight() && report.is_manifold() && report.is_consistently_oriented());
+
+        // Flipping one facet breaks orientation but not watertightness.
+        let t = mesh.facets[3].triangle;
+        mesh.facets[3].triangle = Triangle::new(t.v0, t.v2, t.v1);
+        let report = mesh.check_topology();
+        assert!(report.is_watertight());
+        assert_eq!(report.inconsistent_edges, 3);
+
+        // Removing a facet opens 3 boundary edges.
+        mesh.facets.pop();
+        let report = mesh.check_topology();
+        assert!(!report.is_watertight());
+        assert_eq!(report.boundary_edges, 3);
+
+        // Two tetrahedra touching at a single vertex are not manifold.
+        let mut pair = tetrahedron();
+        let shifted: Vec<Triangle> = tetrahedron().facets.iter()
+            .map(|f| Triangle::new(f.triangle.v0 * -1.0, f.triangle.v2 * -1.0, f.triangle.v1 * -1.0))
+            .collect();
+        pair.facets.extend(StlMesh::from_triangles("", &shifted).facets);
+        let report = pair.check_topology();
+        assert!(report.is_watertight());
+        assert_eq!(report.non_manifold_vertices, 1);
+    }
+}
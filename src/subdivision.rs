@@ -0,0 +1,314 @@
+use std::collections::{HashMap, HashSet};
+use std::f32::consts::PI;
+use vector3::Vector3;
+
+/// The faces and new point of an edge during subdivision.
+struct EdgeInfo {
+    faces: Vec<usize>,
+    point: usize,
+}
+
+/// Returns the key of the undirected edge between `a` and `b`.
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// Returns the edges of `faces` with the faces that use them, giving each a new point index after `first_point`.
+fn collect_edges<F: AsRef<[usize]>>(faces: &[F], first_point: usize) -> HashMap<(usize, usize), EdgeInfo> {
+    let mut edges: HashMap<(usize, usize), EdgeInfo> = HashMap::new();
+    for (f, face) in faces.iter().enumerate() {
+        let face = face.as_ref();
+        for i in 0..face.len() {
+            let next = first_point + edges.len();
+            let edge = edges.entry(edge_key(face[i], face[(i + 1) % face.len()]))
+                .or_insert(EdgeInfo { faces: Vec::new(), point: next });
+            edge.faces.push(f);
+        }
+    }
+
+    edges
+}
+
+/// Returns true if the edge between `a` and `b` is sharp because it is on a boundary, creased or non-manifold.
+fn is_sharp(edges: &HashMap<(usize, usize), EdgeInfo>, creases: &HashSet<(usize, usize)>, a: usize, b: usize) -> bool {
+    let key = edge_key(a, b);
+    creases.contains(&key) || edges[&key].faces.len() != 2
+}
+
+/// Returns the neighbors of each vertex and the ones connected by sharp edges.
+fn vertex_neighbors(vertex_count: usize, edges: &HashMap<(usize, usize), EdgeInfo>, creases: &HashSet<(usize, usize)>)
+    -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+    let mut neighbors = vec![Vec::new(); vertex_count];
+    let mut sharp = vec![Vec::new(); vertex_count];
+    for &(a, b) in edges.keys() {
+        neighbors[a].push(b);
+        neighbors[b].push(a);
+        if is_sharp(edges, creases, a, b) {
+            sharp[a].push(b);
+            sharp[b].push(a);
+        }
+    }
+
+    (neighbors, sharp)
+}
+
+/// Returns the position of a vertex on a sharp edge or corner, or `None` if the vertex is smooth.
+/// Vertices on 2 sharp edges follow the curve rule and vertices on more stay in place as corners.
+fn sharp_vertex(v: Vector3, sharp: &[usize], positions: &[Vector3]) -> Option<Vector3> {
+    match sharp.len() {
+        0 | 1 => None,
+        2 => Some(v * 0.75 + (positions[sharp[0]] + positions[sharp[1]]) * 0.125),
+        _ => Some(v),
+    }
+}
+
+/// Returns the crease edges after each is split at its new point.
+fn split_creases(creases: &HashSet<(usize, usize)>, edges: &HashMap<(usize, usize), EdgeInfo>) -> HashSet<(usize, usize)> {
+    let mut split = HashSet::new();
+    for &(a, b) in creases {
+        if let Some(edge) = edges.get(&(a, b)) {
+            split.insert(edge_key(a, edge.point));
+            split.insert(edge_key(edge.point, b));
+        }
+    }
+
+    split
+}
+
+/// Returns the triangles `indices` into `positions` after `levels` steps of Loop subdivision.
+/// Boundary edges and the edges in `creases` stay sharp, and vertices on more than 2 sharp edges stay in place.
+/// Original vertices keep their indices and new vertices are added after them.
+/// Panics if a triangle uses the same vertex twice.
+pub fn loop_subdivide(positions: &[Vector3], indices: &[[usize; 3]], creases: &[(usize, usize)], levels: usize)
+    -> (Vec<Vector3>, Vec<[usize; 3]>) {
+    assert!(indices.iter().all(|&[a, b, c]| a != b && b != c && c != a), "triangles must have 3 distinct vertices");
+    let mut positions = positions.to_vec();
+    let mut indices = indices.to_vec();
+    let mut creases: HashSet<(usize, usize)> = creases.iter().map(|&(a, b)| edge_key(a, b)).collect();
+
+    for _ in 0..levels {
+        let edges = collect_edges(&indices, positions.len());
+        let (neighbors, sharp) = vertex_neighbors(positions.len(), &edges, &creases);
+
+        let mut next = Vec::with_capacity(positions.len() + edges.len());
+        for (v, &p) in positions.iter().enumerate() {
+            let point = sharp_vertex(p, &sharp[v], &positions).unwrap_or_else(|| {
+                let n = neighbors[v].len();
+                if n == 0 {
+                    return p;
+                }
+
+                let c = 0.375 + 0.25 * (2.0 * PI / n as f32).cos();
+                let beta = (0.625 - c * c) / n as f32;
+                let sum = neighbors[v].iter().fold(Vector3::zero(), |sum, &u| sum + positions[u]);
+                p * (1.0 - n as f32 * beta) + sum * beta
+            });
+            next.push(point);
+        }
+
+        next.resize(positions.len() + edges.len(), Vector3::zero());
+        for (&(a, b), edge) in &edges {
+            next[edge.point] = if is_sharp(&edges, &creases, a, b) {
+                (positions[a] + positions[b]) * 0.5
+            } else {
+                let opposite = |f: usize| indices[f].iter().cloned().find(|&v| v != a && v != b).unwrap();
+                (positions[a] + positions[b]) * 0.375
+                    + (positions[opposite(edge.faces[0])] + positions[opposite(edge.faces[1])]) * 0.125
+            };
+        }
+
+        let point = |a: usize, b: usize| edges[&edge_key(a, b)].point;
+        indices = indices.iter()
+            .flat_map(|&[a, b, c]| {
+                let (ab, bc, ca) = (point(a, b), point(b, c), point(c, a));
+                vec![[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]]
+            })
+            .collect();
+
+        creases = split_creases(&creases, &edges);
+        positions = next;
+    }
+
+    (positions, indices)
+}
+
+/// Returns the quads after `levels` steps of Catmull–Clark subdivision of the polygons `faces` into `positions`.
+/// Boundary edges and the edges in `creases` stay sharp, and vertices on more than 2 sharp edges stay in place.
+/// Original vertices keep their indices, followed by the new edge points and then the face points of each level.
+/// Panics if `levels` is 0, since only subdivided faces are all quads, or if a face has fewer than 3 vertices.
+pub fn catmull_clark_subdivide(positions: &[Vector3], faces: &[Vec<usize>], creases: &[(usize, usize)], levels: usize)
+    -> (Vec<Vector3>, Vec<[usize; 4]>) {
+    assert!(levels > 0, "at least one level of subdivision is required");
+    assert!(faces.iter().all(|face| face.len() >= 3), "faces must have at least 3 vertices");
+    let mut positions = positions.to_vec();
+    let mut faces = faces.to_vec();
+    let mut creases: HashSet<(usize, usize)> = creases.iter().map(|&(a, b)| edge_key(a, b)).collect();
+
+    for _ in 0..levels {
+        let edges = collect_edges(&faces, positions.len());
+        let (neighbors, sharp) = vertex_neighbors(positions.len(), &edges, &creases);
+        let first_face_point = positions.len() + edges.len();
+
+        let face_points: Vec<Vector3> = faces.iter()
+            .map(|face| face.iter().fold(Vector3::zero(), |sum, &v| sum + positions[v]) / face.len() as f32)
+            .collect();
+
+        let mut vertex_faces = vec![Vec::new(); positions.len()];
+        for (f, face) in faces.iter().enumerate() {
+            for &v in face {
+                vertex_faces[v].push(f);
+            }
+        }
+
+        let mut next = Vec::with_capacity(first_face_point + faces.len());
+        for (v, &p) in positions.iter().enumerate() {
+            let point = sharp_vertex(p, &sharp[v], &positions).unwrap_or_else(|| {
+                let n = neighbors[v].len();
+                if n < 3 || vertex_faces[v].is_empty() {
+                    return p;
+                }
+
+                let f = vertex_faces[v].iter().fold(Vector3::zero(), |sum, &f| sum + face_points[f]) / vertex_faces[v].len() as f32;
+                let r = neighbors[v].iter().fold(Vector3::zero(), |sum, &u| sum + (p + positions[u]) * 0.5) / n as f32;
+                (f + r * 2.0 + p * (n as f32 - 3.0)) / n as f32
+            });
+            next.push(point);
+        }
+
+        next.resize(first_face_point, Vector3::zero());
+        for (&(a, b), edge) in &edges {
+            next[edge.point] = if is_sharp(&edges, &creases, a, b) {
+                (positions[a] + positions[b]) * 0.5
+            } else {
+                (positions[a] + positions[b] + face_points[edge.faces[0]] + face_points[edge.faces[1]]) * 0.25
+            };
+        }
+        next.extend_from_slice(&face_points);
+
+        let point = |a: usize, b: usize| edges[&edge_key(a, b)].point;
+        faces = faces.iter()
+            .enumerate()
+            .flat_map(|(f, face)| {
+                let n = face.len();
+                (0..n).map(|i| {
+                    let (prev, v, after) = (face[(i + n - 1) % n], face[i], face[(i + 1) % n]);
+                    vec![v, point(v, after), first_face_point + f, point(prev, v)]
+                }).collect::<Vec<Vec<usize>>>()
+            })
+            .collect();
+
+        creases = split_creases(&creases, &edges);
+        positions = next;
+    }
+
+    let quads = faces.iter().map(|face| [face[0], face[1], face[2], face[3]]).collect();
+    (positions, quads)
+}
+
+#[cfg(test)]
+mod tests {
+    use subdivision::{loop_subdivide, catmull_clark_subdivide};
+    use vector3::Vector3;
+
+    fn cube() -> (Vec<Vector3>, Vec<Vec<usize>>) {
+        let mut positions = Vec::new();
+        for &z in [-1.0, 1.0].iter() {
+            for &y in [-1.0, 1.0].iter() {
+                for &x in [-1.0, 1.0].iter() {
+                    positions.push(Vector3::new(x, y, z));
+                }
+            }
+        }
+
+        let faces = vec![vec![0, 2, 3, 1], vec![4, 5, 7, 6], vec![0, 1, 5, 4],
+                         vec![2, 6, 7, 3], vec![0, 4, 6, 2], vec![1, 3, 7, 5]];
+        (positions, faces)
+    }
+
+    #[test]
+    fn test_loop_subdivide() {
+        let positions = vec![Vector3::zero(), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0)];
+        let indices = [[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]];
+
+        let (p, i) = loop_subdivide(&positions, &indices, &[], 2);
+        assert_eq!(i.len(), 64);
+        assert_eq!(p.len(), 34);
+
+        // A flat square stays flat and inside its boundary.
+        let square = [Vector3::zero(), Vector3::new(1.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 0.0), Vector3::new(0.0, 1.0, 0.0)];
+        let (p, _) = loop_subdivide(&square, &[[0, 1, 2], [0, 2, 3]], &[], 3);
+        for v in &p {
+            assert_eq!(v.z, 0.0);
+        }
+        assert!(p.iter().all(|v| v.x >= 0.0 && v.x <= 1.0 && v.y >= 0.0 && v.y <= 1.0));
+    }
+
+    #[test]
+    fn test_catmull_clark_subdivide() {
+        let (positions, faces) = cube();
+
+        let (p, q) = catmull_clark_subdivide(&positions, &faces, &[], 1);
+        assert_eq!(q.len(), 24);
+        assert_eq!(p.len(), 26);
+        assert!((p[7] - Vector3::new(5.0, 5.0, 5.0) / 9.0).length() < 0.0001);
+
+        let (p, q) = catmull_clark_subdivide(&positions, &faces, &[], 3);
+        assert_eq!(q.len(), 6 * 64);
+        for v in &p {
+            assert!(v.length() < 3.0f32.sqrt());
+        }
+    }
+
+    #[test]
+    fn test_catmull_clark_polygons() {
+        // A pentagon and a triangle sharing an edge become quads around their face points.
+        let positions = vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(2.0, 0.0, 0.0), Vector3::new(3.0, 1.5, 0.0),
+                             Vector3::new(1.0, 3.0, 0.0), Vector3::new(-1.0, 1.5, 0.0), Vector3::new(1.0, -2.0, 0.0)];
+        let faces = vec![vec![0, 1, 2, 3, 4], vec![1, 0, 5]];
+        let (p, q) = catmull_clark_subdivide(&positions, &faces, &[], 1);
+        assert_eq!(q.len(), 8);
+        assert_eq!(p.len(), 6 + 7 + 2);
+        assert!(q.iter().all(|quad| (0..4).all(|i| quad[i] != quad[(i + 1) % 4])));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_catmull_clark_rejects_small_faces() {
+        catmull_clark_subdivide(&[Vector3::zero(), Vector3::left()], &[vec![0, 1]], &[], 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_catmull_clark_rejects_zero_levels() {
+        let (positions, faces) = cube();
+        catmull_clark_subdivide(&positions, &faces, &[], 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_loop_subdivide_rejects_repeated_vertices() {
+        loop_subdivide(&[Vector3::zero(), Vector3::left()], &[[0, 1, 0]], &[], 1);
+    }
+
+    #[test]
+    fn test_catmull_clark_creases() {
+        let (positions, faces) = cube();
+        let creases: Vec<(usize, usize)> = faces.iter()
+            .flat_map(|f| (0..4).map(move |i| (f[i], f[(i + 1) % 4])))
+            .collect();
+
+        // With every edge creased the cube keeps its corners and every point stays on its faces.
+        let (p, _) = catmull_clark_subdivide(&positions, &faces, &creases, 2);
+        assert_eq!(p[7].x, 1.0);
+        assert_eq!(p[7].y, 1.0);
+        assert_eq!(p[7].z, 1.0);
+        for v in &p {
+            assert!((v.x.abs().max(v.y.abs()).max(v.z.abs()) - 1.0).abs() < 0.0001);
+        }
+
+        // A single creased ring keeps the vertices on it in the plane z = 1.
+        let ring = [(4, 5), (5, 7), (7, 6), (6, 4)];
+        let (p, _) = catmull_clark_subdivide(&positions, &faces, &ring, 1);
+        assert_eq!(p[4].z, 1.0);
+    }
+}
@@ -0,0 +1,277 @@
+use vector3::Vector3;
+use ray::Ray;
+use plane::Plane;
+use aabb::Aabb;
+use triangle::{Triangle, Barycentric};
+use capsule::Capsule;
+use distance::point_triangle_distance;
+
+/// The first contact of a moving shape during a sweep.
+#[derive(Debug, Copy, Clone)]
+pub struct TimeOfImpact {
+    /// The time of first contact in `[0, 1]`, where 0 is the start of the motion and 1 is the end.
+    pub time: f32,
+    /// The unit surface normal of the obstacle at the contact, facing the moving shape.
+    pub normal: Vector3,
+}
+
+/// Returns the normal from `surface` towards `center`, or `fallback` if they are the same point.
+fn contact_normal(center: Vector3, surface: Vector3, fallback: Vector3) -> Vector3 {
+    let d = center - surface;
+    let l = d.length();
+    if l <= f32::EPSILON {
+        return fallback;
+    }
+
+    d / l
+}
+
+/// Returns the face normal of `aabb` along the axis where `point` is closest to leaving it.
+fn least_penetration_normal(point: Vector3, aabb: &Aabb) -> Vector3 {
+    let mut best = (f32::INFINITY, Vector3::zero());
+    for axis in 0..3 {
+        let mut normal = Vector3::zero();
+        let below = point[axis] - aabb.min[axis];
+        let above = aabb.max[axis] - point[axis];
+        normal[axis] = if below < above { -1.0 } else { 1.0 };
+        if below.min(above) < best.0 {
+            best = (below.min(above), normal);
+        }
+    }
+    best.1
+}
+
+/// Returns the normal of a sphere centered inside `aabb`, against its motion or out of the nearest face when it is still.
+fn sphere_aabb_fallback(center: Vector3, velocity: Vector3, aabb: &Aabb) -> Vector3 {
+    if velocity.length_squared() > 0.0 {
+        -velocity.normalized()
+    } else {
+        least_penetration_normal(center, aabb)
+    }
+}
+
+/// Returns the earlier of two optional times.
+fn earliest(a: Option<f32>, b: Option<f32>) -> Option<f32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+/// Returns the first contact of a sphere at `center` with `radius` moving by `velocity` against `plane`.
+/// Returns `None` if the sphere does not touch the plane during the motion.
+pub fn sweep_sphere_plane(center: Vector3, radius: f32, velocity: Vector3, plane: &Plane) -> Option<TimeOfImpact> {
+    let d = plane.distance(center);
+    let side = if d >= 0.0 { 1.0 } else { -1.0 };
+    let normal = plane.normal * side;
+
+    if d.abs() <= radius {
+        return Some(TimeOfImpact { time: 0.0, normal });
+    }
+
+    let denom = Vector3::dot(plane.normal, velocity);
+    if denom * d >= 0.0 {
+        return None;
+    }
+
+    let time = (radius * side - d) / denom;
+    if time > 1.0 {
+        return None;
+    }
+
+    Some(TimeOfImpact { time, normal })
+}
+
+/// Returns the first contact of a sphere at `center` with `radius` moving by `velocity` against `triangle`.
+/// Returns `None` if the sphere does not touch the triangle during the motion.
+pub fn sweep_sphere_triangle(center: Vector3, radius: f32, velocity: Vector3, triangle: &Triangle) -> Option<TimeOfImpact> {
+    let closest_at = |time: f32| {
+        point_triangle_distance(center + velocity * time, triangle.v0, triangle.v1, triangle.v2).point
+    };
+
+    let n = triangle.normal();
+    let start = point_triangle_distance(center, triangle.v0, triangle.v1, triangle.v2);
+    if start.distance <= radius {
+        return Some(TimeOfImpact { time: 0.0, normal: contact_normal(center, start.point, n) });
+    }
+
+    // The swept volume is the triangle thickened by the radius plus capsules around its edges.
+    let mut time = None;
+    if n.length_squared() > 0.0 {
+        let plane = Plane::from_point_normal(triangle.v0, n);
+        if let Some(hit) = sweep_sphere_plane(center, radius, velocity, &plane) {
+            let contact = center + velocity * hit.time - hit.normal * radius;
+            if let Barycentric::Inside(_) = triangle.barycentric(contact) {
+                time = Some(hit.time);
+            }
+        }
+    }
+
+    let ray = Ray::new(center, velocity, 0.0);
+    for &(a, b) in [(triangle.v0, triangle.v1), (triangle.v1, triangle.v2), (triangle.v2, triangle.v0)].iter() {
+        time = earliest(time, Capsule::new(a, b, radius).intersect_ray(&ray).filter(|t| *t <= 1.0));
+    }
+
+    time.map(|time| TimeOfImpact { time, normal: contact_normal(center + velocity * time, closest_at(time), n) })
+}
+
+/// Returns the first contact of a sphere at `center` with `radius` moving by `velocity` against `aabb`.
+/// Returns `None` if the sphere does not touch the box during the motion.
+pub fn sweep_sphere_aabb(center: Vector3, radius: f32, velocity: Vector3, aabb: &Aabb) -> Option<TimeOfImpact> {
+    let start = aabb.closest_point(center);
+    if (center - start).length() <= radius {
+        return Some(TimeOfImpact { time: 0.0, normal: contact_normal(center, start, sphere_aabb_fallback(center, velocity, aabb)) });
+    }
+
+    // The swept volume is the box grown by the radius along each axis in turn plus capsules around its edges.
+    let ray = Ray::new(center, velocity, 0.0);
+    let mut time = None;
+    for axis in 0..3 {
+        let mut grown = *aabb;
+        grown.min[axis] -= radius;
+        grown.max[axis] += radius;
+        time = earliest(time, grown.intersect_ray_range(&ray, 0.0, 1.0).map(|(t, _)| t));
+    }
+
+    let corners = aabb.corners();
+    let edges = [(0, 1), (1, 2), (2, 3), (3, 0), (4, 5), (5, 6), (6, 7), (7, 4), (0, 4), (1, 5), (2, 6), (3, 7)];
+    for &(i, j) in edges.iter() {
+        time = earliest(time, Capsule::new(corners[i], corners[j], radius).intersect_ray(&ray).filter(|t| *t <= 1.0));
+    }
+
+    time.map(|time| {
+        let moved = center + velocity * time;
+        TimeOfImpact { time, normal: contact_normal(moved, aabb.closest_point(moved), sphere_aabb_fallback(moved, velocity, aabb)) }
+    })
+}
+
+/// Returns the first contact of `a` moving by `velocity_a` against `b` moving by `velocity_b`.
+/// The normal is the face normal of `b` that `a` touches.
+/// Returns `None` if the boxes do not touch during the motion.
+pub fn sweep_aabb_aabb(a: &Aabb, velocity_a: Vector3, b: &Aabb, velocity_b: Vector3) -> Option<TimeOfImpact> {
+    let velocity = velocity_a - velocity_b;
+    let extents = a.half_extents();
+    let grown = Aabb::new(b.min - extents, b.max + extents);
+    let origin = a.center();
+
+    if grown.contains_point(origin) {
+        // Already overlapping, so push out along the axis of least penetration.
+        return Some(TimeOfImpact { time: 0.0, normal: least_penetration_normal(origin, &grown) });
+    }
+
+    let mut t_enter = 0.0f32;
+    let mut t_exit = 1.0f32;
+    let mut normal = Vector3::zero();
+    for axis in 0..3 {
+        if velocity[axis] == 0.0 {
+            if origin[axis] < grown.min[axis] || origin[axis] > grown.max[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let inv = 1.0 / velocity[axis];
+        let mut t0 = (grown.min[axis] - origin[axis]) * inv;
+        let mut t1 = (grown.max[axis] - origin[axis]) * inv;
+        if inv < 0.0 {
+            std::mem::swap(&mut t0, &mut t1);
+        }
+
+        if t0 > t_enter {
+            t_enter = t0;
+            normal = Vector3::zero();
+            normal[axis] = if velocity[axis] > 0.0 { -1.0 } else { 1.0 };
+        }
+        t_exit = t_exit.min(t1);
+        if t_exit < t_enter {
+            return None;
+        }
+    }
+
+    Some(TimeOfImpact { time: t_enter, normal })
+}
+
+#[cfg(test)]
+mod tests {
+    use sweep::*;
+    use vector3::Vector3;
+    use plane::Plane;
+    use aabb::Aabb;
+    use triangle::Triangle;
+
+    fn assert_near(a: Vector3, b: Vector3) {
+        assert!((a - b).length() < 0.0001, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_sweep_sphere_plane() {
+        let plane = Plane::from_point_normal(Vector3::zero(), Vector3::up());
+
+        let hit = sweep_sphere_plane(Vector3::new(0.0, 5.0, 0.0), 1.0, Vector3::new(0.0, -8.0, 0.0), &plane).unwrap();
+        assert!((hit.time - 0.5).abs() < 0.0001);
+        assert_near(hit.normal, Vector3::up());
+
+        assert!(sweep_sphere_plane(Vector3::new(0.0, 5.0, 0.0), 1.0, Vector3::new(0.0, -2.0, 0.0), &plane).is_none());
+        assert!(sweep_sphere_plane(Vector3::new(0.0, 5.0, 0.0), 1.0, Vector3::new(0.0, 8.0, 0.0), &plane).is_none());
+
+        let hit = sweep_sphere_plane(Vector3::new(0.0, -5.0, 0.0), 1.0, Vector3::new(0.0, 8.0, 0.0), &plane).unwrap();
+        assert_near(hit.normal, -Vector3::up());
+    }
+
+    #[test]
+    fn test_sweep_sphere_triangle() {
+        let t = Triangle::new(Vector3::new(-1.0, 0.0, -1.0), Vector3::new(0.0, 0.0, 1.0), Vector3::new(1.0, 0.0, -1.0));
+
+        // Through the face.
+        let hit = sweep_sphere_triangle(Vector3::new(0.0, 3.0, 0.0), 0.5, Vector3::new(0.0, -5.0, 0.0), &t).unwrap();
+        assert!((hit.time - 0.5).abs() < 0.0001);
+        assert_near(hit.normal, Vector3::up());
+
+        // Edge on into the vertex at (0, 0, 1).
+        let hit = sweep_sphere_triangle(Vector3::new(0.0, 0.0, 4.0), 0.5, Vector3::new(0.0, 0.0, -6.0), &t).unwrap();
+        assert!((hit.time - 2.5 / 6.0).abs() < 0.0001);
+        assert_near(hit.normal, Vector3::forward());
+
+        // Passing beside the triangle.
+        assert!(sweep_sphere_triangle(Vector3::new(0.0, 3.0, 3.0), 0.5, Vector3::new(0.0, -5.0, 0.0), &t).is_none());
+    }
+
+    #[test]
+    fn test_sweep_sphere_aabb() {
+        let b = Aabb::new(Vector3::zero(), Vector3::new(1.0, 1.0, 1.0));
+
+        let hit = sweep_sphere_aabb(Vector3::new(-2.0, 0.5, 0.5), 0.5, Vector3::new(4.0, 0.0, 0.0), &b).unwrap();
+        assert!((hit.time - 0.375).abs() < 0.0001);
+        assert_near(hit.normal, -Vector3::left());
+
+        // Clipping the edge at x = 0, y = 1.
+        let hit = sweep_sphere_aabb(Vector3::new(-2.0, 1.3, 0.5), 0.5, Vector3::new(4.0, 0.0, 0.0), &b).unwrap();
+        assert!((hit.time - (2.0 - 0.4) / 4.0).abs() < 0.0001);
+        assert_near(hit.normal, Vector3::new(-0.8, 0.6, 0.0));
+
+        // The corner region of the grown box that a sphere does not reach.
+        assert!(sweep_sphere_aabb(Vector3::new(-2.0, 1.45, 1.45), 0.5, Vector3::new(4.0, 0.0, 0.0), &b).is_none());
+
+        // A still sphere centered inside the box is pushed out of the nearest face.
+        let hit = sweep_sphere_aabb(Vector3::new(0.5, 0.9, 0.4), 0.25, Vector3::zero(), &b).unwrap();
+        assert_eq!(hit.time, 0.0);
+        assert_near(hit.normal, Vector3::up());
+    }
+
+    #[test]
+    fn test_sweep_aabb_aabb() {
+        let a = Aabb::new(Vector3::zero(), Vector3::new(1.0, 1.0, 1.0));
+        let b = Aabb::new(Vector3::new(3.0, 0.0, 0.0), Vector3::new(4.0, 1.0, 1.0));
+
+        let hit = sweep_aabb_aabb(&a, Vector3::new(4.0, 0.0, 0.0), &b, Vector3::zero()).unwrap();
+        assert!((hit.time - 0.5).abs() < 0.0001);
+        assert_near(hit.normal, -Vector3::left());
+
+        let hit = sweep_aabb_aabb(&a, Vector3::new(2.0, 0.0, 0.0), &b, Vector3::new(-2.0, 0.0, 0.0)).unwrap();
+        assert!((hit.time - 0.5).abs() < 0.0001);
+
+        assert!(sweep_aabb_aabb(&a, Vector3::new(1.0, 0.0, 0.0), &b, Vector3::zero()).is_none());
+        assert!(sweep_aabb_aabb(&a, Vector3::new(4.0, 3.0, 0.0), &b, Vector3::zero()).is_none());
+    }
+}
@@ -0,0 +1,279 @@
+extern crate rand;
+
+use self::rand::Rng;
+use vector3::Vector3;
+use ray::Ray;
+
+/// A triangle defined by 3 vertices.
+#[derive(Debug, Copy, Clone)]
+pub struct Triangle {
+    pub v0: Vector3,
+    pub v1: Vector3,
+    pub v2: Vector3,
+}
+
+/// The result of a barycentric coordinate query against a `Triangle`.
+#[derive(Debug, Copy, Clone)]
+pub enum Barycentric {
+    /// The triangle has no area so the coordinates are undefined.
+    Degenerate,
+    /// The point projects onto the triangle's plane inside the triangle.
+    Inside(Vector3),
+    /// The point projects onto the triangle's plane outside the triangle.
+    Outside(Vector3),
+}
+
+/// A hit between a `Ray` and a `Triangle`.
+#[derive(Debug, Copy, Clone)]
+pub struct TriangleHit {
+    /// The time along the ray of the hit.
+    pub t: f32,
+    /// The barycentric coordinates of the hit for the triangle's vertices.
+    pub barycentric: Vector3,
+}
+
+impl Triangle {
+    /// Returns a new `Triangle`.
+    pub fn new(v0: Vector3, v1: Vector3, v2: Vector3) -> Triangle {
+        Triangle { v0, v1, v2 }
+    }
+
+    /// Returns the vertex at `index`.
+    pub fn vertex(&self, index: usize) -> Vector3 {
+        match index {
+            0 => self.v0,
+            1 => self.v1,
+            2 => self.v2,
+            _ => panic!("Invalid Triangle vertex index"),
+        }
+    }
+
+    /// Returns the cross product of the triangle's edges, which has a length of twice the area.
+    pub fn scaled_normal(&self) -> Vector3 {
+        Vector3::cross(self.v1 - self.v0, self.v2 - self.v0)
+    }
+
+    /// Returns the unit normal of the triangle facing the side its vertices appear counter-clockwise from.
+    /// Returns a zero vector if the triangle is degenerate.
+    pub fn normal(&self) -> Vector3 {
+        let n = self.scaled_normal();
+        let l = n.length();
+        if l == 0.0 {
+            return Vector3::zero();
+        }
+
+        n / l
+    }
+
+    /// Returns the area of the triangle.
+    pub fn area(&self) -> f32 {
+        self.scaled_normal().length() * 0.5
+    }
+
+    /// Returns the average of the triangle's vertices.
+    pub fn centroid(&self) -> Vector3 {
+        (self.v0 + self.v1 + self.v2) / 3.0
+    }
+
+    /// Returns true if the triangle has no area relative to the length of its edges.
+    pub fn is_degenerate(&self) -> bool {
+        let longest = (self.v1 - self.v0).length_squared()
+            .max((self.v2 - self.v1).length_squared())
+            .max((self.v0 - self.v2).length_squared());
+        self.scaled_normal().length() <= f32::EPSILON * longest
+    }
+
+    /// Returns the barycentric coordinates of `point` projected onto the triangle's plane.
+    pub fn barycentric(&self, point: Vector3) -> Barycentric {
+        if self.is_degenerate() {
+            return Barycentric::Degenerate;
+        }
+
+        let n = self.scaled_normal();
+        let inv = 1.0 / n.length_squared();
+        let u = Vector3::dot(Vector3::cross(self.v2 - self.v1, point - self.v1), n) * inv;
+        let v = Vector3::dot(Vector3::cross(self.v0 - self.v2, point - self.v2), n) * inv;
+        let coordinates = Vector3::new(u, v, 1.0 - u - v);
+
+        if coordinates.x >= 0.0 && coordinates.y >= 0.0 && coordinates.z >= 0.0 {
+            Barycentric::Inside(coordinates)
+        } else {
+            Barycentric::Outside(coordinates)
+        }
+    }
+
+    /// Returns true if `point` is within `epsilon` of the triangle's plane and projects inside the triangle.
+    pub fn contains_point(&self, point: Vector3, epsilon: f32) -> bool {
+        let distance = Vector3::dot(point - self.v0, self.normal()).abs();
+        match self.barycentric(point) {
+            Barycentric::Inside(_) => distance <= epsilon,
+            _ => false,
+        }
+    }
+
+    /// Returns the point with the barycentric coordinates `barycentric`.
+    pub fn point_at(&self, barycentric: Vector3) -> Vector3 {
+        self.v0 * barycentric.x + self.v1 * barycentric.y + self.v2 * barycentric.z
+    }
+
+    /// Returns a point on the triangle for the uniform random numbers `u` and `v` in `[0, 1]`.
+    /// Uniformly distributed `u` and `v` produce points uniformly distributed over the triangle's area.
+    pub fn sample(&self, u: f32, v: f32) -> Vector3 {
+        let su = u.sqrt();
+        self.point_at(Vector3::new(1.0 - su, su * (1.0 - v), su * v))
+    }
+
+    /// Returns a random point uniformly distributed over the triangle's area.
+    pub fn random_point(&self) -> Vector3 {
+        let mut rng = rand::thread_rng();
+        self.sample(rng.gen::<f32>(), rng.gen::<f32>())
+    }
+
+    /// Returns the hit between `ray` and the triangle from either side.
+    /// Rays through a shared edge or vertex of adjacent triangles always hit at least one of them.
+    /// Returns `None` if the ray misses the triangle or the triangle is behind the ray.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<TriangleHit> {
+        let direction = ray.direction();
+        let abs = direction.abs();
+
+        // Shear the ray onto the z axis with the largest direction component.
+        let kz = if abs.x > abs.y { if abs.x > abs.z { 0 } else { 2 } } else if abs.y > abs.z { 1 } else { 2 };
+        let mut kx = (kz + 1) % 3;
+        let mut ky = (kx + 1) % 3;
+        if direction[kz] < 0.0 {
+            std::mem::swap(&mut kx, &mut ky);
+        }
+        if direction[kz] == 0.0 {
+            return None;
+        }
+
+        let sx = direction[kx] / direction[kz];
+        let sy = direction[ky] / direction[kz];
+        let sz = 1.0 / direction[kz];
+
+        let a = self.v0 - ray.origin();
+        let b = self.v1 - ray.origin();
+        let c = self.v2 - ray.origin();
+
+        let ax = a[kx] - sx * a[kz];
+        let ay = a[ky] - sy * a[kz];
+        let bx = b[kx] - sx * b[kz];
+        let by = b[ky] - sy * b[kz];
+        let cx = c[kx] - sx * c[kz];
+        let cy = c[ky] - sy * c[kz];
+
+        let mut u = cx * by - cy * bx;
+        let mut v = ax * cy - ay * cx;
+        let mut w = bx * ay - by * ax;
+
+        // Edges exactly through the ray need double precision to pick a consistent side.
+        if u == 0.0 || v == 0.0 || w == 0.0 {
+            u = (cx as f64 * by as f64 - cy as f64 * bx as f64) as f32;
+            v = (ax as f64 * cy as f64 - ay as f64 * cx as f64) as f32;
+            w = (bx as f64 * ay as f64 - by as f64 * ax as f64) as f32;
+        }
+
+        if (u < 0.0 || v < 0.0 || w < 0.0) && (u > 0.0 || v > 0.0 || w > 0.0) {
+            return None;
+        }
+
+        let det = u + v + w;
+        if det == 0.0 {
+            return None;
+        }
+
+        let t_scaled = u * sz * a[kz] + v * sz * b[kz] + w * sz * c[kz];
+        if (det < 0.0 && t_scaled > 0.0) || (det > 0.0 && t_scaled < 0.0) {
+            return None;
+        }
+
+        let inv = 1.0 / det;
+        Some(TriangleHit { t: t_scaled * inv, barycentric: Vector3::new(u * inv, v * inv, w * inv) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use triangle::{Triangle, Barycentric};
+    use vector3::Vector3;
+    use ray::Ray;
+
+    fn triangle() -> Triangle {
+        Triangle::new(Vector3::zero(), Vector3::new(2.0, 0.0, 0.0), Vector3::new(0.0, 2.0, 0.0))
+    }
+
+    #[test]
+    fn test_triangle_properties() {
+        let t = triangle();
+
+        assert_eq!(t.area(), 2.0);
+        assert!((t.normal() - Vector3::forward()).length() < 0.0001);
+        assert!((t.centroid() - Vector3::new(2.0 / 3.0, 2.0 / 3.0, 0.0)).length() < 0.0001);
+    }
+
+    #[test]
+    fn test_triangle_barycentric() {
+        let t = triangle();
+
+        match t.barycentric(Vector3::new(0.5, 0.5, 4.0)) {
+            Barycentric::Inside(b) => assert!((b - Vector3::new(0.5, 0.25, 0.25)).length() < 0.0001),
+            _ => panic!("expected inside"),
+        }
+        match t.barycentric(Vector3::new(2.0, 2.0, 0.0)) {
+            Barycentric::Outside(b) => assert!(b.x < 0.0),
+            _ => panic!("expected outside"),
+        }
+
+        let degenerate = Triangle::new(Vector3::zero(), Vector3::left(), Vector3::left() * 2.0);
+        match degenerate.barycentric(Vector3::zero()) {
+            Barycentric::Degenerate => {},
+            _ => panic!("expected degenerate"),
+        }
+
+        assert!(t.contains_point(Vector3::new(0.5, 0.5, 0.0), 0.001));
+        assert!(!t.contains_point(Vector3::new(0.5, 0.5, 0.1), 0.001));
+    }
+
+    #[test]
+    fn test_triangle_sample() {
+        let t = triangle();
+        for i in 0..10 {
+            for j in 0..10 {
+                let p = t.sample(i as f32 / 9.0, j as f32 / 9.0);
+                assert!(p.z == 0.0 && p.x >= 0.0 && p.y >= 0.0 && p.x + p.y <= 2.0001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_triangle_intersect_ray() {
+        let t = triangle();
+        let hit = t.intersect_ray(&Ray::new(Vector3::new(0.5, 0.5, 3.0), -Vector3::forward(), 0.0)).unwrap();
+
+        assert!((hit.t - 3.0).abs() < 0.0001);
+        assert!((hit.barycentric - Vector3::new(0.5, 0.25, 0.25)).length() < 0.0001);
+        assert!(t.intersect_ray(&Ray::new(Vector3::new(0.5, 0.5, 3.0), Vector3::forward(), 0.0)).is_none());
+        assert!(t.intersect_ray(&Ray::new(Vector3::new(1.5, 1.5, 3.0), -Vector3::forward(), 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_triangle_intersect_ray_watertight() {
+        let (a, b, c, d) = (Vector3::zero(), Vector3::new(1.0, 0.0, 0.0),
+                            Vector3::new(1.0, 1.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+        let t0 = Triangle::new(a, b, c);
+        let t1 = Triangle::new(a, c, d);
+
+        // Rays through the shared diagonal must hit at least one of the triangles.
+        for i in 1..100 {
+            let f = i as f32 / 100.0;
+            let r = Ray::new(Vector3::new(f, f, 1.0), Vector3::new(0.01, -0.003, -1.0), 0.0);
+            let p = r.point_at_parameter(1.0);
+            if p.x > 0.0 && p.x < 1.0 && p.y > 0.0 && p.y < 1.0 {
+                assert!(t0.intersect_ray(&r).is_some() || t1.intersect_ray(&r).is_some());
+            }
+
+            let r = Ray::new(Vector3::new(f, f, 1.0), -Vector3::forward(), 0.0);
+            assert!(t0.intersect_ray(&r).is_some() || t1.intersect_ray(&r).is_some());
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::ops::{Add, Sub, Mul, Div, Neg, Index, IndexMut};
+
+/// A 2 axis vector of `f32` values.
+#[derive(Debug, Copy, Clone)]
+pub struct Vector2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vector2 {
+    /// Returns a new `Vector2`.
+    pub fn new(x: f32, y: f32) -> Vector2 {
+        Vector2 { x, y }
+    }
+
+    /// Returns a `Vector2` with all values set to 0.
+    pub fn zero() -> Vector2 {
+        Vector2 { x: 0.0, y: 0.0 }
+    }
+
+    /// Returns the dot product of `v0` and `v1`.
+    pub fn dot(v0: Vector2, v1: Vector2) -> f32 {
+        v0.x * v1.x + v0.y * v1.y
+    }
+
+    /// Returns the z value of the cross product of `v0` and `v1` extended to 3 axes.
+    pub fn cross(v0: Vector2, v1: Vector2) -> f32 {
+        v0.x * v1.y - v0.y * v1.x
+    }
+
+    /// Returns the squared length of the vector.
+    pub fn length_squared(&self) -> f32 {
+        self.x * self.x + self.y * self.y
+    }
+
+    /// Returns the length of the vector.
+    pub fn length(&self) -> f32 {
+        self.length_squared().sqrt()
+    }
+}
+
+impl Add for Vector2 {
+    type Output = Vector2;
+
+    fn add(self, other: Vector2) -> Vector2 {
+        Vector2 { x: self.x + other.x,
+                  y: self.y + other.y }
+    }
+}
+
+impl Sub for Vector2 {
+    type Output = Vector2;
+
+    fn sub(self, other: Vector2) -> Vector2 {
+        Vector2 { x: self.x - other.x,
+                  y: self.y - other.y }
+    }
+}
+
+impl Mul<f32> for Vector2 {
+    type Output = Vector2;
+
+    fn mul(self, other: f32) -> Vector2 {
+        Vector2 { x: self.x * other,
+                  y: self.y * other }
+    }
+}
+
+impl Div<f32> for Vector2 {
+    type Output = Vector2;
+
+    fn div(self, other: f32) -> Vector2 {
+        Vector2 { x: self.x / other,
+                  y: self.y / other }
+    }
+}
+
+impl Neg for Vector2 {
+    type Output = Vector2;
+
+    fn neg(self) -> Vector2 {
+        Vector2 { x: -self.x,
+                  y: -self.y }
+    }
+}
+
+impl Index<usize> for Vector2 {
+    type Output = f32;
+
+    fn index(&self, index: usize) -> &f32 {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("Invalid Vector2 index"),
+        }
+    }
+}
+
+impl IndexMut<usize> for Vector2 {
+    fn index_mut(&mut self, index: usize) -> &mut f32 {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("Invalid Vector2 index"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector2::Vector2;
+
+    #[test]
+    fn test_vector2_operations() {
+        let a = Vector2::new(3.0, 4.0);
+        let b = Vector2::new(1.0, -2.0);
+
+        assert_eq!(a.length(), 5.0);
+        assert_eq!(Vector2::dot(a, b), -5.0);
+        assert_eq!(Vector2::cross(a, b), -10.0);
+        assert_eq!((a - b).y, 6.0);
+    }
+}
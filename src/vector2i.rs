@@ -3,7 +3,7 @@ use std::cmp::{min, max};
 use std::ops::{Add, Sub, Mul, Div, Neg, Index, IndexMut};
 
 /// A 2 axis vector of `i32` values.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Vector2i {
     pub x: i32,
     pub y: i32,
@@ -15,15 +15,15 @@ impl Vector2i {
         Vector2i { x, y }
     }
 
-    /// Returns the barycentric coordinates for `point` inside a triangle defined by `v0`, `v1`, `v2`.
-    /// Returns `None` if the point is outside the triangle.
+    /// Returns the barycentric coordinates for `point` inside a triangle defined by `v0`, `v1`, `v2` in either winding.
+    /// Returns `None` if the triangle is degenerate.
     pub fn barycentric(point: Vector2i, v0: Vector2i, v1: Vector2i, v2: Vector2i) -> Option<Vector3> {
         let x = Vector3::new((v2.x - v0.x) as f32, (v1.x - v0.x) as f32, (v0.x - point.x) as f32);
         let y = Vector3::new((v2.y - v0.y) as f32, (v1.y - v0.y) as f32, (v0.y - point.y) as f32);
 
         let u = Vector3::cross(x, y);
 
-        if u.z < 1.0 {
+        if u.z.abs() < 1.0 {
             return None;
         }
 
@@ -50,6 +50,25 @@ impl Vector3 {
                  z: (v0.x * v1.y) - (v0.y * v1.x)}
     }
 
+    /// Returns a `Vector3` of the smallest components of `v0` and `v1`.
+    pub fn min(v0: Vector3, v1: Vector3) -> Vector3 {
+        Vector3 {x: v0.x.min(v1.x),
+                 y: v0.y.min(v1.y),
+                 z: v0.z.min(v1.z)}
+    }
+
+    /// Returns a `Vector3` of the largest components of `v0` and `v1`.
+    pub fn max(v0: Vector3, v1: Vector3) -> Vector3 {
+        Vector3 {x: v0.x.max(v1.x),
+                 y: v0.y.max(v1.y),
+                 z: v0.z.max(v1.z)}
+    }
+
+    /// Returns a `Vector3` with the absolute value of each component.
+    pub fn abs(&self) -> Vector3 {
+        Vector3::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+
     /// Returns the length of the vector before taking the square root.
     pub fn length_squared(&self) -> f32 {
         self.x * self.x + self.y * self.y + self.z * self.z
@@ -67,6 +86,7 @@ impl Vector3 {
     }
 
     /// Returns the barycentric coordinates for `point` inside a triangle defined by `v0`, `v1`, `v2`.
+    /// Returns `None` if the triangle is degenerate. See `Triangle::barycentric` for containment.
     pub fn barycentric(point: Vector3, v0: Vector3, v1: Vector3, v2: Vector3) -> Option<Vector3> {
         let vec0 = v1 - v0;
         let vec1 = v2 - v0;
@@ -77,6 +97,9 @@ impl Vector3 {
         let d20 = Vector3::dot(vec2, vec0);
         let d21 = Vector3::dot(vec2, vec1);
         let denom = d00 * d11 - d01 * d01;
+        if denom.abs() <= f32::EPSILON * d00 * d11 {
+            return None;
+        }
 
         let v = (d11 * d20 - d01 * d21) / denom;
         let w = (d00 * d21 - d01 * d20) / denom;
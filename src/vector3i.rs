@@ -0,0 +1,149 @@
+use vector3::Vector3;
+use std::ops::{Add, Sub, Mul, Div, Neg, Index, IndexMut};
+
+/// A 3 axis vector of `i32` values.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Vector3i {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Vector3i {
+    /// Returns a new `Vector3i`.
+    pub fn new(x: i32, y: i32, z: i32) -> Vector3i {
+        Vector3i { x, y, z }
+    }
+
+    /// Returns a `Vector3i` with all values set to 0.
+    pub fn zero() -> Vector3i {
+        Vector3i { x: 0, y: 0, z: 0 }
+    }
+
+    /// Returns the vector containing `v` rounded down on each axis.
+    pub fn floor(v: Vector3) -> Vector3i {
+        Vector3i::new(v.x.floor() as i32, v.y.floor() as i32, v.z.floor() as i32)
+    }
+
+    /// Returns the vector as a `Vector3`.
+    pub fn to_vector3(&self) -> Vector3 {
+        Vector3::new(self.x as f32, self.y as f32, self.z as f32)
+    }
+}
+
+impl Add for Vector3i {
+    type Output = Vector3i;
+
+    fn add(self, other: Vector3i) -> Vector3i {
+        Vector3i { x: self.x + other.x,
+                   y: self.y + other.y,
+                   z: self.z + other.z }
+    }
+}
+
+impl Sub for Vector3i {
+    type Output = Vector3i;
+
+    fn sub(self, other: Vector3i) -> Vector3i {
+        Vector3i { x: self.x - other.x,
+                   y: self.y - other.y,
+                   z: self.z - other.z }
+    }
+}
+
+impl Mul<i32> for Vector3i {
+    type Output = Vector3i;
+
+    fn mul(self, other: i32) -> Vector3i {
+        Vector3i { x: self.x * other,
+                   y: self.y * other,
+                   z: self.z * other }
+    }
+}
+
+impl Mul<Vector3i> for i32 {
+    type Output = Vector3i;
+
+    fn mul(self, other: Vector3i) -> Vector3i {
+        Vector3i { x: self * other.x,
+                   y: self * other.y,
+                   z: self * other.z }
+    }
+}
+
+impl Div<i32> for Vector3i {
+    type Output = Vector3i;
+
+    fn div(self, other: i32) -> Vector3i {
+        Vector3i { x: self.x / other,
+                   y: self.y / other,
+                   z: self.z / other }
+    }
+}
+
+impl Neg for Vector3i {
+    type Output = Vector3i;
+
+    fn neg(self) -> Vector3i {
+        Vector3i { x: -self.x,
+                   y: -self.y,
+                   z: -self.z }
+    }
+}
+
+impl Index<usize> for Vector3i {
+    type Output = i32;
+
+    fn index(&self, index: usize) -> &i32 {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Invalid Vector3i index"),
+        }
+    }
+}
+
+impl IndexMut<usize> for Vector3i {
+    fn index_mut(&mut self, index: usize) -> &mut i32 {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("Invalid Vector3i index"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector3::Vector3;
+    use vector3i::Vector3i;
+
+    #[test]
+    fn test_vector3i_ops() {
+        let a = Vector3i::new(0, 5, -2);
+        let b = Vector3i::new(-6, 4, 3);
+        assert_eq!(a + b, Vector3i::new(-6, 9, 1));
+        assert_eq!(a - b, Vector3i::new(6, 1, -5));
+        assert_eq!(a * 3, Vector3i::new(0, 15, -6));
+        assert_eq!(2 * b, Vector3i::new(-12, 8, 6));
+        assert_eq!(b / 2, Vector3i::new(-3, 2, 1));
+        assert_eq!(-a, Vector3i::new(0, -5, 2));
+    }
+
+    #[test]
+    fn test_vector3i_floor() {
+        let v = Vector3i::floor(Vector3::new(1.5, -0.5, 3.0));
+        assert_eq!(v, Vector3i::new(1, -1, 3));
+        assert_eq!(v.to_vector3().y, -1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_vector3i_index_panic() {
+        let a = Vector3i::new(12, 0, 1);
+        assert_eq!(a[2], 1);
+        assert_eq!(a[3], 0);
+    }
+}
@@ -1,3 +1,4 @@
+use std::ops::{Add, Sub, Mul, Neg};
 use super::Vector3;
 
 /// A 4 axis vector of `f32` values.
@@ -30,8 +31,54 @@ impl Vector4 {
         }
     }
 
+    /// Returns the dot product of `v0` and `v1`.
+    pub fn dot(v0: Vector4, v1: Vector4) -> f32 {
+        v0.x * v1.x + v0.y * v1.y + v0.z * v1.z + v0.w * v1.w
+    }
+
     /// Returns the `x`, `y` and `z` values as a `Vector3`.
     pub fn xyz(&self) -> Vector3 {
         Vector3::new(self.x, self.y, self.z)
     }
+}
+
+impl Add for Vector4 {
+    type Output = Vector4;
+
+    fn add(self, other: Vector4) -> Vector4 {
+        Vector4 {x: self.x + other.x,
+                 y: self.y + other.y,
+                 z: self.z + other.z,
+                 w: self.w + other.w}
+    }
+}
+
+impl Sub for Vector4 {
+    type Output = Vector4;
+
+    fn sub(self, other: Vector4) -> Vector4 {
+        Vector4 {x: self.x - other.x,
+                 y: self.y - other.y,
+                 z: self.z - other.z,
+                 w: self.w - other.w}
+    }
+}
+
+impl Mul<f32> for Vector4 {
+    type Output = Vector4;
+
+    fn mul(self, other: f32) -> Vector4 {
+        Vector4 {x: self.x * other,
+                 y: self.y * other,
+                 z: self.z * other,
+                 w: self.w * other}
+    }
+}
+
+impl Neg for Vector4 {
+    type Output = Vector4;
+
+    fn neg(self) -> Vector4 {
+        Vector4 {x: -self.x, y: -self.y, z: -self.z, w: -self.w}
+    }
 }
\ No newline at end of file
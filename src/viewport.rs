@@ -0,0 +1,143 @@
+use vector2::Vector2;
+use vector2i::Vector2i;
+use vector3::Vector3;
+use matrix4::Matrix4;
+use frustum::ClipDepth;
+use ray::Ray;
+
+/// A rectangle of the screen that normalized device coordinates are mapped to, with y pointing down.
+#[derive(Debug, Copy, Clone)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub min_depth: f32,
+    pub max_depth: f32,
+    /// The normalized device depth range produced by the projection.
+    pub clip_depth: ClipDepth,
+}
+
+impl Viewport {
+    /// Returns a new `Viewport` with a depth range of 0 to 1 for projections from `Matrix4::perpective`.
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Viewport {
+        Viewport {
+            x,
+            y,
+            width,
+            height,
+            min_depth: 0.0,
+            max_depth: 1.0,
+            clip_depth: ClipDepth::ZeroToOne,
+        }
+    }
+
+    /// Returns the screen position and depth of the normalized device coordinates `ndc`.
+    pub fn ndc_to_screen(&self, ndc: Vector3) -> Vector3 {
+        let z = match self.clip_depth {
+            ClipDepth::ZeroToOne => ndc.z,
+            ClipDepth::NegativeOneToOne => (ndc.z + 1.0) * 0.5,
+        };
+
+        Vector3::new(self.x + (ndc.x + 1.0) * 0.5 * self.width,
+                     self.y + (1.0 - ndc.y) * 0.5 * self.height,
+                     self.min_depth + z * (self.max_depth - self.min_depth))
+    }
+
+    /// Returns the normalized device coordinates of the screen position and depth `screen`.
+    pub fn screen_to_ndc(&self, screen: Vector3) -> Vector3 {
+        let z = (screen.z - self.min_depth) / (self.max_depth - self.min_depth);
+
+        Vector3::new((screen.x - self.x) / self.width * 2.0 - 1.0,
+                     1.0 - (screen.y - self.y) / self.height * 2.0,
+                     match self.clip_depth {
+                         ClipDepth::ZeroToOne => z,
+                         ClipDepth::NegativeOneToOne => z * 2.0 - 1.0,
+                     })
+    }
+
+    /// Returns the pixel containing the screen position `screen`.
+    pub fn pixel(screen: Vector3) -> Vector2i {
+        Vector2i::new(screen.x.floor() as i32, screen.y.floor() as i32)
+    }
+
+    /// Returns the screen position and depth of the world space point `world`.
+    /// Returns `None` if the point is at or behind the eye.
+    pub fn project(&self, world: Vector3, view_proj: Matrix4) -> Option<Vector3> {
+        let clip = view_proj * world.to_vector4(1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+
+        Some(self.ndc_to_screen(clip.xyz() / clip.w))
+    }
+
+    /// Returns the world space ray through the screen position `screen`, starting at `depth` and pointing away from the eye.
+    /// `inv_view_proj` is the inverse of the view projection matrix.
+    pub fn unproject(&self, screen: Vector2, depth: f32, inv_view_proj: Matrix4) -> Ray {
+        let world = |z: f32| {
+            let ndc = self.screen_to_ndc(Vector3::new(screen.x, screen.y, z));
+            let p = inv_view_proj * ndc.to_vector4(1.0);
+            p.xyz() / p.w
+        };
+
+        let near = world(self.min_depth);
+        let far = world(self.max_depth);
+        Ray::new(world(depth), (far - near).normalized(), 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use viewport::Viewport;
+    use frustum::ClipDepth;
+    use matrix4::Matrix4;
+    use vector2::Vector2;
+    use vector3::Vector3;
+
+    fn assert_near(a: Vector3, b: Vector3) {
+        assert!((a - b).length() < 1e-3, "{:?} {:?}", a, b);
+    }
+
+    #[test]
+    fn test_viewport_ndc_round_trip() {
+        let mut viewport = Viewport::new(10.0, 20.0, 640.0, 480.0);
+        assert_near(viewport.ndc_to_screen(Vector3::new(-1.0, 1.0, 0.0)), Vector3::new(10.0, 20.0, 0.0));
+        assert_near(viewport.ndc_to_screen(Vector3::new(1.0, -1.0, 1.0)), Vector3::new(650.0, 500.0, 1.0));
+
+        viewport.clip_depth = ClipDepth::NegativeOneToOne;
+        viewport.min_depth = 0.25;
+        viewport.max_depth = 0.75;
+        let ndc = Vector3::new(0.3, -0.6, -0.2);
+        let screen = viewport.ndc_to_screen(ndc);
+        assert!((screen.z - 0.45).abs() < 1e-6);
+        assert_near(viewport.screen_to_ndc(screen), ndc);
+
+        let pixel = Viewport::pixel(Vector3::new(12.7, 3.2, 0.0));
+        assert_eq!((pixel.x, pixel.y), (12, 3));
+    }
+
+    #[test]
+    fn test_viewport_project_unproject() {
+        let viewport = Viewport::new(0.0, 0.0, 800.0, 600.0);
+        let view = Matrix4::translation(0.0, -2.0, -10.0);
+        let view_proj = Matrix4::perpective(60.0, 800.0 / 600.0, 0.1, 100.0) * view;
+        let inv_view_proj = view_proj.inverse().unwrap();
+
+        let world = Vector3::new(1.5, -0.5, 2.0);
+        let screen = viewport.project(world, view_proj).unwrap();
+        assert!(screen.x > 400.0 && screen.y > 300.0);
+
+        // Unprojecting at the projected depth returns the original point.
+        let ray = viewport.unproject(Vector2::new(screen.x, screen.y), screen.z, inv_view_proj);
+        assert_near(ray.origin(), world);
+
+        // A picking ray from the near plane passes through the point.
+        let ray = viewport.unproject(Vector2::new(screen.x, screen.y), 0.0, inv_view_proj);
+        let to_point = world - ray.origin();
+        assert!(Vector3::cross(ray.direction(), to_point).length() < 1e-3 * to_point.length());
+        assert!(Vector3::dot(ray.direction(), to_point) > 0.0);
+
+        assert!(viewport.project(Vector3::new(0.0, 2.0, 20.0), view_proj).is_none());
+    }
+}
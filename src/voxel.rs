@@ -0,0 +1,278 @@
+use vector3::Vector3;
+use vector3i::Vector3i;
+use aabb::Aabb;
+use ray::Ray;
+
+/// A uniform grid of cubic cells from `min` inclusive to `max` exclusive, with cell `(0, 0, 0)` starting at `origin`.
+#[derive(Debug, Copy, Clone)]
+pub struct VoxelGrid {
+    pub origin: Vector3,
+    pub cell_size: f32,
+    pub min: Vector3i,
+    pub max: Vector3i,
+}
+
+/// A grid cell visited by a ray.
+#[derive(Debug, Copy, Clone)]
+pub struct VoxelHit {
+    pub cell: Vector3i,
+    /// The ray parameter where the ray enters the cell.
+    pub t_enter: f32,
+    /// The ray parameter where the ray leaves the cell.
+    pub t_exit: f32,
+    /// The normal of the cell face the ray entered through, or zero if the ray starts inside the cell.
+    pub normal: Vector3,
+}
+
+impl VoxelGrid {
+    /// Returns a new `VoxelGrid`.
+    pub fn new(origin: Vector3, cell_size: f32, min: Vector3i, max: Vector3i) -> VoxelGrid {
+        VoxelGrid {
+            origin,
+            cell_size,
+            min,
+            max,
+        }
+    }
+
+    /// Returns the bounding box of the grid.
+    pub fn bounds(&self) -> Aabb {
+        Aabb::new(self.origin + self.min.to_vector3() * self.cell_size,
+                  self.origin + self.max.to_vector3() * self.cell_size)
+    }
+
+    /// Returns the cell containing `point`, which may be outside the grid bounds.
+    pub fn cell_at(&self, point: Vector3) -> Vector3i {
+        Vector3i::floor((point - self.origin) / self.cell_size)
+    }
+
+    /// Returns an iterator over the cells `ray` passes through in order, up to the ray parameter `t_max`,
+    /// using the Amanatides-Woo algorithm. A grid with no cells along some axis is never crossed.
+    pub fn traverse(&self, ray: &Ray, t_max: f32) -> VoxelTraversal {
+        if (0..3).any(|axis| self.max[axis] <= self.min[axis]) {
+            return VoxelTraversal::empty();
+        }
+
+        let origin = ray.origin();
+        let direction = ray.direction();
+        let bounds = self.bounds();
+
+        // Clip the ray to the grid bounds, remembering which face it enters through.
+        let mut t_enter = 0.0f32;
+        let mut t_end = t_max;
+        let mut normal = Vector3::zero();
+        for axis in 0..3 {
+            if direction[axis] == 0.0 {
+                if origin[axis] < bounds.min[axis] || origin[axis] > bounds.max[axis] {
+                    return VoxelTraversal::empty();
+                }
+                continue;
+            }
+
+            let inv = 1.0 / direction[axis];
+            let mut t0 = (bounds.min[axis] - origin[axis]) * inv;
+            let mut t1 = (bounds.max[axis] - origin[axis]) * inv;
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            if t0 > t_enter {
+                t_enter = t0;
+                normal = Vector3::zero();
+                normal[axis] = -direction[axis].signum();
+            }
+            t_end = t_end.min(t1);
+        }
+        if t_enter >= t_end {
+            return VoxelTraversal::empty();
+        }
+
+        let start = ray.point_at_parameter(t_enter);
+        let mut cell = self.cell_at(start);
+        for axis in 0..3 {
+            // A ray starting on a cell boundary and moving down the axis is in the cell below it.
+            if direction[axis] < 0.0 && start[axis] == self.origin[axis] + cell[axis] as f32 * self.cell_size {
+                cell[axis] -= 1;
+            }
+            cell[axis] = cell[axis].clamp(self.min[axis], self.max[axis] - 1);
+        }
+
+        let mut step = Vector3i::zero();
+        let mut t_next = Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut t_delta = t_next;
+        for axis in 0..3 {
+            let d = direction[axis];
+            if d == 0.0 {
+                continue;
+            }
+
+            step[axis] = if d > 0.0 { 1 } else { -1 };
+            let boundary = cell[axis] + if d > 0.0 { 1 } else { 0 };
+            let plane = self.origin[axis] + boundary as f32 * self.cell_size;
+            t_next[axis] = (plane - origin[axis]) / d;
+            t_delta[axis] = self.cell_size / d.abs();
+        }
+
+        VoxelTraversal {
+            grid: *self,
+            cell,
+            step,
+            t_next,
+            t_delta,
+            t: t_enter,
+            t_end,
+            normal,
+            done: false,
+        }
+    }
+}
+
+/// An iterator over the cells of a `VoxelGrid` crossed by a ray.
+#[derive(Debug, Clone)]
+pub struct VoxelTraversal {
+    grid: VoxelGrid,
+    cell: Vector3i,
+    step: Vector3i,
+    t_next: Vector3,
+    t_delta: Vector3,
+    t: f32,
+    t_end: f32,
+    normal: Vector3,
+    done: bool,
+}
+
+impl VoxelTraversal {
+    fn empty() -> VoxelTraversal {
+        VoxelTraversal {
+            grid: VoxelGrid::new(Vector3::zero(), 1.0, Vector3i::zero(), Vector3i::zero()),
+            cell: Vector3i::zero(),
+            step: Vector3i::zero(),
+            t_next: Vector3::zero(),
+            t_delta: Vector3::zero(),
+            t: 0.0,
+            t_end: 0.0,
+            normal: Vector3::zero(),
+            done: true,
+        }
+    }
+}
+
+impl Iterator for VoxelTraversal {
+    type Item = VoxelHit;
+
+    fn next(&mut self) -> Option<VoxelHit> {
+        if self.done {
+            return None;
+        }
+
+        let axis = if self.t_next.x < self.t_next.y {
+            if self.t_next.x < self.t_next.z { 0 } else { 2 }
+        } else if self.t_next.y < self.t_next.z {
+            1
+        } else {
+            2
+        };
+
+        let hit = VoxelHit {
+            cell: self.cell,
+            t_enter: self.t,
+            t_exit: self.t_next[axis].min(self.t_end),
+            normal: self.normal,
+        };
+
+        self.t = self.t_next[axis];
+        self.t_next[axis] += self.t_delta[axis];
+        self.cell[axis] += self.step[axis];
+        self.normal = Vector3::zero();
+        self.normal[axis] = -self.step[axis] as f32;
+
+        let cell = self.cell[axis];
+        if self.t >= self.t_end || cell < self.grid.min[axis] || cell >= self.grid.max[axis] {
+            self.done = true;
+        }
+
+        Some(hit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use voxel::VoxelGrid;
+    use ray::Ray;
+    use vector3::Vector3;
+    use vector3i::Vector3i;
+
+    fn grid() -> VoxelGrid {
+        VoxelGrid::new(Vector3::zero(), 2.0, Vector3i::new(0, 0, 0), Vector3i::new(8, 8, 8))
+    }
+
+    #[test]
+    fn test_voxel_axis_aligned() {
+        let ray = Ray::new(Vector3::new(-3.0, 1.0, 5.0), Vector3::new(1.0, 0.0, 0.0), 0.0);
+        let hits: Vec<_> = grid().traverse(&ray, 100.0).collect();
+        assert_eq!(hits.len(), 8);
+        for (i, hit) in hits.iter().enumerate() {
+            assert_eq!(hit.cell, Vector3i::new(i as i32, 0, 2));
+            assert!((hit.t_enter - (3.0 + 2.0 * i as f32)).abs() < 1e-5);
+            assert!((hit.t_exit - hit.t_enter - 2.0).abs() < 1e-5);
+            assert_eq!(hit.normal.x, -1.0);
+        }
+
+        // Traversal stops at `t_max`.
+        let hits: Vec<_> = grid().traverse(&ray, 6.0).collect();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[1].t_exit, 6.0);
+    }
+
+    #[test]
+    fn test_voxel_diagonal() {
+        let ray = Ray::new(Vector3::new(1.3, 0.7, 15.1), Vector3::new(0.6, 0.5, -0.9).normalized(), 0.0);
+        let hits: Vec<_> = grid().traverse(&ray, 1000.0).collect();
+        assert!(hits.len() > 8);
+        assert_eq!(hits[0].cell, Vector3i::new(0, 0, 7));
+        assert_eq!(hits[0].t_enter, 0.0);
+        assert_eq!(hits[0].normal.length(), 0.0);
+
+        for pair in hits.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let d = b.cell - a.cell;
+            assert_eq!(d.x.abs() + d.y.abs() + d.z.abs(), 1);
+            assert!((a.t_exit - b.t_enter).abs() < 1e-5);
+            assert_eq!(b.normal.x, -d.x as f32);
+            assert_eq!(b.normal.y, -d.y as f32);
+            assert_eq!(b.normal.z, -d.z as f32);
+
+            // The ray is inside the cell between its entry and exit.
+            let mid = ray.point_at_parameter((b.t_enter + b.t_exit) * 0.5);
+            assert_eq!(grid().cell_at(mid), b.cell);
+        }
+    }
+
+    #[test]
+    fn test_voxel_miss_and_bounds() {
+        let miss = Ray::new(Vector3::new(-1.0, 20.0, 1.0), Vector3::new(1.0, 0.0, 0.0), 0.0);
+        assert_eq!(grid().traverse(&miss, 100.0).count(), 0);
+
+        let away = Ray::new(Vector3::new(-1.0, 1.0, 1.0), Vector3::new(-1.0, 0.0, 0.0), 0.0);
+        assert_eq!(grid().traverse(&away, 100.0).count(), 0);
+
+        let offset = VoxelGrid::new(Vector3::new(10.0, 0.0, 0.0), 1.0, Vector3i::new(-2, -2, -2), Vector3i::new(2, 2, 2));
+        let ray = Ray::new(Vector3::new(10.5, 10.0, 0.5), Vector3::new(0.0, -1.0, 0.0), 0.0);
+        let hits: Vec<_> = offset.traverse(&ray, 100.0).collect();
+        let cells: Vec<i32> = hits.iter().map(|h| h.cell.y).collect();
+        assert_eq!(cells, vec![1, 0, -1, -2]);
+        assert_eq!(hits[0].normal.y, 1.0);
+        assert!(hits.iter().all(|h| h.cell.x == 0 && h.cell.z == 0));
+
+        // A ray starting on an interior boundary enters the cell it moves into.
+        let unit = VoxelGrid::new(Vector3::zero(), 1.0, Vector3i::new(0, 0, 0), Vector3i::new(4, 4, 4));
+        let ray = Ray::new(Vector3::new(2.0, 0.5, 0.5), Vector3::new(-1.0, 0.0, 0.0), 0.0);
+        let hits: Vec<_> = unit.traverse(&ray, 100.0).collect();
+        let cells: Vec<i32> = hits.iter().map(|h| h.cell.x).collect();
+        assert_eq!(cells, vec![1, 0]);
+        assert_eq!((hits[0].t_enter, hits[0].t_exit), (0.0, 1.0));
+
+        let flat = VoxelGrid::new(Vector3::zero(), 1.0, Vector3i::new(0, 0, 0), Vector3i::new(4, 0, 4));
+        let ray = Ray::new(Vector3::new(-1.0, 0.0, 0.5), Vector3::new(1.0, 0.0, 0.0), 0.0);
+        assert_eq!(flat.traverse(&ray, 100.0).count(), 0);
+    }
+}